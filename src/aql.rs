@@ -0,0 +1,715 @@
+// Lexical analysis of AQL for syntax highlighting and bracket matching.
+//
+// The tokenizer is deliberately lenient: it never fails, so half-typed
+// queries (unterminated strings, open comments) still highlight sensibly.
+// Token positions are char offsets into the query text, with lines joined
+// by '\n'.
+
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+
+pub const KEYWORDS: &[&str] = &[
+    "AGGREGATE",
+    "ALL",
+    "ALL_SHORTEST_PATHS",
+    "AND",
+    "ANY",
+    "ASC",
+    "COLLECT",
+    "DESC",
+    "DISTINCT",
+    "FALSE",
+    "FILTER",
+    "FOR",
+    "GRAPH",
+    "IN",
+    "INBOUND",
+    "INSERT",
+    "INTO",
+    "K_PATHS",
+    "K_SHORTEST_PATHS",
+    "LET",
+    "LIKE",
+    "LIMIT",
+    "NONE",
+    "NOT",
+    "NULL",
+    "OR",
+    "OUTBOUND",
+    "REMOVE",
+    "REPLACE",
+    "RETURN",
+    "SHORTEST_PATH",
+    "SORT",
+    "TRUE",
+    "UPDATE",
+    "UPSERT",
+    "WINDOW",
+    "WITH",
+    // Context dependent keywords
+    "AT",
+    "COUNT",
+    "CURRENT",
+    "KEEP",
+    "LEAST",
+    "NEW",
+    "OLD",
+    "OPTIONS",
+    "PRUNE",
+    "SEARCH",
+    "TO",
+];
+
+// Built-in AQL functions offered by autocompletion
+#[rustfmt::skip]
+pub const FUNCTIONS: &[&str] = &[
+    // Type checks and casts
+    "IS_NULL", "IS_BOOL", "IS_NUMBER", "IS_STRING", "IS_ARRAY", "IS_OBJECT", "IS_DOCUMENT",
+    "IS_DATESTRING", "IS_IPV4", "IS_KEY", "TYPENAME", "TO_BOOL", "TO_NUMBER", "TO_STRING",
+    "TO_ARRAY", "TO_LIST",
+    // Strings
+    "CHAR_LENGTH", "CONCAT", "CONCAT_SEPARATOR", "CONTAINS", "COUNT_SUBSTRING", "CRC32",
+    "ENCODE_URI_COMPONENT", "FIND_FIRST", "FIND_LAST", "FNV64", "JSON_PARSE", "JSON_STRINGIFY",
+    "LEFT", "LEVENSHTEIN_DISTANCE", "LIKE", "LOWER", "LTRIM", "MD5", "NGRAM_POSITIONAL_SIMILARITY",
+    "NGRAM_SIMILARITY", "RANDOM_TOKEN", "REGEX_MATCHES", "REGEX_SPLIT", "REGEX_TEST",
+    "REGEX_REPLACE", "REVERSE", "RIGHT", "RTRIM", "SHA1", "SHA256", "SHA512", "SOUNDEX", "SPLIT",
+    "STARTS_WITH", "SUBSTITUTE", "SUBSTRING", "SUBSTRING_BYTES", "TOKENS", "TO_BASE64", "TO_HEX",
+    "TRIM", "UPPER", "UUID",
+    // Numbers
+    "ABS", "ACOS", "ASIN", "ATAN", "ATAN2", "AVERAGE", "AVG", "CEIL", "COS", "COSINE_SIMILARITY",
+    "DEGREES", "EXP", "EXP2", "FLOOR", "L1_DISTANCE", "L2_DISTANCE", "LOG", "LOG2", "LOG10", "MAX",
+    "MEDIAN", "MIN", "PERCENTILE", "PI", "POW", "PRODUCT", "RADIANS", "RAND", "RANGE", "ROUND",
+    "SIN", "SQRT", "STDDEV", "STDDEV_POPULATION", "STDDEV_SAMPLE", "SUM", "TAN", "VARIANCE",
+    "VARIANCE_POPULATION", "VARIANCE_SAMPLE",
+    // Arrays
+    "APPEND", "CONTAINS_ARRAY", "COUNT", "COUNT_DISTINCT", "COUNT_UNIQUE", "FIRST", "FLATTEN",
+    "INTERLEAVE", "INTERSECTION", "JACCARD", "LAST", "LENGTH", "MINUS", "NTH", "OUTERSECTION",
+    "POP", "POSITION", "PUSH", "REMOVE_NTH", "REMOVE_VALUE", "REMOVE_VALUES", "REPLACE_NTH",
+    "SHIFT", "SLICE", "SORTED", "SORTED_UNIQUE", "UNION", "UNION_DISTINCT", "UNIQUE", "UNSHIFT",
+    // Objects and documents
+    "ATTRIBUTES", "ENTRIES", "HAS", "IS_SAME_COLLECTION", "KEEP", "KEEP_RECURSIVE", "KEYS",
+    "MATCHES", "MERGE", "MERGE_RECURSIVE", "PARSE_IDENTIFIER", "PARSE_KEY", "PARSE_COLLECTION",
+    "TRANSLATE", "UNSET", "UNSET_RECURSIVE", "VALUE", "VALUES", "ZIP",
+    // Dates
+    "DATE_ADD", "DATE_COMPARE", "DATE_DAY", "DATE_DAYOFWEEK", "DATE_DAYOFYEAR", "DATE_DAYS_IN_MONTH",
+    "DATE_DIFF", "DATE_FORMAT", "DATE_HOUR", "DATE_ISO8601", "DATE_ISOWEEK", "DATE_ISOWEEKYEAR",
+    "DATE_LEAPYEAR", "DATE_LOCALTOUTC", "DATE_MILLISECOND", "DATE_MINUTE", "DATE_MONTH",
+    "DATE_NOW", "DATE_QUARTER", "DATE_ROUND", "DATE_SECOND", "DATE_SUBTRACT", "DATE_TIMESTAMP",
+    "DATE_TIMEZONE", "DATE_TIMEZONES", "DATE_TRUNC", "DATE_UTCTOLOCAL", "DATE_YEAR",
+    // Geo
+    "DISTANCE", "GEO_AREA", "GEO_CONTAINS", "GEO_DISTANCE", "GEO_EQUALS", "GEO_INTERSECTS",
+    "GEO_IN_RANGE", "GEO_LINESTRING", "GEO_MULTILINESTRING", "GEO_MULTIPOINT", "GEO_POINT",
+    "GEO_POLYGON", "GEO_MULTIPOLYGON", "IS_IN_POLYGON", "NEAR", "WITHIN", "WITHIN_RECTANGLE",
+    // ArangoSearch
+    "ANALYZER", "BM25", "BOOST", "EXISTS", "IN_RANGE", "LEVENSHTEIN_MATCH", "MIN_MATCH",
+    "NGRAM_MATCH", "PHRASE", "TFIDF",
+    // Documents, collections and miscellaneous
+    "ASSERT", "CALL", "CHECK_DOCUMENT", "COLLECTION_COUNT", "COLLECTIONS", "CURRENT_DATABASE",
+    "CURRENT_USER", "DECODE_REV", "DOCUMENT", "FAIL", "FULLTEXT", "HASH", "NOOPT", "NOT_NULL",
+    "FIRST_LIST", "FIRST_DOCUMENT", "PASSTHRU", "SCHEMA_GET", "SCHEMA_VALIDATE", "SHARD_ID",
+    "SLEEP", "V8", "VERSION", "WARN",
+];
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TokenKind {
+    Keyword,
+    Function,
+    Identifier,
+    String,
+    Number,
+    Comment,
+    BindParameter,
+    Operator,
+    Bracket(char),
+    Punctuation,
+    Whitespace,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub start: usize, // Char offset of the first char
+    pub end: usize,   // Char offset after the last char
+}
+
+pub fn is_keyword(word: &str) -> bool {
+    KEYWORDS.iter().any(|k| k.eq_ignore_ascii_case(word))
+}
+
+fn is_identifier_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '$'
+}
+
+pub fn tokenize(text: &str) -> Vec<Token> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let start = i;
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+
+        let kind = match c {
+            c if c.is_whitespace() => {
+                while i < chars.len() && chars[i].is_whitespace() {
+                    i += 1;
+                }
+                TokenKind::Whitespace
+            }
+            '/' if next == Some('/') => {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+                TokenKind::Comment
+            }
+            '/' if next == Some('*') => {
+                i += 2;
+                while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                    i += 1;
+                }
+                i = (i + 2).min(chars.len());
+                TokenKind::Comment
+            }
+            '"' | '\'' | '`' | '´' => {
+                i += 1;
+                while i < chars.len() && chars[i] != c {
+                    if chars[i] == '\\' {
+                        i += 1;
+                    }
+                    i += 1;
+                }
+                i = (i + 1).min(chars.len());
+                // Backticks and forward ticks quote names, not values
+                if c == '`' || c == '´' {
+                    TokenKind::Identifier
+                } else {
+                    TokenKind::String
+                }
+            }
+            '@' => {
+                i += 1;
+                if chars.get(i) == Some(&'@') {
+                    i += 1;
+                }
+                while i < chars.len() && is_identifier_char(chars[i]) {
+                    i += 1;
+                }
+                TokenKind::BindParameter
+            }
+            c if c.is_ascii_digit() || (c == '.' && next.is_some_and(|n| n.is_ascii_digit())) => {
+                i += 1;
+                while i < chars.len() {
+                    match chars[i] {
+                        d if d.is_ascii_alphanumeric() => i += 1,
+                        // A range like 1..10 ends the number before the dots
+                        '.' if chars.get(i + 1) != Some(&'.') => i += 1,
+                        '+' | '-' if matches!(chars[i - 1], 'e' | 'E') => i += 1,
+                        _ => break,
+                    }
+                }
+                TokenKind::Number
+            }
+            c if is_identifier_char(c) => {
+                while i < chars.len()
+                    && (is_identifier_char(chars[i])
+                        || (chars[i] == ':'
+                            && chars.get(i + 1) == Some(&':')
+                            && chars.get(i + 2).is_some_and(|&n| is_identifier_char(n))))
+                {
+                    i += if chars[i] == ':' { 2 } else { 1 };
+                }
+                let word: String = chars[start..i].iter().collect();
+                let mut lookahead = i;
+                while lookahead < chars.len() && chars[lookahead].is_whitespace() {
+                    lookahead += 1;
+                }
+                if chars.get(lookahead) == Some(&'(') && !is_keyword(&word) {
+                    TokenKind::Function
+                } else if is_keyword(&word) {
+                    TokenKind::Keyword
+                } else {
+                    TokenKind::Identifier
+                }
+            }
+            '(' | ')' | '[' | ']' | '{' | '}' => {
+                i += 1;
+                TokenKind::Bracket(c)
+            }
+            ',' | ';' | ':' => {
+                i += 1;
+                TokenKind::Punctuation
+            }
+            _ => {
+                i += 1;
+                while i < chars.len() && matches!(chars[i], '=' | '&' | '|' | '.' | '~' | '>') {
+                    i += 1;
+                }
+                TokenKind::Operator
+            }
+        };
+
+        tokens.push(Token {
+            kind,
+            start,
+            end: i,
+        });
+    }
+
+    tokens
+}
+
+fn closing_bracket(open: char) -> Option<char> {
+    match open {
+        '(' => Some(')'),
+        '[' => Some(']'),
+        '{' => Some('}'),
+        _ => None,
+    }
+}
+
+// If a bracket starts at `pos`, return the position of its partner (None if unmatched)
+pub fn matching_bracket(tokens: &[Token], pos: usize) -> Option<Option<usize>> {
+    tokens
+        .iter()
+        .find(|t| t.start == pos && matches!(t.kind, TokenKind::Bracket(_)))?;
+
+    let mut stack: Vec<(char, usize)> = Vec::new();
+    for token in tokens {
+        let TokenKind::Bracket(c) = token.kind else {
+            continue;
+        };
+        if closing_bracket(c).is_some() {
+            stack.push((c, token.start));
+            continue;
+        }
+        match stack.last() {
+            Some(&(open, open_pos)) if closing_bracket(open) == Some(c) => {
+                stack.pop();
+                if open_pos == pos {
+                    return Some(Some(token.start));
+                }
+                if token.start == pos {
+                    return Some(Some(open_pos));
+                }
+            }
+            _ if token.start == pos => return Some(None),
+            _ => {}
+        }
+    }
+
+    // An opening bracket that was never closed
+    Some(None)
+}
+
+pub fn token_style(kind: TokenKind) -> Style {
+    match kind {
+        TokenKind::Keyword => Style::default()
+            .fg(Color::Magenta)
+            .add_modifier(Modifier::BOLD),
+        TokenKind::Function => Style::default().fg(Color::Blue),
+        TokenKind::String => Style::default().fg(Color::Green),
+        TokenKind::Number => Style::default().fg(Color::LightYellow),
+        TokenKind::Comment => Style::default()
+            .fg(Color::DarkGray)
+            .add_modifier(Modifier::ITALIC),
+        TokenKind::BindParameter => Style::default().fg(Color::Cyan),
+        TokenKind::Operator | TokenKind::Punctuation => Style::default().fg(Color::Gray),
+        TokenKind::Bracket(_) => Style::default().fg(Color::White),
+        TokenKind::Identifier | TokenKind::Whitespace => Style::default(),
+    }
+}
+
+// Highlight a query, patching the style of single chars with `overlays`.
+// An overlay at a line end or at the end of the text is shown as a blank cell,
+// which is how the editor draws a cursor there.
+pub fn highlight_with(text: &str, overlays: &[(usize, Style)]) -> Vec<Line<'static>> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut styles = vec![Style::default(); chars.len() + 1];
+    for token in tokenize(text) {
+        for style in &mut styles[token.start..token.end] {
+            *style = token_style(token.kind);
+        }
+    }
+    for &(pos, overlay) in overlays {
+        if let Some(style) = styles.get_mut(pos) {
+            *style = style.patch(overlay);
+        }
+    }
+
+    let mut lines = Vec::new();
+    let mut spans: Vec<Span<'static>> = Vec::new();
+    let mut current = String::new();
+    let mut current_style = Style::default();
+
+    for (pos, style) in styles.iter().enumerate() {
+        let c = chars.get(pos).copied();
+        let is_line_end = c.is_none_or(|c| c == '\n');
+        let c = if is_line_end {
+            // Only visible when an overlay marks this position
+            if overlays.iter().any(|&(p, _)| p == pos) {
+                ' '
+            } else {
+                spans.push(Span::styled(std::mem::take(&mut current), current_style));
+                lines.push(Line::from(std::mem::take(&mut spans)));
+                continue;
+            }
+        } else {
+            c.unwrap_or(' ')
+        };
+
+        if *style != current_style && !current.is_empty() {
+            spans.push(Span::styled(std::mem::take(&mut current), current_style));
+        }
+        current_style = *style;
+        current.push(c);
+
+        if is_line_end {
+            spans.push(Span::styled(std::mem::take(&mut current), current_style));
+            lines.push(Line::from(std::mem::take(&mut spans)));
+        }
+    }
+
+    lines
+}
+
+pub fn highlight(text: &str) -> Vec<Line<'static>> {
+    highlight_with(text, &[])
+}
+
+// Line and column (both 1-based) of a parse error, from a server message
+// like "syntax error, unexpected identifier near 'x' at position 2:7"
+pub fn error_position(message: &str) -> Option<(usize, usize)> {
+    let rest = &message[message.find("at position ")? + "at position ".len()..];
+    let (line, rest) = rest.split_once(':')?;
+    let column: String = rest.chars().take_while(|c| c.is_ascii_digit()).collect();
+    Some((line.parse().ok()?, column.parse().ok()?))
+}
+
+// Char range of the token at a 1-based line and column, for marking errors
+pub fn token_range_at(text: &str, line: usize, column: usize) -> Option<(usize, usize)> {
+    let line_start: usize = text
+        .split('\n')
+        .take(line.checked_sub(1)?)
+        .map(|l| l.chars().count() + 1)
+        .sum();
+    let line_length = text.split('\n').nth(line - 1)?.chars().count();
+    let pos = line_start + column.saturating_sub(1).min(line_length);
+    let range = tokenize(text)
+        .into_iter()
+        .find(|t| t.start <= pos && pos < t.end && t.kind != TokenKind::Whitespace)
+        .map_or((pos, pos + 1), |t| (t.start, t.end));
+    Some(range)
+}
+
+// What kind of name is expected at the cursor
+#[derive(Clone, Debug, PartialEq)]
+pub enum CompletionContext {
+    Attribute(String), // Attribute of the given variable, after `var.`
+    Collection,        // After IN, INTO or WITH
+    Graph,             // After GRAPH
+    General,           // Keywords, functions and variables
+}
+
+fn significant(tokens: &[Token]) -> impl DoubleEndedIterator<Item = &Token> {
+    tokens
+        .iter()
+        .filter(|t| !matches!(t.kind, TokenKind::Whitespace | TokenKind::Comment))
+}
+
+fn token_text(chars: &[char], token: &Token) -> String {
+    chars[token.start..token.end].iter().collect()
+}
+
+// Determine the completion context and the partial word before the cursor.
+// Returns None inside strings and comments.
+pub fn completion_context(text_before_cursor: &str) -> Option<(CompletionContext, String)> {
+    let chars: Vec<char> = text_before_cursor.chars().collect();
+    let mut tokens = tokenize(text_before_cursor);
+
+    let prefix = match tokens.last() {
+        Some(t) if matches!(t.kind, TokenKind::String | TokenKind::Comment) => return None,
+        Some(t)
+            if t.end == chars.len()
+                && matches!(
+                    t.kind,
+                    TokenKind::Identifier | TokenKind::Keyword | TokenKind::Function
+                )
+                && chars[t.start] != '`' =>
+        {
+            let prefix = token_text(&chars, t);
+            tokens.pop();
+            prefix
+        }
+        // A bind parameter is typed by the user, nothing to complete
+        Some(t) if t.kind == TokenKind::BindParameter && t.end == chars.len() => return None,
+        _ => String::new(),
+    };
+
+    let mut previous = significant(&tokens).rev();
+    let context = match previous.next() {
+        Some(t) if t.kind == TokenKind::Operator && token_text(&chars, t) == "." => {
+            match previous.next() {
+                Some(v) if v.kind == TokenKind::Identifier => {
+                    CompletionContext::Attribute(token_text(&chars, v))
+                }
+                _ => return None,
+            }
+        }
+        Some(t) if t.kind == TokenKind::Keyword => {
+            match token_text(&chars, t).to_ascii_uppercase().as_str() {
+                "IN" | "INTO" | "WITH" => CompletionContext::Collection,
+                "GRAPH" => CompletionContext::Graph,
+                _ => CompletionContext::General,
+            }
+        }
+        _ => CompletionContext::General,
+    };
+
+    Some((context, prefix))
+}
+
+// Find the collection a variable iterates over, from `FOR var IN collection`
+pub fn variable_collection(text: &str, variable: &str) -> Option<String> {
+    let chars: Vec<char> = text.chars().collect();
+    let tokens = tokenize(text);
+    let words: Vec<(TokenKind, String)> = significant(&tokens)
+        .map(|t| (t.kind, token_text(&chars, t)))
+        .collect();
+
+    words.windows(4).find_map(|w| match w {
+        [
+            (TokenKind::Keyword, for_),
+            (TokenKind::Identifier, var),
+            (TokenKind::Keyword, in_),
+            (TokenKind::Identifier, collection),
+        ] if for_.eq_ignore_ascii_case("FOR")
+            && in_.eq_ignore_ascii_case("IN")
+            && var == variable =>
+        {
+            Some(
+                collection
+                    .trim_matches(|c| c == '`' || c == '´')
+                    .to_string(),
+            )
+        }
+        _ => None,
+    })
+}
+
+// Variables introduced by FOR, LET and COLLECT
+pub fn declared_variables(text: &str) -> Vec<String> {
+    let chars: Vec<char> = text.chars().collect();
+    let tokens = tokenize(text);
+    let words: Vec<(TokenKind, String)> = significant(&tokens)
+        .map(|t| (t.kind, token_text(&chars, t)))
+        .collect();
+
+    let mut variables = Vec::new();
+    for (i, (kind, word)) in words.iter().enumerate() {
+        if *kind != TokenKind::Keyword {
+            continue;
+        }
+        if !matches!(
+            word.to_ascii_uppercase().as_str(),
+            "FOR" | "LET" | "COLLECT"
+        ) {
+            continue;
+        }
+        // FOR v, e, p IN ... declares several variables
+        let mut j = i + 1;
+        while let Some((TokenKind::Identifier, name)) = words.get(j) {
+            if !variables.contains(name) {
+                variables.push(name.clone());
+            }
+            match words.get(j + 1) {
+                Some((TokenKind::Punctuation, comma)) if comma == "," => j += 2,
+                _ => break,
+            }
+        }
+    }
+    variables
+}
+
+// Bind parameters referenced by a query, in order of first use, as the keys
+// they are bound under: `@name` gives `name`, `@@coll` gives `@coll`
+pub fn bind_parameters(text: &str) -> Vec<String> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut names = Vec::new();
+    for token in tokenize(text) {
+        if token.kind != TokenKind::BindParameter {
+            continue;
+        }
+        let name = token_text(&chars, &token)[1..].to_string();
+        if !name.trim_start_matches('@').is_empty() && !names.contains(&name) {
+            names.push(name);
+        }
+    }
+    names
+}
+
+// Whether a query writes data (INSERT, UPDATE, REPLACE, REMOVE or UPSERT), so
+// running it again is not harmless
+pub fn is_modification(text: &str) -> bool {
+    let chars: Vec<char> = text.chars().collect();
+    let tokens = tokenize(text);
+    let words: Vec<&Token> = significant(&tokens).collect();
+    words.iter().enumerate().any(|(i, token)| {
+        // `doc.update` is an attribute, not an operation
+        let after_dot = i > 0 && token_text(&chars, words[i - 1]) == ".";
+        token.kind == TokenKind::Keyword
+            && !after_dot
+            && matches!(
+                token_text(&chars, token).to_ascii_uppercase().as_str(),
+                "INSERT" | "UPDATE" | "REPLACE" | "REMOVE" | "UPSERT"
+            )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Kind and text of the significant tokens
+    fn words(text: &str) -> Vec<(TokenKind, String)> {
+        let chars: Vec<char> = text.chars().collect();
+        let tokens = tokenize(text);
+        significant(&tokens)
+            .map(|t| (t.kind, token_text(&chars, t)))
+            .collect()
+    }
+
+    #[test]
+    fn tokens() {
+        use TokenKind::*;
+        assert_eq!(
+            words("FOR d IN `my coll` FILTER LENGTH(d.tags) >= 1e-3 && d.x == 'it\\'s' RETURN @@c"),
+            [
+                (Keyword, "FOR".to_string()),
+                (Identifier, "d".to_string()),
+                (Keyword, "IN".to_string()),
+                (Identifier, "`my coll`".to_string()),
+                (Keyword, "FILTER".to_string()),
+                (Function, "LENGTH".to_string()),
+                (Bracket('('), "(".to_string()),
+                (Identifier, "d".to_string()),
+                (Operator, ".".to_string()),
+                (Identifier, "tags".to_string()),
+                (Bracket(')'), ")".to_string()),
+                (Operator, ">=".to_string()),
+                (Number, "1e-3".to_string()),
+                (Operator, "&&".to_string()),
+                (Identifier, "d".to_string()),
+                (Operator, ".".to_string()),
+                (Identifier, "x".to_string()),
+                (Operator, "==".to_string()),
+                (String, "'it\\'s'".to_string()),
+                (Keyword, "RETURN".to_string()),
+                (BindParameter, "@@c".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn ranges_namespaces_and_comments() {
+        use TokenKind::*;
+        assert_eq!(
+            words("FOR i IN 1..10 RETURN MY::fn(i) // done"),
+            [
+                (Keyword, "FOR".to_string()),
+                (Identifier, "i".to_string()),
+                (Keyword, "IN".to_string()),
+                (Number, "1".to_string()),
+                (Operator, "..".to_string()),
+                (Number, "10".to_string()),
+                (Keyword, "RETURN".to_string()),
+                (Function, "MY::fn".to_string()),
+                (Bracket('('), "(".to_string()),
+                (Identifier, "i".to_string()),
+                (Bracket(')'), ")".to_string()),
+            ]
+        );
+        let tokens = tokenize("RETURN /* a\nb */ 1");
+        assert_eq!(tokens[2].kind, Comment);
+        assert_eq!((tokens[2].start, tokens[2].end), (7, 16));
+        // Unterminated strings and comments run to the end of the text
+        assert_eq!(tokenize("'abc").last().unwrap().end, 4);
+        assert_eq!(tokenize("/* abc").last().unwrap().kind, Comment);
+    }
+
+    #[test]
+    fn brackets() {
+        let tokens = tokenize("[(1), {a: 2}]");
+        assert_eq!(matching_bracket(&tokens, 0), Some(Some(12)));
+        assert_eq!(matching_bracket(&tokens, 3), Some(Some(1)));
+        assert_eq!(matching_bracket(&tokens, 2), None);
+        assert_eq!(matching_bracket(&tokenize("(]"), 0), Some(None));
+    }
+
+    #[test]
+    fn bind_parameter_names() {
+        assert_eq!(
+            bind_parameters(
+                "FOR d IN @@coll FILTER d.a == @a || d.b == @a2 && d.c == @a LIMIT @ RETURN '@x'"
+            ),
+            ["@coll", "a", "a2"]
+        );
+        assert!(bind_parameters("RETURN 1 // @a").is_empty());
+    }
+
+    #[test]
+    fn error_positions() {
+        let message =
+            "AQL: syntax error, unexpected identifier near 'x' at position 2:7 (while parsing)";
+        assert_eq!(error_position(message), Some((2, 7)));
+        assert_eq!(error_position("collection or view not found: c"), None);
+        assert_eq!(error_position("at position x:1"), None);
+
+        let query = "FOR d IN c\nRETURN dx.a";
+        assert_eq!(token_range_at(query, 2, 8), Some((18, 20)));
+        // Past the end of the line: the position after its last char
+        assert_eq!(token_range_at(query, 1, 40), Some((10, 11)));
+        assert_eq!(token_range_at(query, 3, 1), None);
+        assert_eq!(token_range_at(query, 0, 1), None);
+    }
+
+    #[test]
+    fn completion_contexts() {
+        assert_eq!(
+            completion_context("FOR d IN us"),
+            Some((CompletionContext::Collection, "us".to_string()))
+        );
+        assert_eq!(
+            completion_context("FOR d IN users FILTER d.na"),
+            Some((
+                CompletionContext::Attribute("d".to_string()),
+                "na".to_string()
+            ))
+        );
+        assert_eq!(
+            completion_context("FOR v IN 1 OUTBOUND 'a' GRAPH "),
+            Some((CompletionContext::Graph, String::new()))
+        );
+        assert_eq!(completion_context("RETURN 'd."), None);
+        assert_eq!(completion_context("RETURN @pa"), None);
+    }
+
+    #[test]
+    fn variables() {
+        let query = "FOR v, e IN 1 ANY 'a' edges LET x = 1 COLLECT g = v.g RETURN g";
+        assert_eq!(declared_variables(query), ["v", "e", "x", "g"]);
+        assert_eq!(
+            variable_collection("FOR u IN `users` FOR o IN orders RETURN o", "u"),
+            Some("users".to_string())
+        );
+        assert_eq!(variable_collection("LET u = 1 RETURN u", "u"), None);
+    }
+
+    #[test]
+    fn modifications() {
+        assert!(is_modification("FOR d IN c UPDATE d WITH { a: 1 } IN c"));
+        assert!(is_modification("insert { a: 1 } into c"));
+        assert!(!is_modification("FOR d IN c RETURN d.update"));
+        assert!(!is_modification("RETURN 'REMOVE' // INSERT"));
+    }
+}
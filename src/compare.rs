@@ -0,0 +1,196 @@
+// Comparison of two sets of query results, e.g. before and after a data
+// migration or a query rewrite.
+//
+// Results are matched by an attribute, normally `_key`; nested attributes are
+// given as a dotted path (`address.city`). Keys are the attribute values as
+// JSON, so the number 1 and the string "1" do not match. Results without the
+// attribute are matched by their whole value, so they show up as removed and
+// added when they differ. If several results share a key, the n-th of each set
+// are matched with each other.
+
+use serde_json::Value;
+use std::collections::HashMap;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+impl ChangeKind {
+    pub fn marker(self) -> &'static str {
+        match self {
+            ChangeKind::Added => "+",
+            ChangeKind::Removed => "-",
+            ChangeKind::Changed => "~",
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ResultChange {
+    pub key: String,
+    pub kind: ChangeKind,
+    pub old: Option<Value>,
+    pub new: Option<Value>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct ResultDiff {
+    pub changes: Vec<ResultChange>, // Changed and added in new order, then removed
+    pub unchanged: usize,
+}
+
+impl ResultDiff {
+    pub fn count(&self, kind: ChangeKind) -> usize {
+        self.changes.iter().filter(|c| c.kind == kind).count()
+    }
+}
+
+// The key a result is matched by, as JSON text so values of different types differ
+fn match_key(result: &Value, attribute: &str) -> String {
+    let mut value = result;
+    for part in attribute.split('.') {
+        match value.get(part) {
+            Some(child) => value = child,
+            None => return result.to_string(),
+        }
+    }
+    value.to_string()
+}
+
+// Keys of all results, with a ` #n` suffix for the n-th repetition of a key
+fn keyed<'a>(results: &'a [Value], attribute: &str) -> Vec<(String, &'a Value)> {
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    results
+        .iter()
+        .map(|result| {
+            let key = match_key(result, attribute);
+            let count = seen.entry(key.clone()).or_default();
+            *count += 1;
+            let key = if *count > 1 {
+                format!("{} #{}", key, count)
+            } else {
+                key
+            };
+            (key, result)
+        })
+        .collect()
+}
+
+pub fn compare(old: &[Value], new: &[Value], attribute: &str) -> ResultDiff {
+    let old = keyed(old, attribute);
+    let new = keyed(new, attribute);
+    let old_by_key: HashMap<&str, &Value> = old.iter().map(|(k, v)| (k.as_str(), *v)).collect();
+    let new_by_key: HashMap<&str, &Value> = new.iter().map(|(k, v)| (k.as_str(), *v)).collect();
+
+    let mut diff = ResultDiff::default();
+    for (key, value) in &new {
+        match old_by_key.get(key.as_str()) {
+            Some(previous) if previous == value => diff.unchanged += 1,
+            Some(previous) => diff.changes.push(ResultChange {
+                key: key.clone(),
+                kind: ChangeKind::Changed,
+                old: Some((*previous).clone()),
+                new: Some((*value).clone()),
+            }),
+            None => diff.changes.push(ResultChange {
+                key: key.clone(),
+                kind: ChangeKind::Added,
+                old: None,
+                new: Some((*value).clone()),
+            }),
+        }
+    }
+    for (key, value) in &old {
+        if !new_by_key.contains_key(key.as_str()) {
+            diff.changes.push(ResultChange {
+                key: key.clone(),
+                kind: ChangeKind::Removed,
+                old: Some((*value).clone()),
+                new: None,
+            });
+        }
+    }
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn summary(diff: &ResultDiff) -> Vec<String> {
+        diff.changes
+            .iter()
+            .map(|c| format!("{}{}", c.kind.marker(), c.key))
+            .collect()
+    }
+
+    #[test]
+    fn changes_by_key() {
+        let old = [
+            json!({ "_key": "a", "v": 1 }),
+            json!({ "_key": "b", "v": 1 }),
+            json!({ "_key": "c", "v": 1 }),
+        ];
+        let new = [
+            json!({ "_key": "c", "v": 2 }),
+            json!({ "_key": "a", "v": 1 }),
+            json!({ "_key": "d", "v": 1 }),
+        ];
+        let diff = compare(&old, &new, "_key");
+        assert_eq!(summary(&diff), ["~\"c\"", "+\"d\"", "-\"b\""]);
+        assert_eq!(diff.unchanged, 1);
+        assert_eq!(diff.count(ChangeKind::Changed), 1);
+        assert_eq!(diff.changes[0].old, Some(json!({ "_key": "c", "v": 1 })));
+        assert_eq!(diff.changes[0].new, Some(json!({ "_key": "c", "v": 2 })));
+    }
+
+    #[test]
+    fn duplicate_keys_match_in_order() {
+        let old = [json!({ "k": 1, "v": "x" }), json!({ "k": 1, "v": "y" })];
+        let new = [
+            json!({ "k": 1, "v": "x" }),
+            json!({ "k": 1, "v": "z" }),
+            json!({ "k": 1, "v": "w" }),
+        ];
+        let diff = compare(&old, &new, "k");
+        assert_eq!(summary(&diff), ["~1 #2", "+1 #3"]);
+        assert_eq!(diff.unchanged, 1);
+    }
+
+    #[test]
+    fn nested_attributes() {
+        let old = [
+            json!({ "address": { "city": "Köln" }, "n": 1 }),
+            json!({ "address": { "city": "Bonn" }, "n": 1 }),
+        ];
+        let new = [
+            json!({ "address": { "city": "Bonn" }, "n": 2 }),
+            json!({ "address": { "city": "Köln" }, "n": 1 }),
+        ];
+        let diff = compare(&old, &new, "address.city");
+        assert_eq!(summary(&diff), ["~\"Bonn\""]);
+        assert_eq!(diff.unchanged, 1);
+    }
+
+    #[test]
+    fn results_without_the_attribute() {
+        let old = [json!(1), json!({ "x": 1 }), json!({ "_key": "a" })];
+        let new = [json!(1), json!({ "x": 2 }), json!({ "_key": "a" })];
+        let diff = compare(&old, &new, "_key");
+        assert_eq!(summary(&diff), ["+{\"x\":2}", "-{\"x\":1}"]);
+        assert_eq!(diff.unchanged, 2);
+    }
+
+    #[test]
+    fn keys_of_different_types_differ() {
+        let old = [json!({ "k": 1 }), json!({ "k": "2" })];
+        let new = [json!({ "k": "1" }), json!({ "k": "2" })];
+        let diff = compare(&old, &new, "k");
+        assert_eq!(summary(&diff), ["+\"1\"", "-1"]);
+        assert_eq!(diff.unchanged, 1);
+    }
+}
@@ -0,0 +1,352 @@
+// Export of query results and collection contents to files.
+//
+// Documents are written batch by batch as they arrive from a cursor, so an
+// export never needs all documents in memory. CSV rows are built from
+// flattened documents: nested objects become dotted columns (`address.city`),
+// arrays are written as JSON text. Without given columns, the CSV columns are
+// those of the first batch; attributes that only appear later are not written,
+// but collected so they can be reported.
+
+use anyhow::{Context, Result};
+use serde_json::Value;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ExportFormat {
+    Json,       // One JSON array on a single line
+    JsonLines,  // One document per line
+    Csv,        // Flattened documents with a header row
+    PrettyJson, // Indented JSON array
+}
+
+impl ExportFormat {
+    pub const ALL: [ExportFormat; 4] = [
+        ExportFormat::Json,
+        ExportFormat::JsonLines,
+        ExportFormat::Csv,
+        ExportFormat::PrettyJson,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ExportFormat::Json => "JSON",
+            ExportFormat::JsonLines => "JSON Lines",
+            ExportFormat::Csv => "CSV",
+            ExportFormat::PrettyJson => "pretty JSON",
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Json | ExportFormat::PrettyJson => "json",
+            ExportFormat::JsonLines => "jsonl",
+            ExportFormat::Csv => "csv",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        let position = Self::ALL.iter().position(|&f| f == self).unwrap_or(0);
+        Self::ALL[(position + 1) % Self::ALL.len()]
+    }
+}
+
+// Swap the extension of a path for the one of `format`, if it has a known one
+pub fn with_extension(path: &str, format: ExportFormat) -> String {
+    match path.rsplit_once('.') {
+        Some((stem, extension)) if ExportFormat::ALL.iter().any(|f| f.extension() == extension) => {
+            format!("{}.{}", stem, format.extension())
+        }
+        _ => path.to_string(),
+    }
+}
+
+// Flatten a document into dotted attribute paths and their scalar values.
+// Documents that are not objects become a single `value` column.
+pub fn flatten(document: &Value) -> Vec<(String, Value)> {
+    let mut fields = Vec::new();
+    match document {
+        Value::Object(_) => flatten_into(document, "", &mut fields),
+        other => fields.push(("value".to_string(), other.clone())),
+    }
+    fields
+}
+
+fn flatten_into(value: &Value, path: &str, fields: &mut Vec<(String, Value)>) {
+    match value {
+        Value::Object(object) if !object.is_empty() => {
+            for (name, value) in object {
+                let child = if path.is_empty() {
+                    name.clone()
+                } else {
+                    format!("{}.{}", path, name)
+                };
+                flatten_into(value, &child, fields);
+            }
+        }
+        other => fields.push((path.to_string(), other.clone())),
+    }
+}
+
+// Union of the flattened columns of some documents, in order of first appearance
+pub fn csv_columns(documents: &[Value]) -> Vec<String> {
+    let mut columns: Vec<String> = Vec::new();
+    for document in documents {
+        for (name, _) in flatten(document) {
+            if !columns.contains(&name) {
+                columns.push(name);
+            }
+        }
+    }
+    columns
+}
+
+fn csv_field(value: Option<&Value>) -> String {
+    let text = match value {
+        None | Some(Value::Null) => return String::new(),
+        Some(Value::String(s)) => s.clone(),
+        Some(other) => other.to_string(),
+    };
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text
+    }
+}
+
+// Expand a leading `~/` to the home directory
+pub fn expand_path(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+pub struct Exporter {
+    writer: BufWriter<File>,
+    path: PathBuf,
+    format: ExportFormat,
+    columns: Vec<String>, // CSV only; empty until known
+    dropped: Vec<String>, // CSV only; attributes of documents missing from the columns
+    count: usize,
+}
+
+impl Exporter {
+    // Create the export file; an empty column list for CSV means the columns
+    // are taken from the first batch
+    pub fn create(path: &str, format: ExportFormat, columns: Vec<String>) -> Result<Self> {
+        let path = expand_path(path);
+        let file =
+            File::create(&path).with_context(|| format!("Failed to create {}", path.display()))?;
+        Ok(Self {
+            writer: BufWriter::new(file),
+            path,
+            format,
+            columns,
+            dropped: Vec::new(),
+            count: 0,
+        })
+    }
+
+    pub fn write_batch(&mut self, documents: &[Value]) -> Result<()> {
+        self.write_documents(documents)
+            .with_context(|| format!("Failed to write {}", self.path.display()))
+    }
+
+    fn write_documents(&mut self, documents: &[Value]) -> Result<()> {
+        if self.format == ExportFormat::Csv && self.count == 0 && !documents.is_empty() {
+            if self.columns.is_empty() {
+                self.columns = csv_columns(documents);
+            }
+            let header: Vec<String> = self
+                .columns
+                .iter()
+                .map(|c| csv_field(Some(&Value::String(c.clone()))))
+                .collect();
+            writeln!(self.writer, "{}", header.join(","))?;
+        }
+
+        for document in documents {
+            match self.format {
+                ExportFormat::Json => {
+                    let separator = if self.count == 0 { "[" } else { "," };
+                    write!(self.writer, "{}{}", separator, document)?;
+                }
+                ExportFormat::PrettyJson => {
+                    let separator = if self.count == 0 { "[\n" } else { ",\n" };
+                    let pretty = serde_json::to_string_pretty(document)?;
+                    // Indent the document as an array element
+                    let indented: Vec<String> =
+                        pretty.lines().map(|line| format!("  {}", line)).collect();
+                    write!(self.writer, "{}{}", separator, indented.join("\n"))?;
+                }
+                ExportFormat::JsonLines => writeln!(self.writer, "{}", document)?,
+                ExportFormat::Csv => {
+                    let fields = flatten(document);
+                    for (name, _) in &fields {
+                        if !self.columns.contains(name) && !self.dropped.contains(name) {
+                            self.dropped.push(name.clone());
+                        }
+                    }
+                    let row: Vec<String> = self
+                        .columns
+                        .iter()
+                        .map(|column| {
+                            csv_field(
+                                fields
+                                    .iter()
+                                    .find(|(name, _)| name == column)
+                                    .map(|(_, value)| value),
+                            )
+                        })
+                        .collect();
+                    writeln!(self.writer, "{}", row.join(","))?;
+                }
+            }
+            self.count += 1;
+        }
+        Ok(())
+    }
+
+    // Attributes that were not written because the CSV columns lack them
+    pub fn dropped_columns(&self) -> &[String] {
+        &self.dropped
+    }
+
+    // Close JSON arrays and flush the file, returning the number of documents written
+    pub fn finish(mut self) -> Result<usize> {
+        let closing = match (self.format, self.count) {
+            (ExportFormat::Json, 0) | (ExportFormat::PrettyJson, 0) => "[]\n",
+            (ExportFormat::Json, _) => "]\n",
+            (ExportFormat::PrettyJson, _) => "\n]\n",
+            _ => "",
+        };
+        self.writer
+            .write_all(closing.as_bytes())
+            .and_then(|_| self.writer.flush())
+            .with_context(|| format!("Failed to write {}", self.path.display()))?;
+        Ok(self.count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    // Export the batches to a temporary file and return its content and the
+    // attributes left out of the CSV columns
+    fn export_dropping(
+        format: ExportFormat,
+        columns: Vec<String>,
+        batches: &[Vec<Value>],
+    ) -> (String, Vec<String>) {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        static FILES: AtomicUsize = AtomicUsize::new(0);
+        let path = std::env::temp_dir().join(format!(
+            "arangotui-export-{}-{}",
+            std::process::id(),
+            FILES.fetch_add(1, Ordering::Relaxed)
+        ));
+        let path = path.to_str().unwrap().to_string();
+        let mut exporter = Exporter::create(&path, format, columns).unwrap();
+        for batch in batches {
+            exporter.write_batch(batch).unwrap();
+        }
+        let dropped = exporter.dropped_columns().to_vec();
+        let count = exporter.finish().unwrap();
+        assert_eq!(count, batches.iter().map(Vec::len).sum::<usize>());
+        let content = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        (content, dropped)
+    }
+
+    fn export(format: ExportFormat, columns: Vec<String>, batches: &[Vec<Value>]) -> String {
+        let (content, dropped) = export_dropping(format, columns, batches);
+        assert!(dropped.is_empty(), "unexpectedly dropped {:?}", dropped);
+        content
+    }
+
+    #[test]
+    fn flattening() {
+        let document =
+            json!({ "a": { "b": 1, "c": { "d": null } }, "e": [1, { "f": 2 }], "g": {} });
+        assert_eq!(
+            flatten(&document),
+            [
+                ("a.b".to_string(), json!(1)),
+                ("a.c.d".to_string(), json!(null)),
+                ("e".to_string(), json!([1, { "f": 2 }])),
+                ("g".to_string(), json!({})),
+            ]
+        );
+        assert_eq!(flatten(&json!("x")), [("value".to_string(), json!("x"))]);
+        assert_eq!(
+            csv_columns(&[json!({ "b": 1 }), json!({ "a": { "x": 1 }, "b": 2 })]),
+            ["b", "a.x"]
+        );
+    }
+
+    #[test]
+    fn csv_quoting() {
+        let batches = [
+            vec![json!({ "name": "plain", "note": "a, b", "n": 1 })],
+            vec![json!({ "name": "say \"hi\"", "note": "two\nlines", "extra": true })],
+        ];
+        // The columns come from the first batch; later attributes are reported
+        assert_eq!(
+            export_dropping(ExportFormat::Csv, Vec::new(), &batches),
+            (
+                "name,note,n\nplain,\"a, b\",1\n\"say \"\"hi\"\"\",\"two\nlines\",\n".to_string(),
+                vec!["extra".to_string()]
+            )
+        );
+        // Given columns are used as they are
+        assert_eq!(
+            export_dropping(
+                ExportFormat::Csv,
+                vec!["note".to_string(), "x,y".to_string()],
+                &batches[..1]
+            ),
+            (
+                "note,\"x,y\"\n\"a, b\",\n".to_string(),
+                vec!["name".to_string(), "n".to_string()]
+            )
+        );
+    }
+
+    #[test]
+    fn json_arrays_are_closed() {
+        let batches = [vec![json!({ "a": 1 })], vec![], vec![json!([2])]];
+        assert_eq!(
+            export(ExportFormat::Json, Vec::new(), &batches),
+            "[{\"a\":1},[2]]\n"
+        );
+        assert_eq!(
+            export(ExportFormat::PrettyJson, Vec::new(), &batches),
+            "[\n  {\n    \"a\": 1\n  },\n  [\n    2\n  ]\n]\n"
+        );
+        assert_eq!(
+            export(ExportFormat::JsonLines, Vec::new(), &batches),
+            "{\"a\":1}\n[2]\n"
+        );
+
+        // Valid JSON even without any documents
+        assert_eq!(export(ExportFormat::Json, Vec::new(), &[]), "[]\n");
+        assert_eq!(export(ExportFormat::PrettyJson, Vec::new(), &[]), "[]\n");
+        assert_eq!(export(ExportFormat::Csv, Vec::new(), &[]), "");
+    }
+
+    #[test]
+    fn extensions() {
+        assert_eq!(with_extension("out.json", ExportFormat::Csv), "out.csv");
+        assert_eq!(
+            with_extension("out.csv", ExportFormat::JsonLines),
+            "out.jsonl"
+        );
+        assert_eq!(with_extension("out.txt", ExportFormat::Csv), "out.txt");
+        assert_eq!(with_extension("out", ExportFormat::Csv), "out");
+        assert_eq!(ExportFormat::PrettyJson.next(), ExportFormat::Json);
+    }
+}
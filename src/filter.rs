@@ -0,0 +1,451 @@
+// Translation of simple document filter expressions into parameterised AQL.
+//
+// Supported syntax:
+//   status == "active" && age > 30
+//   (role == "admin" || role == "owner") && !deleted == true
+//   address.city IN ["Cologne", "Berlin"]
+//   name LIKE "A%"
+//
+// Attribute paths and literal values are never spliced into the query text,
+// they are always passed as bind variables.
+
+use anyhow::{Result, bail};
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Str(String),
+    Number(serde_json::Number),
+    Op(&'static str),
+    And,
+    Or,
+    Not,
+    LParen,
+    RParen,
+    LBracket,
+    RBracket,
+    Comma,
+    Dot,
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            c if c.is_whitespace() => i += 1,
+            '(' => {
+                tokens.push(Token::LParen);
+                i += 1;
+            }
+            ')' => {
+                tokens.push(Token::RParen);
+                i += 1;
+            }
+            '[' => {
+                tokens.push(Token::LBracket);
+                i += 1;
+            }
+            ']' => {
+                tokens.push(Token::RBracket);
+                i += 1;
+            }
+            ',' => {
+                tokens.push(Token::Comma);
+                i += 1;
+            }
+            '.' if !chars.get(i + 1).is_some_and(|n| n.is_ascii_digit()) => {
+                tokens.push(Token::Dot);
+                i += 1;
+            }
+            '&' if chars.get(i + 1) == Some(&'&') => {
+                tokens.push(Token::And);
+                i += 2;
+            }
+            '|' if chars.get(i + 1) == Some(&'|') => {
+                tokens.push(Token::Or);
+                i += 2;
+            }
+            '=' | '!' | '<' | '>' => {
+                let next = chars.get(i + 1).copied();
+                let (op, len) = match (c, next) {
+                    ('=', Some('=')) => ("==", 2),
+                    ('=', Some('~')) => ("=~", 2),
+                    ('!', Some('=')) => ("!=", 2),
+                    ('!', Some('~')) => ("!~", 2),
+                    ('<', Some('=')) => ("<=", 2),
+                    ('>', Some('=')) => (">=", 2),
+                    ('<', _) => ("<", 1),
+                    ('>', _) => (">", 1),
+                    ('!', _) => {
+                        tokens.push(Token::Not);
+                        i += 1;
+                        continue;
+                    }
+                    _ => bail!("Unexpected '{}' at position {}", c, i + 1),
+                };
+                tokens.push(Token::Op(op));
+                i += len;
+            }
+            '"' | '\'' => {
+                let quote = c;
+                let mut value = String::new();
+                i += 1;
+                loop {
+                    match chars.get(i) {
+                        None => bail!("Unterminated string literal"),
+                        Some('\\') => {
+                            match chars.get(i + 1) {
+                                Some('n') => value.push('\n'),
+                                Some('t') => value.push('\t'),
+                                Some(&other) => value.push(other),
+                                None => bail!("Unterminated string literal"),
+                            }
+                            i += 2;
+                        }
+                        Some(&ch) if ch == quote => {
+                            i += 1;
+                            break;
+                        }
+                        Some(&ch) => {
+                            value.push(ch);
+                            i += 1;
+                        }
+                    }
+                }
+                tokens.push(Token::Str(value));
+            }
+            '`' => {
+                // Quoted attribute name, e.g. `first name`
+                let start = i + 1;
+                let Some(len) = chars[start..].iter().position(|&ch| ch == '`') else {
+                    bail!("Unterminated quoted attribute name");
+                };
+                tokens.push(Token::Ident(chars[start..start + len].iter().collect()));
+                i = start + len + 1;
+            }
+            c if c.is_ascii_digit() || c == '-' || c == '.' => {
+                let start = i;
+                i += 1;
+                while i < chars.len()
+                    && (chars[i].is_ascii_digit()
+                        || matches!(chars[i], '.' | 'e' | 'E')
+                        // Sign of an exponent, e.g. 1e-5
+                        || (matches!(chars[i], '+' | '-') && matches!(chars[i - 1], 'e' | 'E')))
+                {
+                    i += 1;
+                }
+                let text: String = chars[start..i].iter().collect();
+                match serde_json::from_str::<serde_json::Number>(&text) {
+                    Ok(number) => tokens.push(Token::Number(number)),
+                    Err(_) => bail!("Invalid number '{}' at position {}", text, start + 1),
+                }
+            }
+            c if c.is_alphabetic() || c == '_' || c == '$' => {
+                let start = i;
+                while i < chars.len()
+                    && (chars[i].is_alphanumeric() || chars[i] == '_' || chars[i] == '$')
+                {
+                    i += 1;
+                }
+                let word: String = chars[start..i].iter().collect();
+                tokens.push(match word.to_ascii_uppercase().as_str() {
+                    "AND" => Token::And,
+                    "OR" => Token::Or,
+                    "NOT" => Token::Not,
+                    "IN" => Token::Op("IN"),
+                    "LIKE" => Token::Op("LIKE"),
+                    _ => Token::Ident(word),
+                });
+            }
+            _ => bail!("Unexpected '{}' at position {}", c, i + 1),
+        }
+    }
+
+    Ok(tokens)
+}
+
+// A filter condition ready to be placed after `FILTER`, with its bind variables
+#[derive(Debug, Clone, PartialEq)]
+pub struct AqlFilter {
+    pub condition: String,
+    pub bind_vars: serde_json::Map<String, serde_json::Value>,
+}
+
+struct Parser<'a> {
+    tokens: Vec<Token>,
+    pos: usize,
+    variable: &'a str,
+    bind_vars: serde_json::Map<String, serde_json::Value>,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn bind(&mut self, value: serde_json::Value) -> String {
+        let name = format!("filter{}", self.bind_vars.len());
+        self.bind_vars.insert(name.clone(), value);
+        name
+    }
+
+    fn parse_or(&mut self) -> Result<String> {
+        let mut left = self.parse_and()?;
+        while self.peek() == Some(&Token::Or) {
+            self.pos += 1;
+            let right = self.parse_and()?;
+            left = format!("{} || {}", left, right);
+        }
+        Ok(left)
+    }
+
+    fn parse_and(&mut self) -> Result<String> {
+        let mut left = self.parse_unary()?;
+        while self.peek() == Some(&Token::And) {
+            self.pos += 1;
+            let right = self.parse_unary()?;
+            left = format!("{} && {}", left, right);
+        }
+        Ok(left)
+    }
+
+    fn parse_unary(&mut self) -> Result<String> {
+        match self.peek() {
+            Some(Token::Not) => {
+                self.pos += 1;
+                Ok(format!("!({})", self.parse_unary()?))
+            }
+            Some(Token::LParen) => {
+                self.pos += 1;
+                let inner = self.parse_or()?;
+                match self.next() {
+                    Some(Token::RParen) => Ok(format!("({})", inner)),
+                    _ => bail!("Missing closing parenthesis"),
+                }
+            }
+            _ => self.parse_comparison(),
+        }
+    }
+
+    fn parse_comparison(&mut self) -> Result<String> {
+        let path = self.parse_path()?;
+        let attribute = if path.len() == 1 {
+            serde_json::Value::String(path[0].clone())
+        } else {
+            serde_json::Value::from(path.clone())
+        };
+        let attribute_var = self.bind(attribute);
+
+        let op = match self.next() {
+            Some(Token::Op(op)) => op,
+            _ => bail!("Expected a comparison operator after '{}'", path.join(".")),
+        };
+        let value = self.parse_value()?;
+        if op == "IN" && !value.is_array() {
+            bail!("IN expects a list, e.g. [1, 2, 3]");
+        }
+        let value_var = self.bind(value);
+
+        Ok(format!(
+            "{}.@{} {} @{}",
+            self.variable, attribute_var, op, value_var
+        ))
+    }
+
+    fn parse_path(&mut self) -> Result<Vec<String>> {
+        let mut path = Vec::new();
+        match self.next() {
+            Some(Token::Ident(name)) => path.push(name),
+            _ => bail!("Expected an attribute name"),
+        }
+        while self.peek() == Some(&Token::Dot) {
+            self.pos += 1;
+            match self.next() {
+                Some(Token::Ident(name)) => path.push(name),
+                _ => bail!("Expected an attribute name after '.'"),
+            }
+        }
+        Ok(path)
+    }
+
+    fn parse_value(&mut self) -> Result<serde_json::Value> {
+        match self.next() {
+            Some(Token::Str(s)) => Ok(serde_json::Value::String(s)),
+            Some(Token::Number(n)) => Ok(serde_json::Value::Number(n)),
+            Some(Token::Ident(word)) => match word.to_ascii_lowercase().as_str() {
+                "true" => Ok(serde_json::Value::Bool(true)),
+                "false" => Ok(serde_json::Value::Bool(false)),
+                "null" => Ok(serde_json::Value::Null),
+                _ => bail!("Unquoted value '{}', use \"{}\" for strings", word, word),
+            },
+            Some(Token::LBracket) => {
+                let mut items = Vec::new();
+                if self.peek() == Some(&Token::RBracket) {
+                    self.pos += 1;
+                    return Ok(serde_json::Value::Array(items));
+                }
+                loop {
+                    items.push(self.parse_value()?);
+                    match self.next() {
+                        Some(Token::Comma) => continue,
+                        Some(Token::RBracket) => break,
+                        _ => bail!("Expected ',' or ']' in list"),
+                    }
+                }
+                Ok(serde_json::Value::Array(items))
+            }
+            _ => bail!("Expected a value (string, number, true, false, null or list)"),
+        }
+    }
+}
+
+// Parse a filter expression into an AQL condition on `variable`
+pub fn parse_filter(input: &str, variable: &str) -> Result<AqlFilter> {
+    let mut parser = Parser {
+        tokens: tokenize(input)?,
+        pos: 0,
+        variable,
+        bind_vars: serde_json::Map::new(),
+    };
+
+    if parser.tokens.is_empty() {
+        bail!("Empty filter");
+    }
+
+    let condition = parser.parse_or()?;
+    if parser.pos < parser.tokens.len() {
+        bail!("Unexpected input after end of filter");
+    }
+
+    Ok(AqlFilter {
+        condition,
+        bind_vars: parser.bind_vars,
+    })
+}
+
+// Split an attribute path like `address.city` into its parts
+pub fn parse_attribute_path(input: &str) -> Result<Vec<String>> {
+    let mut parser = Parser {
+        tokens: tokenize(input)?,
+        pos: 0,
+        variable: "",
+        bind_vars: serde_json::Map::new(),
+    };
+    let path = parser.parse_path()?;
+    if parser.pos < parser.tokens.len() {
+        bail!("Unexpected input after attribute name");
+    }
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn comparisons_bind_attributes_and_values() {
+        let filter = parse_filter("address.city == 'Köln' && age >= 30", "d").unwrap();
+        assert_eq!(
+            filter.condition,
+            "d.@filter0 == @filter1 && d.@filter2 >= @filter3"
+        );
+        assert_eq!(filter.bind_vars["filter0"], json!(["address", "city"]));
+        assert_eq!(filter.bind_vars["filter1"], "Köln");
+        assert_eq!(filter.bind_vars["filter2"], "age");
+        assert_eq!(filter.bind_vars["filter3"], 30);
+    }
+
+    #[test]
+    fn and_binds_tighter_than_or() {
+        let filter = parse_filter("a == 1 || b == 2 && c == 3", "d").unwrap();
+        assert_eq!(
+            filter.condition,
+            "d.@filter0 == @filter1 || d.@filter2 == @filter3 && d.@filter4 == @filter5"
+        );
+
+        let filter = parse_filter("(a == 1 || b == 2) && !c == 3", "d").unwrap();
+        assert_eq!(
+            filter.condition,
+            "(d.@filter0 == @filter1 || d.@filter2 == @filter3) && !(d.@filter4 == @filter5)"
+        );
+    }
+
+    #[test]
+    fn values() {
+        let filter = parse_filter(
+            "tags IN [\"a\", 'b\\'c'] && x == null && y != TRUE && `first name` LIKE \"A%\"",
+            "doc",
+        )
+        .unwrap();
+        assert!(filter.condition.starts_with("doc.@filter0 IN @filter1"));
+        assert!(filter.condition.ends_with("doc.@filter6 LIKE @filter7"));
+        assert_eq!(filter.bind_vars["filter1"], json!(["a", "b'c"]));
+        assert_eq!(filter.bind_vars["filter3"], json!(null));
+        assert_eq!(filter.bind_vars["filter5"], json!(true));
+        assert_eq!(filter.bind_vars["filter6"], "first name");
+    }
+
+    #[test]
+    fn numbers() {
+        let values: Vec<serde_json::Value> = ["-3", "0.5", "1e-5", "2.5E+3", "1e3"]
+            .iter()
+            .map(|n| parse_filter(&format!("x > {}", n), "d").unwrap().bind_vars["filter1"].clone())
+            .collect();
+        assert_eq!(
+            values,
+            [
+                json!(-3),
+                json!(0.5),
+                json!(1e-5),
+                json!(2500.0),
+                json!(1000.0)
+            ]
+        );
+
+        assert!(parse_filter("x > 1-5", "d").is_err());
+        assert!(parse_filter("x > 1e", "d").is_err());
+    }
+
+    #[test]
+    fn errors() {
+        for input in [
+            "",
+            "age",
+            "age >",
+            "age > 30 &&",
+            "(age > 30",
+            "name == Alice",
+            "tags IN \"a\"",
+            "name == \"open",
+            "a == 1 b == 2",
+            "a = 1",
+        ] {
+            assert!(
+                parse_filter(input, "d").is_err(),
+                "{:?} should not parse",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn attribute_paths() {
+        assert_eq!(
+            parse_attribute_path("address.`zip code`").unwrap(),
+            ["address", "zip code"]
+        );
+        assert!(parse_attribute_path("address.").is_err());
+        assert!(parse_attribute_path("a b").is_err());
+    }
+}
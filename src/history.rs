@@ -0,0 +1,337 @@
+// Persistent AQL query history and saved queries.
+//
+// Both live in the user's config directory (e.g. ~/.config/arangotui):
+//   history.jsonl       one executed query per line, newest last
+//   saved_queries.json  named favourites
+//   query_tabs.json     the AQL query tabs of the last session
+//
+// A history that cannot be read or written never stops the application; the
+// caller decides whether to report the error.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Older entries are dropped when the history is loaded
+const MAX_HISTORY_ENTRIES: usize = 1000;
+
+const HISTORY_FILE: &str = "history.jsonl";
+const SAVED_QUERIES_FILE: &str = "saved_queries.json";
+const QUERY_TABS_FILE: &str = "query_tabs.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryRecord {
+    pub query: String,
+    #[serde(rename = "bindVars")]
+    pub bind_vars: serde_json::Value,
+    pub options: serde_json::Value,
+    pub database: String,
+    pub timestamp: u64, // Seconds since the Unix epoch
+    #[serde(rename = "durationMs")]
+    pub duration_ms: u64,
+    #[serde(rename = "resultCount")]
+    pub result_count: usize,
+    // Recorded with more batches pending and no server count: the result count
+    // is a lower bound and the duration covers the first batch only
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub partial: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>, // Only set for saved queries
+}
+
+impl QueryRecord {
+    // Case-insensitive match of the query text, name or database
+    pub fn matches(&self, term: &str) -> bool {
+        let term = term.to_lowercase();
+        self.query.to_lowercase().contains(&term)
+            || self.database.to_lowercase().contains(&term)
+            || self
+                .name
+                .as_ref()
+                .is_some_and(|n| n.to_lowercase().contains(&term))
+    }
+}
+
+// An AQL query tab as restored on the next launch. Bind parameters and
+// options are kept as typed, so unfinished JSON survives as well.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryTab {
+    pub name: String,
+    pub query: String,
+    #[serde(rename = "bindVars")]
+    pub bind_vars: String,
+    pub options: String,
+}
+
+pub struct QueryHistory {
+    dir: Option<PathBuf>,
+    pub entries: Vec<QueryRecord>,
+    pub saved: Vec<QueryRecord>,
+}
+
+pub fn now_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+// Format seconds since the Unix epoch as `YYYY-MM-DD HH:MM:SS` (UTC)
+pub fn format_timestamp(timestamp: u64) -> String {
+    let days = (timestamp / 86_400) as i64;
+    let seconds = timestamp % 86_400;
+
+    // Civil date from days since 1970-01-01 (Howard Hinnant's algorithm)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+        year,
+        month,
+        day,
+        seconds / 3_600,
+        seconds % 3_600 / 60,
+        seconds % 60
+    )
+}
+
+impl QueryHistory {
+    // Load history and saved queries; unreadable files start out empty
+    pub fn load() -> Self {
+        Self::load_from(dirs::config_dir().map(|d| d.join("arangotui")))
+    }
+
+    fn load_from(dir: Option<PathBuf>) -> Self {
+        let mut history = Self {
+            dir,
+            entries: Vec::new(),
+            saved: Vec::new(),
+        };
+
+        if let Some(path) = history.path(HISTORY_FILE)
+            && let Ok(content) = fs::read_to_string(&path)
+        {
+            history.entries = content
+                .lines()
+                .filter_map(|line| serde_json::from_str(line).ok())
+                .collect();
+            if history.entries.len() > MAX_HISTORY_ENTRIES {
+                let excess = history.entries.len() - MAX_HISTORY_ENTRIES;
+                history.entries.drain(..excess);
+                let _ = history.rewrite_entries();
+            }
+        }
+
+        if let Some(path) = history.path(SAVED_QUERIES_FILE)
+            && let Ok(content) = fs::read_to_string(&path)
+        {
+            history.saved = serde_json::from_str(&content).unwrap_or_default();
+        }
+
+        history
+    }
+
+    fn path(&self, file: &str) -> Option<PathBuf> {
+        self.dir.as_ref().map(|d| d.join(file))
+    }
+
+    fn ensure_dir(&self) -> Result<PathBuf> {
+        let dir = self
+            .dir
+            .clone()
+            .context("No config directory available for the query history")?;
+        fs::create_dir_all(&dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+        Ok(dir)
+    }
+
+    fn rewrite_entries(&self) -> Result<()> {
+        let path = self.ensure_dir()?.join(HISTORY_FILE);
+        let mut content = String::new();
+        for entry in &self.entries {
+            content.push_str(&serde_json::to_string(entry)?);
+            content.push('\n');
+        }
+        fs::write(&path, content).with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    // Append an executed query to the history
+    pub fn record(&mut self, record: QueryRecord) -> Result<()> {
+        let path = self.ensure_dir()?.join(HISTORY_FILE);
+        let line = serde_json::to_string(&record)?;
+        self.entries.push(record);
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .with_context(|| format!("Failed to open {}", path.display()))?;
+        writeln!(file, "{}", line).with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    fn write_saved(&self) -> Result<()> {
+        let path = self.ensure_dir()?.join(SAVED_QUERIES_FILE);
+        fs::write(&path, serde_json::to_string_pretty(&self.saved)?)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    // Save a query under a name, replacing a saved query with the same name
+    pub fn save_query(&mut self, mut record: QueryRecord, name: &str) -> Result<()> {
+        record.name = Some(name.to_string());
+        match self
+            .saved
+            .iter_mut()
+            .find(|s| s.name.as_deref() == Some(name))
+        {
+            Some(existing) => *existing = record,
+            None => self.saved.push(record),
+        }
+        self.write_saved()
+    }
+
+    pub fn delete_saved_query(&mut self, name: &str) -> Result<()> {
+        self.saved.retain(|s| s.name.as_deref() != Some(name));
+        self.write_saved()
+    }
+
+    // Query tabs of the last session; none if they were never saved
+    pub fn load_tabs(&self) -> Vec<QueryTab> {
+        self.path(QUERY_TABS_FILE)
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save_tabs(&self, tabs: &[QueryTab]) -> Result<()> {
+        let path = self.ensure_dir()?.join(QUERY_TABS_FILE);
+        fs::write(&path, serde_json::to_string_pretty(tabs)?)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    // A fresh directory below the temporary directory, removed by the caller
+    fn temp_dir() -> PathBuf {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        static DIRS: AtomicUsize = AtomicUsize::new(0);
+        std::env::temp_dir().join(format!(
+            "arangotui-history-{}-{}",
+            std::process::id(),
+            DIRS.fetch_add(1, Ordering::Relaxed)
+        ))
+    }
+
+    fn record(query: &str) -> QueryRecord {
+        QueryRecord {
+            query: query.to_string(),
+            bind_vars: json!({ "x": 1 }),
+            options: json!({ "count": true }),
+            database: "_system".to_string(),
+            timestamp: 1_700_000_000,
+            duration_ms: 12,
+            result_count: 3,
+            partial: false,
+            error: None,
+            name: None,
+        }
+    }
+
+    #[test]
+    fn timestamps() {
+        assert_eq!(format_timestamp(0), "1970-01-01 00:00:00");
+        assert_eq!(format_timestamp(951_782_400), "2000-02-29 00:00:00");
+        assert_eq!(format_timestamp(1_700_000_000), "2023-11-14 22:13:20");
+        assert_eq!(format_timestamp(4_107_542_399), "2100-02-28 23:59:59");
+    }
+
+    #[test]
+    fn history_round_trip() {
+        let dir = temp_dir();
+        let mut history = QueryHistory::load_from(Some(dir.clone()));
+        assert!(history.entries.is_empty());
+
+        history.record(record("RETURN 1")).unwrap();
+        let mut partial = record("FOR d IN c RETURN d");
+        partial.partial = true;
+        partial.error = Some("timeout".to_string());
+        history.record(partial).unwrap();
+        // Lines that cannot be parsed are skipped
+        let path = dir.join(HISTORY_FILE);
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        writeln!(file, "not json").unwrap();
+
+        let loaded = QueryHistory::load_from(Some(dir.clone()));
+        let queries: Vec<&str> = loaded.entries.iter().map(|e| e.query.as_str()).collect();
+        assert_eq!(queries, ["RETURN 1", "FOR d IN c RETURN d"]);
+        assert_eq!(loaded.entries[0].bind_vars, json!({ "x": 1 }));
+        assert!(!loaded.entries[0].partial);
+        assert!(loaded.entries[1].partial);
+        assert_eq!(loaded.entries[1].error.as_deref(), Some("timeout"));
+        assert!(loaded.entries[1].matches("SYSTEM"));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn saved_queries_and_tabs() {
+        let dir = temp_dir();
+        let mut history = QueryHistory::load_from(Some(dir.clone()));
+        assert!(history.load_tabs().is_empty());
+
+        history.save_query(record("RETURN 1"), "one").unwrap();
+        history.save_query(record("RETURN 2"), "two").unwrap();
+        history.save_query(record("RETURN 3"), "one").unwrap();
+        history.delete_saved_query("two").unwrap();
+        let tabs = [QueryTab {
+            name: "Query 1".to_string(),
+            query: "RETURN @x".to_string(),
+            bind_vars: "{ \"x\": ".to_string(),
+            options: "{}".to_string(),
+        }];
+        history.save_tabs(&tabs).unwrap();
+
+        let loaded = QueryHistory::load_from(Some(dir.clone()));
+        assert_eq!(loaded.saved.len(), 1);
+        assert_eq!(loaded.saved[0].name.as_deref(), Some("one"));
+        assert_eq!(loaded.saved[0].query, "RETURN 3");
+        assert!(loaded.saved[0].matches("ON"));
+        let restored = loaded.load_tabs();
+        assert_eq!(restored.len(), 1);
+        assert_eq!(restored[0].query, "RETURN @x");
+        assert_eq!(restored[0].bind_vars, "{ \"x\": ");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn long_histories_are_trimmed() {
+        let dir = temp_dir();
+        fs::create_dir_all(&dir).unwrap();
+        let lines: Vec<String> = (0..MAX_HISTORY_ENTRIES + 5)
+            .map(|n| serde_json::to_string(&record(&format!("RETURN {}", n))).unwrap())
+            .collect();
+        fs::write(dir.join(HISTORY_FILE), lines.join("\n")).unwrap();
+
+        let loaded = QueryHistory::load_from(Some(dir.clone()));
+        assert_eq!(loaded.entries.len(), MAX_HISTORY_ENTRIES);
+        assert_eq!(loaded.entries[0].query, "RETURN 5");
+        // The file is rewritten without the dropped entries
+        let content = fs::read_to_string(dir.join(HISTORY_FILE)).unwrap();
+        assert_eq!(content.lines().count(), MAX_HISTORY_ENTRIES);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
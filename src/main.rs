@@ -15,8 +15,19 @@ use ratatui::{
 };
 use reqwest::Client;
 use serde::Deserialize;
+use std::collections::HashMap;
 use std::io;
-use tui_textarea::TextArea;
+use tui_textarea::{CursorMove, TextArea};
+
+mod aql;
+mod compare;
+mod export;
+mod filter;
+mod history;
+mod params;
+mod plan;
+mod results;
+mod schema;
 
 #[derive(Parser, Debug)]
 #[command(name = "arangotui")]
@@ -37,6 +48,10 @@ struct Args {
     /// Password for authentication
     #[arg(long, default_value = "")]
     password: String,
+
+    /// Restore the AQL query tabs of the last session
+    #[arg(long)]
+    restore_tabs: bool,
 }
 
 #[derive(Debug, Deserialize)]
@@ -160,6 +175,8 @@ struct CollectionCount {
     key_options: Option<serde_json::Value>,
     #[serde(rename = "computedValues")]
     computed_values: Option<serde_json::Value>,
+    #[serde(rename = "replicationFactor")]
+    replication_factor: Option<serde_json::Value>,
     #[serde(rename = "objectId")]
     object_id: Option<String>,
 }
@@ -193,6 +210,39 @@ struct GraphInfo {
     replication_factor: Option<serde_json::Value>,
 }
 
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct IndexInfo {
+    id: String,
+    name: Option<String>,
+    #[serde(rename = "type")]
+    index_type: String,
+    #[serde(default)]
+    fields: Vec<serde_json::Value>,
+    #[serde(default)]
+    unique: bool,
+    #[serde(default)]
+    sparse: bool,
+    selectivity_estimate: Option<f64>,
+    in_background: Option<bool>,
+    is_building: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+struct IndexListResponse {
+    indexes: Vec<IndexInfo>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+struct ViewInfo {
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ViewListResponse {
+    result: Vec<ViewInfo>,
+}
+
 #[derive(Debug, Deserialize)]
 struct GraphListResponse {
     error: bool,
@@ -207,6 +257,7 @@ struct AqlQueryResponse {
     result: Vec<serde_json::Value>,
     #[serde(rename = "hasMore")]
     has_more: bool,
+    count: Option<u64>, // Only with the `count` option
     cached: bool,
     extra: Option<serde_json::Value>,
     id: Option<String>,
@@ -219,6 +270,7 @@ struct AqlCursorNextResponse {
     result: Vec<serde_json::Value>,
     #[serde(rename = "hasMore")]
     has_more: bool,
+    extra: Option<serde_json::Value>, // Final statistics arrive with the last batch of a stream
     id: Option<String>,
 }
 
@@ -246,6 +298,7 @@ struct AppState {
     gae_version: Option<GaeVersion>,
     selected_menu_item: usize,
     http_client: Client,
+    restore_tabs: bool,
 }
 
 enum MenuItem {
@@ -283,6 +336,19 @@ fn create_http_client() -> Result<Client> {
         .context("Failed to create HTTP client")
 }
 
+// URL of an API path in a database. Each segment is percent-encoded, so names
+// and document keys may contain characters like '/', '?', '#' or '%'.
+fn database_api_url(endpoint: &str, database: &str, path: &[&str]) -> Result<reqwest::Url> {
+    let mut url = reqwest::Url::parse(endpoint.trim_end_matches('/'))
+        .with_context(|| format!("Invalid endpoint {}", endpoint))?;
+    url.path_segments_mut()
+        .map_err(|_| anyhow::anyhow!("Invalid endpoint {}", endpoint))?
+        .pop_if_empty()
+        .extend(["_db", database, "_api"])
+        .extend(path);
+    Ok(url)
+}
+
 async fn check_arango_version(
     client: &Client,
     endpoint: &str,
@@ -416,13 +482,9 @@ async fn get_collections(
     username: &str,
     password: &str,
 ) -> Result<Vec<CollectionInfo>> {
-    let url = format!(
-        "{}/_db/{}/_api/collection",
-        endpoint.trim_end_matches('/'),
-        database
-    );
+    let url = database_api_url(endpoint, database, &["collection"])?;
     let response = client
-        .get(&url)
+        .get(url)
         .basic_auth(username, Some(password))
         .send()
         .await
@@ -448,14 +510,9 @@ async fn get_collection_count(
     username: &str,
     password: &str,
 ) -> Result<CollectionCount> {
-    let url = format!(
-        "{}/_db/{}/_api/collection/{}/count",
-        endpoint.trim_end_matches('/'),
-        database,
-        collection
-    );
+    let url = database_api_url(endpoint, database, &["collection", collection, "count"])?;
     let response = client
-        .get(&url)
+        .get(url)
         .basic_auth(username, Some(password))
         .send()
         .await
@@ -480,13 +537,9 @@ async fn get_graphs(
     username: &str,
     password: &str,
 ) -> Result<Vec<GraphInfo>> {
-    let url = format!(
-        "{}/_db/{}/_api/gharial",
-        endpoint.trim_end_matches('/'),
-        database
-    );
+    let url = database_api_url(endpoint, database, &["gharial"])?;
     let response = client
-        .get(&url)
+        .get(url)
         .basic_auth(username, Some(password))
         .send()
         .await
@@ -504,47 +557,31 @@ async fn get_graphs(
     Ok(graph_response.graphs)
 }
 
-async fn execute_aql_query(
+async fn get_views(
     client: &Client,
     endpoint: &str,
     database: &str,
-    query: &str,
     username: &str,
     password: &str,
-) -> Result<Vec<serde_json::Value>> {
-    let url = format!(
-        "{}/_db/{}/_api/cursor",
-        endpoint.trim_end_matches('/'),
-        database
-    );
-
-    let body = serde_json::json!({
-        "query": query,
-        "count": false,
-        "batchSize": 1000,
-        "options": {
-            "stream": true
-        }
-    });
-
+) -> Result<Vec<ViewInfo>> {
+    let url = database_api_url(endpoint, database, &["view"])?;
     let response = client
-        .post(&url)
+        .get(url)
         .basic_auth(username, Some(password))
-        .json(&body)
         .send()
         .await
-        .context("Failed to execute AQL query")?;
+        .context("Failed to fetch views")?;
 
     if !response.status().is_success() {
-        anyhow::bail!("Failed to execute AQL query: {}", response.status());
+        anyhow::bail!("Failed to fetch views: {}", response.status());
     }
 
-    let query_response: AqlQueryResponse = response
+    let view_response: ViewListResponse = response
         .json()
         .await
-        .context("Failed to parse AQL query response")?;
+        .context("Failed to parse view list response")?;
 
-    Ok(query_response.result)
+    Ok(view_response.result)
 }
 
 async fn execute_aql_query_with_params(
@@ -558,37 +595,83 @@ async fn execute_aql_query_with_params(
     username: &str,
     password: &str,
 ) -> Result<AqlQueryResponse> {
-    let url = format!(
-        "{}/_db/{}/_api/cursor",
-        endpoint.trim_end_matches('/'),
-        database
-    );
+    let options = AqlQueryOptions {
+        batch_size,
+        stream,
+        ..AqlQueryOptions::default()
+    };
+    create_cursor(
+        client, endpoint, database, query, bind_vars, &options, username, password,
+    )
+    .await
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn create_cursor(
+    client: &Client,
+    endpoint: &str,
+    database: &str,
+    query: &str,
+    bind_vars: Option<serde_json::Value>,
+    options: &AqlQueryOptions,
+    username: &str,
+    password: &str,
+) -> Result<AqlQueryResponse> {
+    let url = database_api_url(endpoint, database, &["cursor"])?;
 
     let mut body = serde_json::json!({
         "query": query,
-        "count": false,
-        "batchSize": batch_size,
+        "count": options.count,
+        "batchSize": options.batch_size,
         "options": {
-            "stream": stream
+            "stream": options.stream,
+            "fullCount": options.full_count
         }
     });
 
     if let Some(vars) = bind_vars {
         body["bindVars"] = vars;
     }
+    if let Some(ttl) = options.ttl {
+        body["ttl"] = ttl.into();
+    }
+    if let Some(limit) = options.memory_limit {
+        body["memoryLimit"] = limit.into();
+    }
+    if let Some(runtime) = options.max_runtime {
+        body["options"]["maxRuntime"] = runtime.into();
+    }
+    if let Some(profile) = options.profile {
+        body["options"]["profile"] = profile.into();
+    }
+    if let Some(fail) = options.fail_on_warning {
+        body["options"]["failOnWarning"] = fail.into();
+    }
+    if !options.optimizer_rules.is_empty() {
+        body["options"]["optimizer"] = serde_json::json!({ "rules": options.optimizer_rules });
+    }
+    if let Some(plans) = options.max_plans {
+        body["options"]["maxPlans"] = plans.into();
+    }
+    if let Some(cache) = options.cache {
+        body["cache"] = cache.into();
+    }
 
-    let response = client
-        .post(&url)
+    let mut request = client
+        .post(url)
         .basic_auth(username, Some(password))
-        .json(&body)
+        .json(&body);
+    if options.allow_dirty_reads {
+        request = request.header("x-arango-allow-dirty-read", "true");
+    }
+    let response = request
         .send()
         .await
         .context("Failed to execute AQL query")?;
 
     if !response.status().is_success() {
-        let status = response.status();
-        let error_text = response.text().await.unwrap_or_default();
-        anyhow::bail!("Failed to execute AQL query: {} - {}", status, error_text);
+        return Err(anyhow::Error::new(read_arango_error(response).await)
+            .context("Failed to execute AQL query"));
     }
 
     let query_response: AqlQueryResponse = response
@@ -599,6 +682,41 @@ async fn execute_aql_query_with_params(
     Ok(query_response)
 }
 
+async fn explain_aql_query(
+    client: &Client,
+    endpoint: &str,
+    database: &str,
+    query: &str,
+    bind_vars: Option<serde_json::Value>,
+    username: &str,
+    password: &str,
+) -> Result<serde_json::Value> {
+    let url = database_api_url(endpoint, database, &["explain"])?;
+
+    let mut body = serde_json::json!({ "query": query });
+    if let Some(vars) = bind_vars {
+        body["bindVars"] = vars;
+    }
+
+    let response = client
+        .post(url)
+        .basic_auth(username, Some(password))
+        .json(&body)
+        .send()
+        .await
+        .context("Failed to explain AQL query")?;
+
+    if !response.status().is_success() {
+        return Err(anyhow::Error::new(read_arango_error(response).await)
+            .context("Failed to explain AQL query"));
+    }
+
+    response
+        .json()
+        .await
+        .context("Failed to parse explain response")
+}
+
 async fn fetch_cursor_next(
     client: &Client,
     endpoint: &str,
@@ -607,22 +725,19 @@ async fn fetch_cursor_next(
     username: &str,
     password: &str,
 ) -> Result<AqlCursorNextResponse> {
-    let url = format!(
-        "{}/_db/{}/_api/cursor/{}",
-        endpoint.trim_end_matches('/'),
-        database,
-        cursor_id
-    );
+    let url = database_api_url(endpoint, database, &["cursor", cursor_id])?;
 
     let response = client
-        .put(&url)
+        .put(url)
         .basic_auth(username, Some(password))
         .send()
         .await
         .context("Failed to fetch cursor")?;
 
     if !response.status().is_success() {
-        anyhow::bail!("Failed to fetch cursor: {}", response.status());
+        return Err(
+            anyhow::Error::new(read_arango_error(response).await).context("Failed to fetch cursor")
+        );
     }
 
     let cursor_response: AqlCursorNextResponse = response
@@ -633,388 +748,3602 @@ async fn fetch_cursor_next(
     Ok(cursor_response)
 }
 
-async fn get_database_stats(
+async fn delete_cursor(
     client: &Client,
     endpoint: &str,
     database: &str,
+    cursor_id: &str,
     username: &str,
     password: &str,
-) -> DatabaseStats {
-    match get_collections(client, endpoint, database, username, password).await {
-        Ok(collections) => {
-            let mut doc_collections = 0;
-            let mut edge_collections = 0;
-            let mut system_collections = 0;
+) -> Result<()> {
+    let url = database_api_url(endpoint, database, &["cursor", cursor_id])?;
 
-            for coll in collections {
-                if coll.is_system {
-                    system_collections += 1;
-                } else if coll.collection_type == 2 {
-                    doc_collections += 1;
-                } else if coll.collection_type == 3 {
-                    edge_collections += 1;
-                }
-            }
+    let response = client
+        .delete(url)
+        .basic_auth(username, Some(password))
+        .send()
+        .await
+        .context("Failed to delete cursor")?;
 
-            DatabaseStats {
-                name: database.to_string(),
-                doc_collections,
-                edge_collections,
-                system_collections,
-                accessible: true,
-            }
-        }
-        Err(_) => DatabaseStats {
-            name: database.to_string(),
-            doc_collections: 0,
-            edge_collections: 0,
-            system_collections: 0,
-            accessible: false,
-        },
+    // The cursor may already be exhausted and gone
+    if !response.status().is_success() && response.status() != reqwest::StatusCode::NOT_FOUND {
+        anyhow::bail!(
+            "Failed to delete cursor: {}",
+            read_arango_error(response).await
+        );
     }
+    Ok(())
 }
 
-#[derive(Clone, Debug)]
-enum BrowserView {
-    DatabaseList,
-    CollectionList(String),               // database name
-    GraphList(String),                    // database name
-    CollectionProperties(String, String), // database name, collection name
-    DocumentViewer(String, String),       // database name, collection name
-    GraphProperties(String, String),      // database name, graph name
-    AqlQueryInput(String),                // database name
-    AqlQueryResults(String),              // database name
+// A query currently executing on the server, from the query API
+#[derive(Debug, Deserialize, Clone)]
+struct RunningQueryInfo {
+    id: String,
+    query: String,
+    #[serde(rename = "bindVars", default)]
+    bind_vars: serde_json::Value,
+    #[serde(default)]
+    user: String,
+    #[serde(default)]
+    started: String,
+    #[serde(rename = "runTime", default)]
+    run_time: f64, // Seconds
+    #[serde(default)]
+    state: String,
 }
 
-#[derive(Clone, Debug)]
-enum GaeView {
-    Graphs,
-    Jobs,
-    LoadGraphInput,
+async fn get_running_queries(
+    client: &Client,
+    endpoint: &str,
+    database: &str,
+    username: &str,
+    password: &str,
+) -> Result<Vec<RunningQueryInfo>> {
+    get_query_list(client, endpoint, database, "current", username, password).await
 }
 
-enum InputState {
-    None,
-    EnteringDocumentCount(String), // Current input string
+// Recently finished queries that took longer than the slow query threshold
+async fn get_slow_queries(
+    client: &Client,
+    endpoint: &str,
+    database: &str,
+    username: &str,
+    password: &str,
+) -> Result<Vec<RunningQueryInfo>> {
+    get_query_list(client, endpoint, database, "slow", username, password).await
 }
 
-#[derive(Clone, Debug)]
-enum AqlInputField {
-    Query,
-    Parameters,
-    Options,
-    Submit,
+async fn get_query_list(
+    client: &Client,
+    endpoint: &str,
+    database: &str,
+    list: &str,
+    username: &str,
+    password: &str,
+) -> Result<Vec<RunningQueryInfo>> {
+    let url = database_api_url(endpoint, database, &["query", list])?;
+
+    let response = client
+        .get(url)
+        .basic_auth(username, Some(password))
+        .send()
+        .await
+        .with_context(|| format!("Failed to fetch {} queries", list))?;
+
+    if !response.status().is_success() {
+        anyhow::bail!(
+            "Failed to fetch {} queries: {}",
+            list,
+            read_arango_error(response).await
+        );
+    }
+
+    response
+        .json()
+        .await
+        .with_context(|| format!("Failed to parse {} queries", list))
 }
 
-#[derive(Clone, Debug)]
-struct AqlQueryOptions {
-    batch_size: usize,
-    stream: bool,
-    max_documents: usize,
+async fn kill_query(
+    client: &Client,
+    endpoint: &str,
+    database: &str,
+    query_id: &str,
+    username: &str,
+    password: &str,
+) -> Result<()> {
+    let url = database_api_url(endpoint, database, &["query", query_id])?;
+
+    let response = client
+        .delete(url)
+        .basic_auth(username, Some(password))
+        .send()
+        .await
+        .context("Failed to kill query")?;
+
+    // The query may have finished in the meantime
+    if !response.status().is_success() && response.status() != reqwest::StatusCode::NOT_FOUND {
+        anyhow::bail!(
+            "Failed to kill query: {}",
+            read_arango_error(response).await
+        );
+    }
+    Ok(())
 }
 
-impl Default for AqlQueryOptions {
-    fn default() -> Self {
-        Self {
-            batch_size: 1000,
-            stream: true,
-            max_documents: 100000,
+// Run a query with profiling; the plan and per-node statistics are returned in `extra`
+async fn profile_aql_query(
+    client: &Client,
+    endpoint: &str,
+    database: &str,
+    query: &str,
+    bind_vars: Option<serde_json::Value>,
+    username: &str,
+    password: &str,
+) -> Result<AqlQueryResponse> {
+    let url = database_api_url(endpoint, database, &["cursor"])?;
+
+    // Not streaming, so the query runs to completion and the first response has the profile
+    let mut body = serde_json::json!({
+        "query": query,
+        "batchSize": 1,
+        "options": {
+            "profile": 2,
+            "stream": false
         }
+    });
+    if let Some(vars) = bind_vars {
+        body["bindVars"] = vars;
+    }
+
+    let response = client
+        .post(url)
+        .basic_auth(username, Some(password))
+        .json(&body)
+        .send()
+        .await
+        .context("Failed to profile AQL query")?;
+
+    if !response.status().is_success() {
+        return Err(anyhow::Error::new(read_arango_error(response).await)
+            .context("Failed to profile AQL query"));
     }
+
+    let query_response: AqlQueryResponse = response
+        .json()
+        .await
+        .context("Failed to parse AQL query response")?;
+
+    // Only the profile is of interest, not the results
+    if let Some(cursor_id) = &query_response.id {
+        let _ = delete_cursor(client, endpoint, database, cursor_id, username, password).await;
+    }
+
+    Ok(query_response)
 }
 
-struct AqlState {
-    query_textarea: TextArea<'static>,
-    parameters_textarea: TextArea<'static>,
-    options_textarea: TextArea<'static>,
-    active_field: AqlInputField,
-    parameters_valid: bool,
-    options_valid: bool,
-    // Results state
-    results: Vec<serde_json::Value>,
-    total_fetched: usize,
-    has_more: bool,
-    cursor_id: Option<String>,
-    current_page: usize,
-    scroll_offset: usize,
-    is_fetching: bool,
+// Outcome of a write that carries a `_rev` precondition
+enum DocumentWriteOutcome {
+    Written(serde_json::Value),
+    Conflict,
 }
 
+// An error reported by the server, with the errorNum and errorMessage of the
+// response body kept apart
 #[derive(Clone, Debug)]
-enum LoadGraphField {
-    JsonInput,
-    Submit,
+struct ArangoError {
+    error_num: Option<i64>,
+    message: String,
 }
 
-struct LoadGraphState {
-    textarea: TextArea<'static>,
-    json_valid: bool,
-    active_field: LoadGraphField,
+impl std::fmt::Display for ArangoError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self.error_num {
+            Some(num) => write!(f, "{} (errorNum {})", self.message, num),
+            None => write!(f, "{}", self.message),
+        }
+    }
 }
 
-struct GaeBrowser {
-    view: GaeView,
-    graphs: Vec<GaeGraph>,
-    selected_graph_index: usize,
-    jobs: Vec<GaeJob>,
-    selected_job_index: usize,
-    accessible: bool,
-    error_message: Option<String>,
-    load_graph_state: Option<LoadGraphState>,
+impl std::error::Error for ArangoError {}
+
+impl ArangoError {
+    fn from_error(e: &anyhow::Error) -> Self {
+        e.downcast_ref::<ArangoError>()
+            .cloned()
+            .unwrap_or_else(|| ArangoError {
+                error_num: None,
+                message: format!("{:#}", e),
+            })
+    }
 }
 
-impl GaeBrowser {
-    fn new() -> Self {
-        Self {
-            view: GaeView::Graphs,
-            graphs: Vec::new(),
-            selected_graph_index: 0,
-            jobs: Vec::new(),
-            selected_job_index: 0,
-            accessible: true,
-            error_message: None,
-            load_graph_state: None,
+// Extract the server's errorMessage from an ArangoDB error response body,
+// falling back to the HTTP status and the body text
+async fn read_arango_error(response: reqwest::Response) -> ArangoError {
+    let status = response.status();
+    let error_text = response.text().await.unwrap_or_default();
+    match serde_json::from_str::<serde_json::Value>(&error_text) {
+        Ok(body) if body["errorMessage"].is_string() => ArangoError {
+            error_num: body["errorNum"].as_i64(),
+            message: body["errorMessage"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+        },
+        _ => ArangoError {
+            error_num: None,
+            message: format!("{} - {}", status, error_text),
+        },
+    }
+}
+
+async fn get_document(
+    client: &Client,
+    endpoint: &str,
+    database: &str,
+    collection: &str,
+    key: &str,
+    username: &str,
+    password: &str,
+) -> Result<Option<serde_json::Value>> {
+    let url = database_api_url(endpoint, database, &["document", collection, key])?;
+
+    let response = client
+        .get(url)
+        .basic_auth(username, Some(password))
+        .send()
+        .await
+        .context("Failed to fetch document")?;
+
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+
+    if !response.status().is_success() {
+        anyhow::bail!(
+            "Failed to fetch document: {}",
+            read_arango_error(response).await
+        );
+    }
+
+    let document: serde_json::Value = response
+        .json()
+        .await
+        .context("Failed to parse document response")?;
+
+    Ok(Some(document))
+}
+
+async fn insert_document(
+    client: &Client,
+    endpoint: &str,
+    database: &str,
+    collection: &str,
+    document: &serde_json::Value,
+    username: &str,
+    password: &str,
+) -> Result<serde_json::Value> {
+    let mut url = database_api_url(endpoint, database, &["document", collection])?;
+    url.query_pairs_mut().append_pair("returnNew", "true");
+
+    let response = client
+        .post(url)
+        .basic_auth(username, Some(password))
+        .json(document)
+        .send()
+        .await
+        .context("Failed to insert document")?;
+
+    if !response.status().is_success() {
+        anyhow::bail!(
+            "Failed to insert document: {}",
+            read_arango_error(response).await
+        );
+    }
+
+    let mut insert_response: serde_json::Value = response
+        .json()
+        .await
+        .context("Failed to parse insert response")?;
+
+    Ok(insert_response["new"].take())
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn replace_document(
+    client: &Client,
+    endpoint: &str,
+    database: &str,
+    collection: &str,
+    key: &str,
+    document: &serde_json::Value,
+    rev: Option<&str>,
+    username: &str,
+    password: &str,
+) -> Result<DocumentWriteOutcome> {
+    let mut url = database_api_url(endpoint, database, &["document", collection, key])?;
+    url.query_pairs_mut().append_pair("returnNew", "true");
+
+    let mut request = client
+        .put(url)
+        .basic_auth(username, Some(password))
+        .json(document);
+    if let Some(rev) = rev {
+        request = request.header(reqwest::header::IF_MATCH, rev);
+    }
+
+    let response = request.send().await.context("Failed to save document")?;
+
+    if response.status() == reqwest::StatusCode::PRECONDITION_FAILED {
+        return Ok(DocumentWriteOutcome::Conflict);
+    }
+
+    if !response.status().is_success() {
+        anyhow::bail!(
+            "Failed to save document: {}",
+            read_arango_error(response).await
+        );
+    }
+
+    let mut replace_response: serde_json::Value = response
+        .json()
+        .await
+        .context("Failed to parse replace response")?;
+
+    Ok(DocumentWriteOutcome::Written(
+        replace_response["new"].take(),
+    ))
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn delete_document(
+    client: &Client,
+    endpoint: &str,
+    database: &str,
+    collection: &str,
+    key: &str,
+    rev: Option<&str>,
+    username: &str,
+    password: &str,
+) -> Result<DocumentWriteOutcome> {
+    let url = database_api_url(endpoint, database, &["document", collection, key])?;
+
+    let mut request = client.delete(url).basic_auth(username, Some(password));
+    if let Some(rev) = rev {
+        request = request.header(reqwest::header::IF_MATCH, rev);
+    }
+
+    let response = request.send().await.context("Failed to delete document")?;
+
+    if response.status() == reqwest::StatusCode::PRECONDITION_FAILED {
+        return Ok(DocumentWriteOutcome::Conflict);
+    }
+
+    if !response.status().is_success() {
+        anyhow::bail!(
+            "Failed to delete document: {}",
+            read_arango_error(response).await
+        );
+    }
+
+    let delete_response: serde_json::Value = response
+        .json()
+        .await
+        .context("Failed to parse delete response")?;
+
+    Ok(DocumentWriteOutcome::Written(delete_response))
+}
+
+// Line-based diff between two texts (longest common subsequence)
+#[derive(Clone, Debug, PartialEq)]
+enum DiffLine {
+    Same(String),
+    Removed(String),
+    Added(String),
+}
+
+// Most cells of the LCS table; larger differences are shown as all old lines
+// removed and all new lines added
+const DIFF_MAX_CELLS: usize = 1_000_000;
+
+fn diff_lines(old: &str, new: &str) -> Vec<DiffLine> {
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+
+    // Lines shared at the start and end need no table
+    let prefix = old_lines
+        .iter()
+        .zip(&new_lines)
+        .take_while(|(a, b)| a == b)
+        .count();
+    let suffix = old_lines[prefix..]
+        .iter()
+        .rev()
+        .zip(new_lines[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let old_middle = &old_lines[prefix..old_lines.len() - suffix];
+    let new_middle = &new_lines[prefix..new_lines.len() - suffix];
+
+    let mut diff: Vec<DiffLine> = old_lines[..prefix]
+        .iter()
+        .map(|l| DiffLine::Same(l.to_string()))
+        .collect();
+    if (old_middle.len() + 1).saturating_mul(new_middle.len() + 1) > DIFF_MAX_CELLS {
+        diff.extend(old_middle.iter().map(|l| DiffLine::Removed(l.to_string())));
+        diff.extend(new_middle.iter().map(|l| DiffLine::Added(l.to_string())));
+    } else {
+        diff.extend(lcs_diff(old_middle, new_middle));
+    }
+    diff.extend(
+        old_lines[old_lines.len() - suffix..]
+            .iter()
+            .map(|l| DiffLine::Same(l.to_string())),
+    );
+    diff
+}
+
+fn lcs_diff(old_lines: &[&str], new_lines: &[&str]) -> Vec<DiffLine> {
+    let (n, m) = (old_lines.len(), new_lines.len());
+
+    // lcs[i][j] = length of the LCS of old_lines[i..] and new_lines[j..]
+    let mut lcs = vec![vec![0usize; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if old_lines[i] == new_lines[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut diff = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if old_lines[i] == new_lines[j] {
+            diff.push(DiffLine::Same(old_lines[i].to_string()));
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            diff.push(DiffLine::Removed(old_lines[i].to_string()));
+            i += 1;
+        } else {
+            diff.push(DiffLine::Added(new_lines[j].to_string()));
+            j += 1;
+        }
+    }
+    diff.extend(
+        old_lines[i..]
+            .iter()
+            .map(|l| DiffLine::Removed(l.to_string())),
+    );
+    diff.extend(
+        new_lines[j..]
+            .iter()
+            .map(|l| DiffLine::Added(l.to_string())),
+    );
+    diff
+}
+
+fn diff_to_lines(diff: &[DiffLine]) -> Vec<Line<'static>> {
+    diff.iter()
+        .map(|d| match d {
+            DiffLine::Same(l) => Line::from(format!("  {}", l)),
+            DiffLine::Removed(l) => {
+                Line::styled(format!("- {}", l), Style::default().fg(Color::Red))
+            }
+            DiffLine::Added(l) => {
+                Line::styled(format!("+ {}", l), Style::default().fg(Color::Green))
+            }
+        })
+        .collect()
+}
+
+async fn create_collection(
+    client: &Client,
+    endpoint: &str,
+    database: &str,
+    properties: &serde_json::Value,
+    username: &str,
+    password: &str,
+) -> Result<()> {
+    let url = database_api_url(endpoint, database, &["collection"])?;
+
+    let response = client
+        .post(url)
+        .basic_auth(username, Some(password))
+        .json(properties)
+        .send()
+        .await
+        .context("Failed to create collection")?;
+
+    if !response.status().is_success() {
+        anyhow::bail!(
+            "Failed to create collection: {}",
+            read_arango_error(response).await
+        );
+    }
+
+    Ok(())
+}
+
+async fn update_collection_properties(
+    client: &Client,
+    endpoint: &str,
+    database: &str,
+    collection: &str,
+    properties: &serde_json::Value,
+    username: &str,
+    password: &str,
+) -> Result<()> {
+    let url = database_api_url(
+        endpoint,
+        database,
+        &["collection", collection, "properties"],
+    )?;
+
+    let response = client
+        .put(url)
+        .basic_auth(username, Some(password))
+        .json(properties)
+        .send()
+        .await
+        .context("Failed to update collection properties")?;
+
+    if !response.status().is_success() {
+        anyhow::bail!(
+            "Failed to update collection properties: {}",
+            read_arango_error(response).await
+        );
+    }
+
+    Ok(())
+}
+
+async fn truncate_collection(
+    client: &Client,
+    endpoint: &str,
+    database: &str,
+    collection: &str,
+    username: &str,
+    password: &str,
+) -> Result<()> {
+    let url = database_api_url(endpoint, database, &["collection", collection, "truncate"])?;
+
+    let response = client
+        .put(url)
+        .basic_auth(username, Some(password))
+        .send()
+        .await
+        .context("Failed to truncate collection")?;
+
+    if !response.status().is_success() {
+        anyhow::bail!(
+            "Failed to truncate collection: {}",
+            read_arango_error(response).await
+        );
+    }
+
+    Ok(())
+}
+
+async fn drop_collection(
+    client: &Client,
+    endpoint: &str,
+    database: &str,
+    collection: &str,
+    is_system: bool,
+    username: &str,
+    password: &str,
+) -> Result<()> {
+    let mut url = database_api_url(endpoint, database, &["collection", collection])?;
+    url.query_pairs_mut()
+        .append_pair("isSystem", &is_system.to_string());
+
+    let response = client
+        .delete(url)
+        .basic_auth(username, Some(password))
+        .send()
+        .await
+        .context("Failed to drop collection")?;
+
+    if !response.status().is_success() {
+        anyhow::bail!(
+            "Failed to drop collection: {}",
+            read_arango_error(response).await
+        );
+    }
+
+    Ok(())
+}
+
+async fn rename_collection(
+    client: &Client,
+    endpoint: &str,
+    database: &str,
+    collection: &str,
+    new_name: &str,
+    username: &str,
+    password: &str,
+) -> Result<()> {
+    let url = database_api_url(endpoint, database, &["collection", collection, "rename"])?;
+
+    let response = client
+        .put(url)
+        .basic_auth(username, Some(password))
+        .json(&serde_json::json!({ "name": new_name }))
+        .send()
+        .await
+        .context("Failed to rename collection")?;
+
+    if !response.status().is_success() {
+        anyhow::bail!(
+            "Failed to rename collection: {}",
+            read_arango_error(response).await
+        );
+    }
+
+    Ok(())
+}
+
+async fn get_indexes(
+    client: &Client,
+    endpoint: &str,
+    database: &str,
+    collection: &str,
+    username: &str,
+    password: &str,
+) -> Result<Vec<IndexInfo>> {
+    let mut url = database_api_url(endpoint, database, &["index"])?;
+    url.query_pairs_mut().append_pair("collection", collection);
+
+    let response = client
+        .get(url)
+        .basic_auth(username, Some(password))
+        .send()
+        .await
+        .context("Failed to fetch indexes")?;
+
+    if !response.status().is_success() {
+        anyhow::bail!(
+            "Failed to fetch indexes: {}",
+            read_arango_error(response).await
+        );
+    }
+
+    let index_response: IndexListResponse = response
+        .json()
+        .await
+        .context("Failed to parse index list response")?;
+
+    Ok(index_response.indexes)
+}
+
+async fn create_index(
+    client: &Client,
+    endpoint: &str,
+    database: &str,
+    collection: &str,
+    definition: &serde_json::Value,
+    username: &str,
+    password: &str,
+) -> Result<()> {
+    let mut url = database_api_url(endpoint, database, &["index"])?;
+    url.query_pairs_mut().append_pair("collection", collection);
+
+    let response = client
+        .post(url)
+        .basic_auth(username, Some(password))
+        .json(definition)
+        .send()
+        .await
+        .context("Failed to create index")?;
+
+    if !response.status().is_success() {
+        anyhow::bail!(
+            "Failed to create index: {}",
+            read_arango_error(response).await
+        );
+    }
+
+    Ok(())
+}
+
+async fn drop_index(
+    client: &Client,
+    endpoint: &str,
+    database: &str,
+    index_id: &str,
+    username: &str,
+    password: &str,
+) -> Result<()> {
+    // Index ids are `<collection>/<id>`
+    let index_path: Vec<&str> = std::iter::once("index")
+        .chain(index_id.split('/'))
+        .collect();
+    let url = database_api_url(endpoint, database, &index_path)?;
+
+    let response = client
+        .delete(url)
+        .basic_auth(username, Some(password))
+        .send()
+        .await
+        .context("Failed to drop index")?;
+
+    if !response.status().is_success() {
+        anyhow::bail!(
+            "Failed to drop index: {}",
+            read_arango_error(response).await
+        );
+    }
+
+    Ok(())
+}
+
+// Form templates for the index types that can be created from the index view
+fn index_template(index_type: &str) -> serde_json::Value {
+    match index_type {
+        "ttl" => serde_json::json!({
+            "type": "ttl",
+            "name": "",
+            "fields": ["createdAt"],
+            "expireAfter": 3600
+        }),
+        "geo" => serde_json::json!({
+            "type": "geo",
+            "name": "",
+            "fields": ["location"],
+            "geoJson": true
+        }),
+        "fulltext" => serde_json::json!({
+            "type": "fulltext",
+            "name": "",
+            "fields": ["text"],
+            "minLength": 3
+        }),
+        "inverted" => serde_json::json!({
+            "type": "inverted",
+            "name": "",
+            "fields": [{ "name": "attribute" }],
+            "inBackground": true
+        }),
+        "vector" => serde_json::json!({
+            "type": "vector",
+            "name": "",
+            "fields": ["embedding"],
+            "params": {
+                "metric": "cosine",
+                "dimension": 128,
+                "nLists": 100
+            },
+            "inBackground": true
+        }),
+        _ => serde_json::json!({
+            "type": "persistent",
+            "name": "",
+            "fields": ["attribute"],
+            "unique": false,
+            "sparse": false,
+            "inBackground": true
+        }),
+    }
+}
+
+async fn get_database_stats(
+    client: &Client,
+    endpoint: &str,
+    database: &str,
+    username: &str,
+    password: &str,
+) -> DatabaseStats {
+    match get_collections(client, endpoint, database, username, password).await {
+        Ok(collections) => {
+            let mut doc_collections = 0;
+            let mut edge_collections = 0;
+            let mut system_collections = 0;
+
+            for coll in collections {
+                if coll.is_system {
+                    system_collections += 1;
+                } else if coll.collection_type == 2 {
+                    doc_collections += 1;
+                } else if coll.collection_type == 3 {
+                    edge_collections += 1;
+                }
+            }
+
+            DatabaseStats {
+                name: database.to_string(),
+                doc_collections,
+                edge_collections,
+                system_collections,
+                accessible: true,
+            }
+        }
+        Err(_) => DatabaseStats {
+            name: database.to_string(),
+            doc_collections: 0,
+            edge_collections: 0,
+            system_collections: 0,
+            accessible: false,
+        },
+    }
+}
+
+#[derive(Clone, Debug)]
+enum BrowserView {
+    DatabaseList,
+    CollectionList(String),                   // database name
+    GraphList(String),                        // database name
+    CollectionProperties(String, String),     // database name, collection name
+    DocumentViewer(String, String),           // database name, collection name
+    DocumentEditor(String, String),           // database name, collection name
+    DocumentDetail(String, String, String),   // database name, collection name, document key
+    CreateCollection(String),                 // database name
+    GraphProperties(String, String),          // database name, graph name
+    AqlQueryInput(String),                    // database name
+    AqlQueryResults(String),                  // database name
+    Indexes(String, String),                  // database name, collection name
+    CreateIndex(String, String),              // database name, collection name
+    EditCollectionProperties(String, String), // database name, collection name
+    SchemaEditor(String, String),             // database name, collection name
+    AqlHistory(String),                       // database name
+    AqlExplain(String),                       // database name
+    QueryMonitor(String),                     // database name
+    ResultDiff(String),                       // database name
+}
+
+#[derive(Clone, Debug)]
+enum GaeView {
+    Graphs,
+    Jobs,
+    LoadGraphInput,
+}
+
+enum InputState {
+    None,
+    EnteringDocumentCount(String),         // Current input string
+    ConfirmDeleteDocument(String),         // Key of the document to delete
+    EditingDocumentFilter(String),         // Filter expression for the document viewer
+    EditingDocumentSort(String),           // Sort attribute for the document viewer
+    EnteringDocumentHandle(String),        // [db:]collection/key or key of the document to open
+    ConfirmTruncateCollection(String),     // Collection name
+    ConfirmDropCollection(String, String), // Collection name, typed confirmation
+    RenamingCollection(String, String),    // Collection name, new name
+    ConfirmDropIndex(String),              // Index id
+    EnteringSchemaSampleSize(String),      // Number of documents to test the schema against
+    SearchingHistory(String),              // Search term for the query history
+    NamingSavedQuery(String),              // Name for the selected query
+    RenamingAqlTab(String),                // New name for the active query tab
+    EnteringCompareAttribute(String),      // Attribute results are matched by
+    EnteringExportPath(export::ExportFormat, String), // Format, file path
+    EnteringExportColumns(String, String), // File path, comma separated CSV columns
+    ConfirmKillQuery(String),              // Query id
+}
+
+// Maximum number of edges fetched per edge collection and direction for a vertex
+const MAX_EDGES_PER_DIRECTION: usize = 100;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum EdgeDirection {
+    Outbound,
+    Inbound,
+}
+
+#[derive(Clone, Debug)]
+struct DocumentEdge {
+    direction: EdgeDirection,
+    edge: serde_json::Value,
+}
+
+fn is_edge_document(document: &serde_json::Value) -> bool {
+    document["_from"].is_string() && document["_to"].is_string()
+}
+
+// Parse `db:collection/key`, `collection/key` or a bare `key` (in the current
+// collection). Keys may contain ':', so a prefix is only taken as a database if
+// it names one of `databases` and a collection follows.
+fn parse_document_handle(
+    input: &str,
+    database: &str,
+    collection: Option<&str>,
+    databases: &[&str],
+) -> Option<(String, String, String)> {
+    let input = input.trim();
+    let (db, handle) = match input.split_once(':') {
+        Some((db, handle)) if databases.contains(&db) && handle.contains('/') => (db, handle),
+        _ => (database, input),
+    };
+    let (coll, key) = match handle.split_once('/') {
+        Some((coll, key)) => (coll, key),
+        None => (collection?, handle),
+    };
+    if coll.is_empty() || key.is_empty() || key.contains('/') {
+        return None;
+    }
+    Some((db.to_string(), coll.to_string(), key.to_string()))
+}
+
+#[derive(Clone, Debug)]
+enum AqlInputField {
+    Query,
+    Parameters,
+    Options,
+    Submit,
+    Explain,
+    Profile,
+}
+
+// Options of the AQL input view. `maxDocuments` limits how many results are
+// held in memory, everything else is passed on to the cursor API.
+#[derive(Clone, Debug)]
+struct AqlQueryOptions {
+    batch_size: usize,
+    stream: bool,
+    max_documents: usize,
+    count: bool,
+    full_count: bool,
+    ttl: Option<f64>,
+    memory_limit: Option<u64>,
+    max_runtime: Option<f64>,
+    profile: Option<u64>,
+    fail_on_warning: Option<bool>,
+    optimizer_rules: Vec<String>,
+    allow_dirty_reads: bool,
+    max_plans: Option<u64>,
+    cache: Option<bool>,
+}
+
+impl Default for AqlQueryOptions {
+    fn default() -> Self {
+        Self {
+            batch_size: 1000,
+            stream: true,
+            max_documents: 10000,
+            count: false,
+            full_count: false,
+            ttl: None,
+            memory_limit: None,
+            max_runtime: None,
+            profile: None,
+            fail_on_warning: None,
+            optimizer_rules: Vec::new(),
+            allow_dirty_reads: false,
+            max_plans: None,
+            cache: None,
+        }
+    }
+}
+
+// JSON Schema of the options JSON of the AQL input view
+fn aql_options_schema() -> serde_json::Value {
+    serde_json::json!({
+        "type": "object",
+        "additionalProperties": false,
+        "properties": {
+            "batchSize": { "type": "integer", "minimum": 1 },
+            "stream": { "type": "boolean" },
+            "maxDocuments": { "type": "integer", "minimum": 1 },
+            "count": { "type": "boolean" },
+            "fullCount": { "type": "boolean" },
+            "ttl": { "type": "number", "minimum": 1 },
+            "memoryLimit": { "type": "integer", "minimum": 0 },
+            "maxRuntime": { "type": "number", "minimum": 0 },
+            "profile": { "type": ["boolean", "integer"], "minimum": 0, "maximum": 2 },
+            "failOnWarning": { "type": "boolean" },
+            "optimizerRules": {
+                "type": "array",
+                "items": { "type": "string", "pattern": "^[+-]" }
+            },
+            "allowDirtyReads": { "type": "boolean" },
+            "maxPlans": { "type": "integer", "minimum": 1 },
+            "cache": { "type": "boolean" }
+        }
+    })
+}
+
+impl AqlQueryOptions {
+    // Parse the options JSON, or describe everything that is wrong with it
+    fn from_json(options: &serde_json::Value) -> Result<Self, Vec<String>> {
+        let violations = schema::validate_document(&aql_options_schema(), options);
+        if !violations.is_empty() {
+            return Err(violations
+                .into_iter()
+                .map(|v| match v.path.as_str() {
+                    "(document)" => v.message,
+                    path => format!("{}: {}", path, v.message),
+                })
+                .collect());
+        }
+
+        let defaults = Self::default();
+        Ok(Self {
+            batch_size: options["batchSize"]
+                .as_u64()
+                .map_or(defaults.batch_size, |n| n as usize),
+            stream: options["stream"].as_bool().unwrap_or(defaults.stream),
+            max_documents: options["maxDocuments"]
+                .as_u64()
+                .map_or(defaults.max_documents, |n| n as usize),
+            count: options["count"].as_bool().unwrap_or(false),
+            full_count: options["fullCount"].as_bool().unwrap_or(false),
+            ttl: options["ttl"].as_f64(),
+            memory_limit: options["memoryLimit"].as_u64(),
+            max_runtime: options["maxRuntime"].as_f64(),
+            // `true` and `false` mean levels 1 and 0
+            profile: options["profile"]
+                .as_u64()
+                .or_else(|| options["profile"].as_bool().map(u64::from)),
+            fail_on_warning: options["failOnWarning"].as_bool(),
+            optimizer_rules: options["optimizerRules"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|rule| rule.as_str().map(|r| r.to_string()))
+                .collect(),
+            allow_dirty_reads: options["allowDirtyReads"].as_bool().unwrap_or(false),
+            max_plans: options["maxPlans"].as_u64(),
+            cache: options["cache"].as_bool(),
+        })
+    }
+}
+
+// Execution plan of a query as shown in the explain view
+struct QueryPlan {
+    rows: Vec<plan::PlanRow>,
+    indexes: Vec<String>,
+    rules: Vec<String>,
+    warnings: Vec<String>,
+    estimated_cost: Option<f64>,
+    estimated_items: Option<u64>,
+    profile: Option<QueryProfile>,
+}
+
+// Measured values of a profiled query run
+struct QueryProfile {
+    node_stats: HashMap<u64, serde_json::Value>, // Per plan node: calls, items, runtime
+    stats: serde_json::Value,
+    phases: Vec<(String, f64)>, // Query phase and its duration in seconds
+}
+
+impl QueryPlan {
+    fn from_explain(response: &serde_json::Value) -> Self {
+        let plan = &response["plan"];
+        Self {
+            rows: plan::plan_rows(plan),
+            indexes: plan::used_indexes(plan),
+            rules: plan["rules"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|r| r.as_str().map(|r| r.to_string()))
+                .collect(),
+            warnings: response["warnings"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .map(|w| format!("{} ({})", w["message"].as_str().unwrap_or("?"), w["code"]))
+                .collect(),
+            estimated_cost: plan["estimatedCost"].as_f64(),
+            estimated_items: plan["estimatedNrItems"].as_u64(),
+            profile: None,
+        }
+    }
+
+    // The plan of a profiled run, annotated with the measured values
+    fn from_profile(extra: &serde_json::Value) -> Self {
+        let mut plan = Self::from_explain(extra);
+        plan.profile = Some(QueryProfile {
+            node_stats: extra["stats"]["nodes"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|n| Some((n["id"].as_u64()?, n.clone())))
+                .collect(),
+            stats: extra["stats"].clone(),
+            phases: extra["profile"]
+                .as_object()
+                .into_iter()
+                .flatten()
+                .filter_map(|(phase, seconds)| Some((phase.clone(), seconds.as_f64()?)))
+                .collect(),
+        });
+        plan
+    }
+}
+
+fn format_bytes(bytes: u64) -> String {
+    match bytes {
+        0..1_024 => format!("{} B", bytes),
+        1_024..1_048_576 => format!("{:.1} KB", bytes as f64 / 1_024.0),
+        1_048_576..1_073_741_824 => format!("{:.1} MB", bytes as f64 / 1_048_576.0),
+        _ => format!("{:.1} GB", bytes as f64 / 1_073_741_824.0),
+    }
+}
+
+// One line summary of the `extra.stats` of a query
+fn query_stats_line(stats: &serde_json::Value) -> Line<'static> {
+    let number = |name: &str| stats[name].as_u64().unwrap_or(0);
+    let label = Style::default().fg(Color::DarkGray);
+    let mut spans = vec![
+        Span::styled("executionTime ", label),
+        Span::raw(format!(
+            "{:.3} s",
+            stats["executionTime"].as_f64().unwrap_or(0.0)
+        )),
+        Span::styled("  scannedFull ", label),
+        Span::raw(number("scannedFull").to_string()),
+        Span::styled("  scannedIndex ", label),
+        Span::raw(number("scannedIndex").to_string()),
+        Span::styled("  filtered ", label),
+        Span::raw(number("filtered").to_string()),
+        Span::styled("  peakMemoryUsage ", label),
+        Span::raw(format_bytes(number("peakMemoryUsage"))),
+    ];
+    if number("writesExecuted") > 0 || number("writesIgnored") > 0 {
+        spans.push(Span::styled("  writes ", label));
+        spans.push(Span::raw(format!(
+            "{} executed, {} ignored",
+            number("writesExecuted"),
+            number("writesIgnored")
+        )));
+    }
+    Line::from(spans)
+}
+
+// Query results put aside to compare later results with
+struct PinnedResults {
+    label: String, // Tab name and number of results
+    results: Vec<serde_json::Value>,
+}
+
+// The pinned results compared with the results of the active query tab
+struct ResultComparison {
+    title: String,
+    attribute: String,
+    diff: compare::ResultDiff,
+    selected: usize,
+    scroll: u16, // Of the document diff
+}
+
+// Names offered by the AQL autocompletion, cached per database
+#[derive(Default)]
+struct AqlCompletionData {
+    collections: Vec<String>,
+    views: Vec<String>,
+    graphs: Vec<String>,
+    attributes: HashMap<String, Vec<String>>, // Sampled attribute names per collection
+}
+
+// Number of documents sampled to suggest the attribute names of a collection
+const COMPLETION_SAMPLE_SIZE: usize = 20;
+
+#[derive(Clone, Debug)]
+struct Completion {
+    text: String,
+    kind: &'static str,
+}
+
+struct CompletionPopup {
+    items: Vec<Completion>,
+    selected: usize,
+    prefix: String, // Partial word before the cursor that an accepted item replaces
+}
+
+struct AqlState {
+    name: String, // Shown in the tab bar
+    query_textarea: TextArea<'static>,
+    parameters_textarea: TextArea<'static>,
+    options_textarea: TextArea<'static>,
+    query_scroll: (usize, usize), // Top row and left column of the highlighted query view
+    completion: Option<CompletionPopup>,
+    active_field: AqlInputField,
+    parameters_valid: bool,
+    options_errors: Vec<String>, // Problems of the options JSON, shown below it
+    param_form: Option<params::ParamForm>, // Parameters entered in a form instead of JSON
+    // Results state; `results` is a window of at most `max_results` (plus a
+    // page) results, starting with result number `offset`
+    results: Vec<serde_json::Value>,
+    offset: usize,
+    max_results: usize,
+    results_database: String, // Database of the results and their cursor
+    results_query: Option<(String, Option<serde_json::Value>, AqlQueryOptions)>, // Query, bind variables and options of the results
+    total_fetched: usize,
+    has_more: bool,
+    cursor_id: Option<String>,
+    current_page: usize,
+    scroll_offset: usize,
+    is_fetching: bool,
+    extra: Option<serde_json::Value>, // `extra` of the last run (stats, warnings)
+    count: Option<u64>,               // Result count, with the `count` option
+    cached: bool,                     // Results came from the query results cache
+    error: Option<ArangoError>,       // Error of the last run, explain or profile
+    table_mode: bool,                 // Show results as a table instead of JSON
+    table: results::ResultTable,
+}
+
+impl AqlState {
+    // Replace the partial word before the cursor with the selected completion
+    fn accept_completion(&mut self) {
+        let Some(popup) = self.completion.take() else {
+            return;
+        };
+        let Some(item) = popup.items.get(popup.selected) else {
+            return;
+        };
+        for _ in 0..popup.prefix.chars().count() {
+            self.query_textarea.delete_char();
+        }
+        self.query_textarea.insert_str(&item.text);
+        if item.kind == "function" {
+            self.query_textarea.insert_char('(');
+        }
+    }
+
+    // Give up the cursor of results not fetched yet, returning its database and id
+    fn take_open_cursor(&mut self) -> Option<(String, String)> {
+        let cursor_id = self.cursor_id.take()?;
+        std::mem::take(&mut self.has_more).then(|| (self.results_database.clone(), cursor_id))
+    }
+
+    // Append a batch, dropping whole pages from the front of the window when it
+    // holds more than `max_results`, so page boundaries stay where they were
+    fn append_results(&mut self, batch: Vec<serde_json::Value>) {
+        self.results.extend(batch);
+        let excess = self.results.len().saturating_sub(self.max_results);
+        let dropped = excess / RESULT_PAGE_SIZE * RESULT_PAGE_SIZE;
+        self.results.drain(..dropped);
+        self.offset += dropped;
+        self.total_fetched = self.offset + self.results.len();
+        self.current_page = self.current_page.max(self.offset / RESULT_PAGE_SIZE);
+        self.table.update(&self.results, dropped);
+    }
+
+    // Whether a key would page past the results in memory while the cursor has more
+    fn needs_more_results(&self, key: KeyCode) -> bool {
+        if !self.has_more || self.is_fetching {
+            return false;
+        }
+        if self.table_mode {
+            let step = match key {
+                KeyCode::Down | KeyCode::Char('j') => 1,
+                KeyCode::PageDown => 20,
+                KeyCode::End => return true,
+                _ => return false,
+            };
+            self.table.selected_row + step >= self.table.order.len()
+        } else {
+            key == KeyCode::Right
+                && (self.current_page + 1) * RESULT_PAGE_SIZE >= self.total_fetched
+        }
+    }
+
+    // Check the options JSON against the options schema
+    fn validate_options(&mut self) {
+        let text = self.options_textarea.lines().join("\n");
+        self.options_errors = match serde_json::from_str(&text) {
+            Ok(options) => AqlQueryOptions::from_json(&options)
+                .err()
+                .unwrap_or_default(),
+            Err(e) => vec![format!("Invalid JSON: {}", e)],
+        };
+    }
+
+    fn set_parameters(&mut self, bind_vars: &serde_json::Map<String, serde_json::Value>) {
+        let text = serde_json::to_string_pretty(bind_vars).unwrap_or_default();
+        self.parameters_textarea = TextArea::from(text.lines().map(|s| s.to_string()));
+        self.parameters_valid = true;
+    }
+
+    // The bind variables, if the parameters are a valid JSON object
+    fn bind_vars_object(&self) -> Option<serde_json::Map<String, serde_json::Value>> {
+        let text = self.parameters_textarea.lines().join("\n");
+        match serde_json::from_str(&text) {
+            Ok(serde_json::Value::Object(bind_vars)) => Some(bind_vars),
+            _ => None,
+        }
+    }
+
+    // Add form fields for the parameters the query references but that are
+    // not bound yet. The JSON editor only flags them, as any value put in as a
+    // placeholder (even null) would be bound.
+    fn sync_bind_parameters(&mut self) {
+        let names = aql::bind_parameters(&self.query_textarea.lines().join("\n"));
+        if let Some(form) = &mut self.param_form {
+            form.add_missing(&names);
+            self.apply_param_form();
+        }
+    }
+
+    // Write the form inputs to the parameters JSON
+    fn apply_param_form(&mut self) {
+        match self.param_form.as_ref().map(|form| form.bind_vars()) {
+            Some(Ok(bind_vars)) => self.set_parameters(&bind_vars),
+            Some(Err(_)) => self.parameters_valid = false,
+            None => {}
+        }
+    }
+
+    // Switch the parameters pane between the JSON editor and the form
+    fn toggle_param_form(&mut self) {
+        if self.param_form.take().is_some() {
+            return;
+        }
+        let Some(bind_vars) = self.bind_vars_object() else {
+            return;
+        };
+        let names = aql::bind_parameters(&self.query_textarea.lines().join("\n"));
+        self.param_form = Some(params::ParamForm::new(&bind_vars, &names));
+        self.sync_bind_parameters();
+    }
+
+    // Sync the parameters before a run and refuse to run with unbound ones
+    fn check_bind_parameters(&mut self) -> bool {
+        self.sync_bind_parameters();
+        let names = aql::bind_parameters(&self.query_textarea.lines().join("\n"));
+        let unbound = self
+            .bind_vars_object()
+            .map(|bind_vars| params::unbound(&bind_vars, &names))
+            .unwrap_or_default();
+        let message = match self.param_form.as_ref().map(|form| form.bind_vars()) {
+            Some(Err(e)) => format!("Invalid bind parameter {}", e),
+            _ if !unbound.is_empty() => {
+                format!("No value for bind parameter @{}", unbound.join(", @"))
+            }
+            _ => return true,
+        };
+        self.error = Some(ArangoError {
+            error_num: None,
+            message,
+        });
+        self.active_field = AqlInputField::Parameters;
+        false
+    }
+}
+
+#[derive(Clone, Debug)]
+enum JsonFormField {
+    JsonInput,
+    Submit,
+}
+
+// A JSON editor with a submit button, used for documents, the create/edit
+// dialogs and the GAE load request
+struct JsonFormState {
+    textarea: TextArea<'static>,
+    json_valid: bool,
+    active_field: JsonFormField,
+    error_message: Option<String>,
+}
+
+impl JsonFormState {
+    fn new(value: &serde_json::Value) -> Self {
+        let json_str = serde_json::to_string_pretty(value).unwrap_or_default();
+        Self {
+            textarea: TextArea::from(json_str.lines().map(|s| s.to_string()).collect::<Vec<_>>()),
+            json_valid: true,
+            active_field: JsonFormField::JsonInput,
+            error_message: None,
+        }
+    }
+
+    fn value(&self) -> Result<serde_json::Value> {
+        serde_json::from_str(&self.textarea.lines().join("\n")).context("Invalid JSON")
+    }
+
+    fn toggle_field(&mut self) {
+        self.active_field = match self.active_field {
+            JsonFormField::JsonInput => JsonFormField::Submit,
+            JsonFormField::Submit => JsonFormField::JsonInput,
+        };
+    }
+
+    fn input(&mut self, key: crossterm::event::KeyEvent) {
+        if matches!(self.active_field, JsonFormField::JsonInput) {
+            self.textarea.input(key);
+
+            // Validate JSON after input
+            let text = self.textarea.lines().join("\n");
+            self.json_valid = serde_json::from_str::<serde_json::Value>(&text).is_ok();
+        }
+    }
+}
+
+struct DocumentEditState {
+    form: JsonFormState,
+    key: Option<String>,                 // None when inserting a new document
+    rev: Option<String>,                 // Revision the edit is based on (If-Match precondition)
+    conflict: Option<serde_json::Value>, // Server version after a 412 response
+}
+
+impl DocumentEditState {
+    fn new(document: Option<&serde_json::Value>) -> Self {
+        let empty = serde_json::json!({});
+        Self {
+            form: JsonFormState::new(document.unwrap_or(&empty)),
+            key: document.and_then(|d| d["_key"].as_str().map(|s| s.to_string())),
+            rev: document.and_then(|d| d["_rev"].as_str().map(|s| s.to_string())),
+            conflict: None,
+        }
+    }
+}
+
+struct GaeBrowser {
+    view: GaeView,
+    graphs: Vec<GaeGraph>,
+    selected_graph_index: usize,
+    jobs: Vec<GaeJob>,
+    selected_job_index: usize,
+    accessible: bool,
+    error_message: Option<String>,
+    load_graph_state: Option<JsonFormState>,
+}
+
+impl GaeBrowser {
+    fn new() -> Self {
+        Self {
+            view: GaeView::Graphs,
+            graphs: Vec::new(),
+            selected_graph_index: 0,
+            jobs: Vec::new(),
+            selected_job_index: 0,
+            accessible: true,
+            error_message: None,
+            load_graph_state: None,
+        }
+    }
+
+    fn init_load_graph_state(&mut self) {
+        let default_json = serde_json::json!({
+            "database": "_system",
+            "vertex_collections": ["V"],
+            "vertex_attributes": [],
+            "vertex_attribute_types": [],
+            "edge_collections": ["E"],
+            "parallelism": 10,
+            "batch_size": 4000000
+        });
+
+        self.load_graph_state = Some(JsonFormState::new(&default_json));
+    }
+
+    async fn load_graphs(&mut self, app_state: &AppState) -> Result<()> {
+        if let Some(ref gae_endpoint) = app_state.gae_endpoint {
+            match get_gae_graphs(&app_state.http_client, gae_endpoint).await {
+                Ok(graphs) => {
+                    self.graphs = graphs;
+                    self.selected_graph_index = 0;
+                    self.accessible = true;
+                    self.error_message = None;
+                    Ok(())
+                }
+                Err(e) => {
+                    self.accessible = false;
+                    self.error_message = Some(format!("Failed to fetch graphs: {}", e));
+                    Err(e)
+                }
+            }
+        } else {
+            self.accessible = false;
+            self.error_message = Some("GAE endpoint not configured".to_string());
+            anyhow::bail!("GAE endpoint not configured")
+        }
+    }
+
+    async fn load_jobs(&mut self, app_state: &AppState) -> Result<()> {
+        if let Some(ref gae_endpoint) = app_state.gae_endpoint {
+            match get_gae_jobs(&app_state.http_client, gae_endpoint).await {
+                Ok(jobs) => {
+                    self.jobs = jobs;
+                    self.selected_job_index = 0;
+                    self.accessible = true;
+                    self.error_message = None;
+                    Ok(())
+                }
+                Err(e) => {
+                    self.accessible = false;
+                    self.error_message = Some(format!("Failed to fetch jobs: {}", e));
+                    Err(e)
+                }
+            }
+        } else {
+            self.accessible = false;
+            self.error_message = Some("GAE endpoint not configured".to_string());
+            anyhow::bail!("GAE endpoint not configured")
+        }
+    }
+}
+
+// Filter expression typed into the document viewer's filter bar
+#[derive(Clone, Debug)]
+struct DocumentFilter {
+    text: String,
+    aql: filter::AqlFilter,
+}
+
+// Sort attribute picked in the document viewer
+#[derive(Clone, Debug)]
+struct DocumentSort {
+    text: String,
+    path: Vec<String>,
+    descending: bool,
+}
+
+impl DocumentSort {
+    // Parse `attribute [asc|desc]`
+    fn parse(input: &str) -> Result<Self> {
+        let trimmed = input.trim();
+        let (attribute, descending) = match trimmed.rsplit_once(char::is_whitespace) {
+            Some((attr, dir)) if dir.eq_ignore_ascii_case("desc") => (attr.trim(), true),
+            Some((attr, dir)) if dir.eq_ignore_ascii_case("asc") => (attr.trim(), false),
+            _ => (trimmed, false),
+        };
+        Ok(Self {
+            text: trimmed.to_string(),
+            path: filter::parse_attribute_path(attribute)?,
+            descending,
+        })
+    }
+}
+
+// Paging state for the document viewer: keyset paging on `_key`, or offset
+// paging when a custom sort order is active
+#[derive(Clone, Debug)]
+struct DocumentPaging {
+    page_size: usize,
+    page_after: Vec<Option<String>>, // Stack of the _key each visited page starts after
+    has_more: bool,
+    total: Option<u64>,
+    filter: Option<DocumentFilter>,
+    sort: Option<DocumentSort>,
+}
+
+impl Default for DocumentPaging {
+    fn default() -> Self {
+        Self {
+            page_size: 100,
+            page_after: vec![None],
+            has_more: false,
+            total: None,
+            filter: None,
+            sort: None,
+        }
+    }
+}
+
+impl DocumentPaging {
+    // Position of the first document of the current page within the collection
+    fn offset(&self) -> usize {
+        (self.page_after.len() - 1) * self.page_size
+    }
+
+    // Build the query for the current page, fetching one extra document to detect more pages
+    fn page_query(&self, collection: &str) -> (String, serde_json::Value) {
+        let mut query = vec!["FOR d IN @@collection".to_string()];
+        let mut bind_vars = serde_json::json!({
+            "@collection": collection,
+            "limit": self.page_size + 1,
+        });
+
+        let after = self.page_after.last().cloned().flatten();
+        if let (None, Some(after)) = (&self.sort, after) {
+            query.push("FILTER d._key > @after".to_string());
+            bind_vars["after"] = serde_json::Value::String(after);
+        }
+
+        if let Some(filter) = &self.filter {
+            query.push(format!("FILTER {}", filter.aql.condition));
+            for (name, value) in &filter.aql.bind_vars {
+                bind_vars[name] = value.clone();
+            }
+        }
+
+        match &self.sort {
+            Some(sort) => {
+                query.push(format!(
+                    "SORT d.@sortAttribute {}, d._key",
+                    if sort.descending { "DESC" } else { "ASC" }
+                ));
+                query.push("LIMIT @offset, @limit".to_string());
+                bind_vars["sortAttribute"] = serde_json::Value::from(sort.path.clone());
+                bind_vars["offset"] = serde_json::Value::from(self.offset());
+            }
+            None => {
+                query.push("SORT d._key".to_string());
+                query.push("LIMIT @limit".to_string());
+            }
+        }
+
+        query.push("RETURN d".to_string());
+        (query.join(" "), bind_vars)
+    }
+
+    // Build the query returning all filtered documents in sort order, for exports
+    fn export_query(&self, collection: &str) -> (String, serde_json::Value) {
+        let mut query = vec!["FOR d IN @@collection".to_string()];
+        let mut bind_vars = serde_json::json!({ "@collection": collection });
+        if let Some(filter) = &self.filter {
+            query.push(format!("FILTER {}", filter.aql.condition));
+            for (name, value) in &filter.aql.bind_vars {
+                bind_vars[name] = value.clone();
+            }
+        }
+        if let Some(sort) = &self.sort {
+            query.push(format!(
+                "SORT d.@sortAttribute {}",
+                if sort.descending { "DESC" } else { "ASC" }
+            ));
+            bind_vars["sortAttribute"] = serde_json::Value::from(sort.path.clone());
+        }
+        query.push("RETURN d".to_string());
+        (query.join(" "), bind_vars)
+    }
+
+    // Build the query counting the filtered documents; None when the collection count applies
+    fn count_query(&self, collection: &str) -> Option<(String, serde_json::Value)> {
+        let filter = self.filter.as_ref()?;
+        let mut bind_vars = serde_json::json!({ "@collection": collection });
+        for (name, value) in &filter.aql.bind_vars {
+            bind_vars[name] = value.clone();
+        }
+        let query = format!(
+            "FOR d IN @@collection FILTER {} COLLECT WITH COUNT INTO n RETURN n",
+            filter.aql.condition
+        );
+        Some((query, bind_vars))
+    }
+
+    fn description(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(filter) = &self.filter {
+            parts.push(format!("filter: {}", filter.text));
+        }
+        if let Some(sort) = &self.sort {
+            parts.push(format!("sort: {}", sort.text));
+        }
+        parts.join(" | ")
+    }
+}
+
+// Fetch one page of documents, returning it together with whether more pages follow
+async fn fetch_document_page(
+    app_state: &AppState,
+    database: &str,
+    collection: &str,
+    paging: &DocumentPaging,
+) -> Result<(Vec<serde_json::Value>, bool)> {
+    let (query, bind_vars) = paging.page_query(collection);
+
+    let response = execute_aql_query_with_params(
+        &app_state.http_client,
+        &app_state.arango_endpoint,
+        database,
+        &query,
+        Some(bind_vars),
+        paging.page_size + 1,
+        true,
+        &app_state.username,
+        &app_state.password,
+    )
+    .await?;
+
+    let mut documents = response.result;
+    let has_more = documents.len() > paging.page_size;
+    documents.truncate(paging.page_size);
+    Ok((documents, has_more))
+}
+
+// Progress of a query running on a background task
+enum QueryEvent {
+    Batch {
+        results: Vec<serde_json::Value>,
+        cursor_id: Option<String>,
+        has_more: bool,
+        extra: Option<serde_json::Value>,
+        count: Option<u64>,
+        cached: bool,
+    },
+    Finished,
+    Failed(anyhow::Error),
+}
+
+// A query started from the AQL input view that is still fetching results
+struct QueryExecution {
+    database: String,
+    events: tokio::sync::mpsc::UnboundedReceiver<QueryEvent>,
+    task: tokio::task::JoinHandle<()>,
+    started: std::time::Instant,
+    cursor_id: Option<String>,
+    marker: Option<String>, // Comment appended to the query, to find it among the running queries
+    record: history::QueryRecord, // Completed when the query ends
+}
+
+// Comment that identifies one execution of a query on the server, e.g.
+// `/* arangotui:18f3a2c41b2e-4d2 */`
+fn query_marker() -> String {
+    use std::sync::atomic::{AtomicU64, Ordering};
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or_default();
+    format!(
+        "/* arangotui:{:x}-{:x}-{:x} */",
+        nanos,
+        std::process::id(),
+        COUNTER.fetch_add(1, Ordering::Relaxed)
+    )
+}
+
+// Run a query on a background task and report its first batch. Later batches
+// are fetched on demand while paging through the results.
+fn spawn_query(
+    app_state: &AppState,
+    database: &str,
+    query: &str,
+    bind_vars: Option<serde_json::Value>,
+    options: AqlQueryOptions,
+) -> (
+    tokio::sync::mpsc::UnboundedReceiver<QueryEvent>,
+    tokio::task::JoinHandle<()>,
+) {
+    let (sender, receiver) = tokio::sync::mpsc::unbounded_channel();
+    let client = app_state.http_client.clone();
+    let endpoint = app_state.arango_endpoint.clone();
+    let username = app_state.username.clone();
+    let password = app_state.password.clone();
+    let database = database.to_string();
+    let query = query.to_string();
+
+    let task = tokio::spawn(async move {
+        let response = match create_cursor(
+            &client, &endpoint, &database, &query, bind_vars, &options, &username, &password,
+        )
+        .await
+        {
+            Ok(response) => response,
+            Err(e) => {
+                let _ = sender.send(QueryEvent::Failed(e));
+                return;
+            }
+        };
+
+        let batch = QueryEvent::Batch {
+            results: response.result,
+            cursor_id: response.id,
+            has_more: response.has_more,
+            extra: response.extra,
+            count: response.count,
+            cached: response.cached,
+        };
+        if sender.send(batch).is_err() {
+            return;
+        }
+        let _ = sender.send(QueryEvent::Finished);
+    });
+    (receiver, task)
+}
+
+// Key hints of the create/edit dialogs
+const FORM_KEYS: &str = "TAB: switch fields | ESC: cancel";
+
+// Results per page of the JSON results view
+const RESULT_PAGE_SIZE: usize = 100;
+
+// Idle time after which the server drops a result cursor, unless the `ttl`
+// option says otherwise; every fetch starts it anew
+const RESULT_CURSOR_TTL: f64 = 600.0;
+
+// Delete a cursor without waiting for the server, e.g. when its results are replaced
+fn spawn_delete_cursor(app_state: &AppState, database: String, cursor_id: String) {
+    let client = app_state.http_client.clone();
+    let endpoint = app_state.arango_endpoint.clone();
+    let username = app_state.username.clone();
+    let password = app_state.password.clone();
+    tokio::spawn(async move {
+        let _ = delete_cursor(
+            &client, &endpoint, &database, &cursor_id, &username, &password,
+        )
+        .await;
+    });
+}
+
+// How often the query monitor reloads its list
+const MONITOR_REFRESH_INTERVAL: std::time::Duration = std::time::Duration::from_secs(3);
+
+// Batch size used when streaming documents into an export file
+const EXPORT_BATCH_SIZE: usize = 1000;
+
+// Write the first batch and the remaining batches of a cursor to an export
+// file; a cursor left open by a failed export is deleted
+async fn export_cursor(
+    app_state: &AppState,
+    database: &str,
+    exporter: &mut export::Exporter,
+    first: AqlQueryResponse,
+) -> Result<()> {
+    let cursor_id = first.id;
+    let mut has_more = first.has_more;
+    let mut written = exporter.write_batch(&first.result);
+    while written.is_ok() && has_more {
+        let Some(cursor) = &cursor_id else {
+            break;
+        };
+        written = match fetch_cursor_next(
+            &app_state.http_client,
+            &app_state.arango_endpoint,
+            database,
+            cursor,
+            &app_state.username,
+            &app_state.password,
+        )
+        .await
+        {
+            Ok(response) => {
+                has_more = response.has_more;
+                exporter.write_batch(&response.result)
+            }
+            Err(e) => Err(e),
+        };
+    }
+    if written.is_err()
+        && has_more
+        && let Some(cursor_id) = cursor_id
+    {
+        spawn_delete_cursor(app_state, database.to_string(), cursor_id);
+    }
+    written
+}
+
+// Outcome of validating a schema against a sample of existing documents
+struct SchemaTestReport {
+    sampled: usize,
+    failures: Vec<(String, Vec<schema::SchemaViolation>)>, // Document key, violations
+}
+
+fn format_count(count: u64) -> String {
+    match count {
+        0..1_000 => count.to_string(),
+        1_000..1_000_000 => format!("{:.1}K", count as f64 / 1_000.0),
+        1_000_000..1_000_000_000 => format!("{:.1}M", count as f64 / 1_000_000.0),
+        _ => format!("{:.1}B", count as f64 / 1_000_000_000.0),
+    }
+}
+
+struct DatabaseBrowser {
+    view: BrowserView,
+    database_stats: Vec<DatabaseStats>,
+    selected_db_index: usize,
+    collections: Vec<CollectionWithCount>,
+    selected_coll_index: usize,
+    graphs: Vec<GraphInfo>,
+    selected_graph_index: usize,
+    collection_details: Option<CollectionCount>,
+    scroll_offset: usize,
+    accessible: bool,
+    input_state: InputState,
+    documents: Vec<serde_json::Value>,
+    selected_doc_index: usize,
+    document_paging: DocumentPaging,
+    document_edit: Option<DocumentEditState>,
+    document_detail: Option<serde_json::Value>,
+    document_edges: Vec<DocumentEdge>, // Edges of the vertex shown in the detail view
+    selected_edge_index: usize,
+    status_message: Option<String>,
+    form_state: Option<JsonFormState>,
+    indexes: Vec<IndexInfo>,
+    selected_index_row: usize,
+    schema_sample_size: usize,
+    schema_report: Option<SchemaTestReport>,
+    completion_cache: HashMap<String, AqlCompletionData>, // Keyed by database name
+    query_history: history::QueryHistory,
+    query_plan: Option<QueryPlan>,
+    selected_plan_row: usize,
+    query_execution: Option<QueryExecution>,
+    monitor_queries: Vec<RunningQueryInfo>,
+    monitor_show_slow: bool,
+    selected_monitor_index: usize,
+    monitor_refreshed: Option<std::time::Instant>,
+    pinned_results: Option<PinnedResults>,
+    result_comparison: Option<ResultComparison>,
+    history_show_saved: bool, // Saved queries instead of the history
+    history_filter: String,
+    selected_history_index: usize,
+    navigation_stack: Vec<(BrowserView, usize)>, // Stack to track navigation history (view, selected_index)
+    graph_details: Option<GraphInfo>,
+    aql_tabs: Vec<AqlState>, // Query tabs, each with its own query and results
+    active_aql_tab: usize,
+}
+
+impl DatabaseBrowser {
+    fn new() -> Self {
+        Self {
+            view: BrowserView::DatabaseList,
+            database_stats: Vec::new(),
+            selected_db_index: 0,
+            collections: Vec::new(),
+            selected_coll_index: 0,
+            graphs: Vec::new(),
+            selected_graph_index: 0,
+            collection_details: None,
+            scroll_offset: 0,
+            accessible: true,
+            input_state: InputState::None,
+            documents: Vec::new(),
+            selected_doc_index: 0,
+            document_paging: DocumentPaging::default(),
+            document_edit: None,
+            document_detail: None,
+            document_edges: Vec::new(),
+            selected_edge_index: 0,
+            status_message: None,
+            form_state: None,
+            indexes: Vec::new(),
+            selected_index_row: 0,
+            schema_sample_size: 100,
+            schema_report: None,
+            completion_cache: HashMap::new(),
+            query_history: history::QueryHistory::load(),
+            query_plan: None,
+            selected_plan_row: 0,
+            query_execution: None,
+            monitor_queries: Vec::new(),
+            monitor_show_slow: false,
+            selected_monitor_index: 0,
+            monitor_refreshed: None,
+            pinned_results: None,
+            result_comparison: None,
+            history_show_saved: false,
+            history_filter: String::new(),
+            selected_history_index: 0,
+            navigation_stack: Vec::new(),
+            graph_details: None,
+            aql_tabs: Vec::new(),
+            active_aql_tab: 0,
+        }
+    }
+
+    // Open a new, empty query tab and make it the active one
+    fn add_aql_tab(&mut self) {
+        let default_options = AqlQueryOptions::default();
+        let options_json = serde_json::json!({
+            "batchSize": default_options.batch_size,
+            "stream": default_options.stream,
+            "maxDocuments": default_options.max_documents,
+        });
+
+        let query_textarea = TextArea::default();
+        let parameters_textarea = TextArea::from(["{}".to_string()]);
+        let options_textarea = TextArea::from(
+            serde_json::to_string_pretty(&options_json)
+                .unwrap_or_default()
+                .lines()
+                .map(|s| s.to_string())
+                .collect::<Vec<_>>(),
+        );
+
+        self.aql_tabs.push(AqlState {
+            name: format!("Query {}", self.aql_tabs.len() + 1),
+            query_textarea,
+            parameters_textarea,
+            options_textarea,
+            query_scroll: (0, 0),
+            completion: None,
+            active_field: AqlInputField::Query,
+            parameters_valid: true,
+            options_errors: Vec::new(),
+            param_form: None,
+            results: Vec::new(),
+            offset: 0,
+            max_results: default_options.max_documents,
+            results_database: String::new(),
+            results_query: None,
+            total_fetched: 0,
+            has_more: false,
+            cursor_id: None,
+            current_page: 0,
+            scroll_offset: 0,
+            is_fetching: false,
+            extra: None,
+            count: None,
+            cached: false,
+            error: None,
+            table_mode: false,
+            table: results::ResultTable::new(&[]),
+        });
+        self.active_aql_tab = self.aql_tabs.len() - 1;
+    }
+
+    // Completion data of a database, fetched on first use. Failures leave lists empty
+    // rather than interrupting typing.
+    async fn completion_data(
+        &mut self,
+        app_state: &AppState,
+        database: &str,
+    ) -> &mut AqlCompletionData {
+        if !self.completion_cache.contains_key(database) {
+            let collections = get_collections(
+                &app_state.http_client,
+                &app_state.arango_endpoint,
+                database,
+                &app_state.username,
+                &app_state.password,
+            )
+            .await
+            .unwrap_or_default();
+            let views = get_views(
+                &app_state.http_client,
+                &app_state.arango_endpoint,
+                database,
+                &app_state.username,
+                &app_state.password,
+            )
+            .await
+            .unwrap_or_default();
+            let graphs = get_graphs(
+                &app_state.http_client,
+                &app_state.arango_endpoint,
+                database,
+                &app_state.username,
+                &app_state.password,
+            )
+            .await
+            .unwrap_or_default();
+
+            self.completion_cache.insert(
+                database.to_string(),
+                AqlCompletionData {
+                    collections: collections.into_iter().map(|c| c.name).collect(),
+                    views: views.into_iter().map(|v| v.name).collect(),
+                    graphs: graphs.into_iter().map(|g| g.name).collect(),
+                    attributes: HashMap::new(),
+                },
+            );
+        }
+        self.completion_cache
+            .entry(database.to_string())
+            .or_default()
+    }
+
+    // Attribute names of a collection, sampled from its first documents
+    async fn completion_attributes(
+        &mut self,
+        app_state: &AppState,
+        database: &str,
+        collection: &str,
+    ) -> Vec<String> {
+        if let Some(attributes) = self
+            .completion_data(app_state, database)
+            .await
+            .attributes
+            .get(collection)
+        {
+            return attributes.clone();
+        }
+
+        let paging = DocumentPaging {
+            page_size: COMPLETION_SAMPLE_SIZE,
+            ..DocumentPaging::default()
+        };
+        let mut attributes: Vec<String> = Vec::new();
+        if let Ok((sample, _)) = fetch_document_page(app_state, database, collection, &paging).await
+        {
+            for document in &sample {
+                for name in document.as_object().into_iter().flat_map(|o| o.keys()) {
+                    if !attributes.contains(name) {
+                        attributes.push(name.clone());
+                    }
+                }
+            }
+        }
+        attributes.sort();
+
+        self.completion_data(app_state, database)
+            .await
+            .attributes
+            .insert(collection.to_string(), attributes.clone());
+        attributes
+    }
+
+    // Recompute the completion popup for the text before the cursor. Without `forced`
+    // the popup only opens once part of a word (or an attribute access) was typed.
+    async fn update_aql_completion(&mut self, app_state: &AppState, database: &str, forced: bool) {
+        let Some(aql_state) = self.aql_tabs.get(self.active_aql_tab) else {
+            return;
+        };
+        let (row, col) = aql_state.query_textarea.cursor();
+        let lines = aql_state.query_textarea.lines();
+        let mut before_cursor = lines[..row].join("\n");
+        if row > 0 {
+            before_cursor.push('\n');
+        }
+        before_cursor.extend(lines[row].chars().take(col));
+        let query = lines.join("\n");
+
+        let Some((context, prefix)) = aql::completion_context(&before_cursor) else {
+            self.close_aql_completion();
+            return;
+        };
+        if prefix.is_empty() && !forced && !matches!(context, aql::CompletionContext::Attribute(_))
+        {
+            self.close_aql_completion();
+            return;
+        }
+
+        let mut candidates: Vec<Completion> = Vec::new();
+        let mut add = |names: &[String], kind: &'static str| {
+            candidates.extend(names.iter().map(|name| Completion {
+                text: name.clone(),
+                kind,
+            }));
+        };
+        match &context {
+            aql::CompletionContext::Attribute(variable) => {
+                if let Some(collection) = aql::variable_collection(&query, variable) {
+                    let attributes = self
+                        .completion_attributes(app_state, database, &collection)
+                        .await;
+                    add(&attributes, "attribute");
+                }
+            }
+            aql::CompletionContext::Collection => {
+                let data = self.completion_data(app_state, database).await;
+                add(&data.collections, "collection");
+                add(&data.views, "view");
+            }
+            aql::CompletionContext::Graph => {
+                let data = self.completion_data(app_state, database).await;
+                let quoted: Vec<String> =
+                    data.graphs.iter().map(|g| format!("\"{}\"", g)).collect();
+                add(&quoted, "graph");
+            }
+            aql::CompletionContext::General => {
+                let variables = aql::declared_variables(&query);
+                add(&variables, "variable");
+                let keywords: Vec<String> = aql::KEYWORDS.iter().map(|k| k.to_string()).collect();
+                add(&keywords, "keyword");
+                let functions: Vec<String> = aql::FUNCTIONS.iter().map(|f| f.to_string()).collect();
+                add(&functions, "function");
+            }
+        }
+
+        let lower_prefix = prefix.to_lowercase();
+        let items: Vec<Completion> = candidates
+            .into_iter()
+            .filter(|c| {
+                let text = c.text.trim_start_matches('"').to_lowercase();
+                text.starts_with(&lower_prefix) && text != lower_prefix
+            })
+            .collect();
+
+        if let Some(aql_state) = self.aql_tabs.get_mut(self.active_aql_tab) {
+            aql_state.completion = if items.is_empty() {
+                None
+            } else {
+                Some(CompletionPopup {
+                    items,
+                    selected: 0,
+                    prefix,
+                })
+            };
+        }
+    }
+
+    fn close_aql_completion(&mut self) {
+        if let Some(aql_state) = self.aql_tabs.get_mut(self.active_aql_tab) {
+            aql_state.completion = None;
+        }
+    }
+
+    // Activate the query tab `delta` positions away, wrapping around
+    fn switch_aql_tab(&mut self, delta: isize) {
+        let count = self.aql_tabs.len() as isize;
+        if count > 0 {
+            self.active_aql_tab = (self.active_aql_tab as isize + delta).rem_euclid(count) as usize;
+        }
+    }
+
+    // Close the active query tab; the last tab is replaced by an empty one
+    fn close_aql_tab(&mut self, app_state: &AppState) {
+        if self.active_aql_tab < self.aql_tabs.len() {
+            let mut tab = self.aql_tabs.remove(self.active_aql_tab);
+            if let Some((database, cursor_id)) = tab.take_open_cursor() {
+                spawn_delete_cursor(app_state, database, cursor_id);
+            }
+        }
+        if self.aql_tabs.is_empty() {
+            self.add_aql_tab();
+        }
+        self.active_aql_tab = self.active_aql_tab.min(self.aql_tabs.len() - 1);
+    }
+
+    // Remember the query tabs for the next launch (see --restore-tabs)
+    fn save_aql_tabs(&mut self) {
+        let tabs: Vec<history::QueryTab> = self
+            .aql_tabs
+            .iter()
+            .map(|tab| history::QueryTab {
+                name: tab.name.clone(),
+                query: tab.query_textarea.lines().join("\n"),
+                bind_vars: tab.parameters_textarea.lines().join("\n"),
+                options: tab.options_textarea.lines().join("\n"),
+            })
+            .collect();
+        if let Err(e) = self.query_history.save_tabs(&tabs) {
+            self.status_message = Some(format!("Query tabs not saved: {}", e));
+        }
+    }
+
+    fn restore_aql_tabs(&mut self) {
+        let to_textarea = |text: &str| TextArea::from(text.lines().map(|s| s.to_string()));
+        let is_json = |text: &str| serde_json::from_str::<serde_json::Value>(text).is_ok();
+        for saved in self.query_history.load_tabs() {
+            self.add_aql_tab();
+            if let Some(tab) = self.aql_tabs.last_mut() {
+                tab.name = saved.name;
+                tab.query_textarea = to_textarea(&saved.query);
+                tab.parameters_textarea = to_textarea(&saved.bind_vars);
+                tab.options_textarea = to_textarea(&saved.options);
+                tab.parameters_valid = is_json(&saved.bind_vars);
+                tab.validate_options();
+            }
+        }
+        self.active_aql_tab = 0;
+    }
+
+    // Start the query from the AQL input view on a background task
+    fn execute_aql_input(&mut self, app_state: &AppState, database: &str) {
+        if self.query_execution.is_some() {
+            return;
+        }
+        let Some(aql_state) = self.aql_tabs.get_mut(self.active_aql_tab) else {
+            return;
+        };
+        if !aql_state.check_bind_parameters()
+            || !aql_state.parameters_valid
+            || !aql_state.options_errors.is_empty()
+        {
+            return;
+        }
+
+        let query_text = aql_state.query_textarea.lines().join("\n");
+        let params_text = aql_state.parameters_textarea.lines().join("\n");
+        let options_text = aql_state.options_textarea.lines().join("\n");
+
+        // Parse parameters
+        let bind_vars: Option<serde_json::Value> = if params_text.trim() == "{}" {
+            None
+        } else {
+            serde_json::from_str(&params_text).ok()
+        };
+
+        // Parse options
+        let Ok(opts) = serde_json::from_str::<serde_json::Value>(&options_text) else {
+            return;
+        };
+        let Ok(mut options) = AqlQueryOptions::from_json(&opts) else {
+            return;
+        };
+        // Keep the cursor around while the results are paged through
+        options.ttl.get_or_insert(RESULT_CURSOR_TTL);
+        if let Some((cursor_database, cursor_id)) = aql_state.take_open_cursor() {
+            spawn_delete_cursor(app_state, cursor_database, cursor_id);
+        }
+
+        aql_state.is_fetching = true;
+        aql_state.results.clear();
+        aql_state.offset = 0;
+        aql_state.max_results = options.max_documents;
+        aql_state.results_database = database.to_string();
+        aql_state.results_query = Some((query_text.clone(), bind_vars.clone(), options.clone()));
+        aql_state.current_page = 0;
+        aql_state.scroll_offset = 0;
+        aql_state.total_fetched = 0;
+        aql_state.has_more = false;
+        aql_state.cursor_id = None;
+        aql_state.extra = None;
+        aql_state.count = None;
+        aql_state.cached = false;
+        aql_state.error = None;
+        self.view = BrowserView::AqlQueryResults(database.to_string());
+
+        // The marker makes the query text unique, which rules out the query
+        // results cache; with `cache: true` the text is sent unchanged instead
+        let marker = (options.cache != Some(true)).then(query_marker);
+        let executed_query = match &marker {
+            // On its own line at the end, so error positions stay the same
+            Some(marker) => format!("{}\n{}", query_text, marker),
+            None => query_text.clone(),
+        };
+        let (events, task) = spawn_query(
+            app_state,
+            database,
+            &executed_query,
+            bind_vars.clone(),
+            options,
+        );
+        self.query_execution = Some(QueryExecution {
+            database: database.to_string(),
+            events,
+            task,
+            started: std::time::Instant::now(),
+            cursor_id: None,
+            marker,
+            record: history::QueryRecord {
+                query: query_text,
+                bind_vars: bind_vars.unwrap_or_else(|| serde_json::json!({})),
+                options: opts,
+                database: database.to_string(),
+                timestamp: history::now_timestamp(),
+                duration_ms: 0,
+                result_count: 0,
+                partial: false,
+                error: None,
+                name: None,
+            },
+        });
+    }
+
+    // Fetch the next batch of the active tab's result cursor
+    async fn fetch_more_results(&mut self, app_state: &AppState) {
+        let Some(aql_state) = self.aql_tabs.get_mut(self.active_aql_tab) else {
+            return;
+        };
+        let Some(cursor_id) = aql_state.cursor_id.clone() else {
+            return;
+        };
+        match fetch_cursor_next(
+            &app_state.http_client,
+            &app_state.arango_endpoint,
+            &aql_state.results_database,
+            &cursor_id,
+            &app_state.username,
+            &app_state.password,
+        )
+        .await
+        {
+            Ok(next) => {
+                aql_state.has_more = next.has_more;
+                aql_state.cursor_id = next.id;
+                if next.extra.is_some() {
+                    aql_state.extra = next.extra;
+                }
+                aql_state.error = None;
+                aql_state.append_results(next.result);
+            }
+            // E.g. the cursor expired; the results in memory stay browsable
+            Err(e) => aql_state.error = Some(ArangoError::from_error(&e)),
+        }
+    }
+
+    // Delete the cursors of all query tabs, e.g. before quitting
+    async fn delete_result_cursors(&mut self, app_state: &AppState) {
+        for aql_state in &mut self.aql_tabs {
+            if let Some((database, cursor_id)) = aql_state.take_open_cursor() {
+                let _ = delete_cursor(
+                    &app_state.http_client,
+                    &app_state.arango_endpoint,
+                    &database,
+                    &cursor_id,
+                    &app_state.username,
+                    &app_state.password,
+                )
+                .await;
+            }
+        }
+    }
+
+    // Apply the batches a running query has delivered since the last call
+    fn poll_query_execution(&mut self) {
+        let Some(execution) = &mut self.query_execution else {
+            return;
+        };
+        let Some(aql_state) = self.aql_tabs.get_mut(self.active_aql_tab) else {
+            return;
+        };
+
+        let mut outcome = None;
+        loop {
+            match execution.events.try_recv() {
+                Ok(QueryEvent::Batch {
+                    results,
+                    cursor_id,
+                    has_more,
+                    extra,
+                    count,
+                    cached,
+                }) => {
+                    aql_state.append_results(results);
+                    aql_state.has_more = has_more;
+                    aql_state.cursor_id = cursor_id.clone();
+                    if extra.is_some() {
+                        aql_state.extra = extra;
+                    }
+                    aql_state.count = count;
+                    aql_state.cached = cached;
+                    execution.cursor_id = cursor_id;
+                }
+                Ok(QueryEvent::Finished) => {
+                    outcome = Some(Ok(()));
+                    break;
+                }
+                Ok(QueryEvent::Failed(e)) => {
+                    outcome = Some(Err(e));
+                    break;
+                }
+                Err(tokio::sync::mpsc::error::TryRecvError::Empty) => break,
+                Err(tokio::sync::mpsc::error::TryRecvError::Disconnected) => {
+                    outcome = Some(Err(anyhow::anyhow!("Query task ended unexpectedly")));
+                    break;
+                }
+            }
+        }
+
+        if let Some(outcome) = outcome {
+            self.finish_query_execution(outcome);
+        }
+    }
+
+    // Show the outcome of a finished, failed or cancelled query and record it in the history
+    fn finish_query_execution(&mut self, outcome: Result<()>) {
+        let Some(execution) = self.query_execution.take() else {
+            return;
+        };
+        let mut record = execution.record;
+        record.duration_ms = execution.started.elapsed().as_millis() as u64;
+
+        if let Some(aql_state) = self.aql_tabs.get_mut(self.active_aql_tab) {
+            aql_state.is_fetching = false;
+            aql_state.table = results::ResultTable::new(&aql_state.results);
+            // Later batches are fetched on demand, so prefer the server's count
+            record.result_count = aql_state
+                .count
+                .map_or(aql_state.results.len(), |n| n as usize);
+            record.partial = aql_state.has_more && aql_state.count.is_none();
+        }
+        if let Err(e) = outcome {
+            record.error = Some(format!("{:#}", e));
+            if record.result_count == 0 {
+                // Nothing to show - go back to input and show the error there
+                self.view = BrowserView::AqlQueryInput(execution.database);
+                self.show_aql_error(&e);
+            } else if let Some(aql_state) = self.aql_tabs.get_mut(self.active_aql_tab) {
+                // Keep the results fetched so far and show the error with them
+                aql_state.error = Some(ArangoError::from_error(&e));
+            }
+        }
+
+        if let Err(e) = self.query_history.record(record) {
+            self.status_message = Some(format!("Query history not saved: {}", e));
+        }
+    }
+
+    // Stop a running query: end the task, delete its cursor and kill it on the server
+    async fn cancel_query_execution(&mut self, app_state: &AppState) {
+        let Some(execution) = &self.query_execution else {
+            return;
+        };
+        execution.task.abort();
+        let database = execution.database.clone();
+        let cursor_id = execution.cursor_id.clone();
+        let marker = execution.marker.clone();
+
+        let mut problems = Vec::new();
+        if let Some(cursor_id) = &cursor_id {
+            if let Err(e) = delete_cursor(
+                &app_state.http_client,
+                &app_state.arango_endpoint,
+                &database,
+                cursor_id,
+                &app_state.username,
+                &app_state.password,
+            )
+            .await
+            {
+                problems.push(e.to_string());
+            }
+        } else if let Some(marker) = &marker {
+            // A query still computing its first batch has no cursor yet, so
+            // find it by the marker comment unique to this execution
+            match get_running_queries(
+                &app_state.http_client,
+                &app_state.arango_endpoint,
+                &database,
+                &app_state.username,
+                &app_state.password,
+            )
+            .await
+            {
+                Ok(running) => {
+                    for info in running.iter().filter(|q| q.query.contains(marker)) {
+                        if let Err(e) = kill_query(
+                            &app_state.http_client,
+                            &app_state.arango_endpoint,
+                            &database,
+                            &info.id,
+                            &app_state.username,
+                            &app_state.password,
+                        )
+                        .await
+                        {
+                            problems.push(e.to_string());
+                        }
+                    }
+                }
+                Err(e) => problems.push(e.to_string()),
+            }
+        } else {
+            problems.push("without a marker the query may still run on the server".to_string());
+        }
+
+        if let Some(aql_state) = self.aql_tabs.get_mut(self.active_aql_tab) {
+            aql_state.cursor_id = None;
+            aql_state.has_more = false;
+        }
+        self.finish_query_execution(Err(anyhow::anyhow!("Query cancelled")));
+        self.status_message = Some(if problems.is_empty() {
+            "Query cancelled".to_string()
+        } else {
+            format!("Query cancelled: {}", problems.join("; "))
+        });
+    }
+
+    // Query text and bind parameters of the AQL input view, None if the parameters are invalid
+    fn aql_input_query(&mut self) -> Option<(String, Option<serde_json::Value>)> {
+        let aql_state = self.aql_tabs.get_mut(self.active_aql_tab)?;
+        if !aql_state.check_bind_parameters() || !aql_state.parameters_valid {
+            return None;
+        }
+        let query_text = aql_state.query_textarea.lines().join("\n");
+        let params_text = aql_state.parameters_textarea.lines().join("\n");
+        let bind_vars = if params_text.trim() == "{}" {
+            None
+        } else {
+            serde_json::from_str(&params_text).ok()
+        };
+        Some((query_text, bind_vars))
+    }
+
+    // Explain the query from the AQL input view without running it
+    async fn explain_aql_input(&mut self, app_state: &AppState, database: &str) {
+        let Some((query_text, bind_vars)) = self.aql_input_query() else {
+            return;
+        };
+
+        match explain_aql_query(
+            &app_state.http_client,
+            &app_state.arango_endpoint,
+            database,
+            &query_text,
+            bind_vars,
+            &app_state.username,
+            &app_state.password,
+        )
+        .await
+        {
+            Ok(response) => {
+                if let Some(aql_state) = self.aql_tabs.get_mut(self.active_aql_tab) {
+                    aql_state.error = None;
+                }
+                self.query_plan = Some(QueryPlan::from_explain(&response));
+                self.selected_plan_row = 0;
+                self.view = BrowserView::AqlExplain(database.to_string());
+            }
+            Err(e) => self.show_aql_error(&e),
+        }
+    }
+
+    // Show a failed query in the error panel of the AQL input view, with the
+    // cursor moved to the position of a parse error
+    fn show_aql_error(&mut self, e: &anyhow::Error) {
+        let Some(aql_state) = self.aql_tabs.get_mut(self.active_aql_tab) else {
+            return;
+        };
+        let error = ArangoError::from_error(e);
+        if let Some((line, column)) = aql::error_position(&error.message) {
+            aql_state.query_textarea.move_cursor(CursorMove::Jump(
+                line.saturating_sub(1) as u16,
+                column.saturating_sub(1) as u16,
+            ));
+            aql_state.active_field = AqlInputField::Query;
+        }
+        aql_state.error = Some(error);
+    }
+
+    // Run the query from the AQL input view with profiling and show the annotated plan
+    async fn profile_aql_input(&mut self, app_state: &AppState, database: &str) {
+        let Some((query_text, bind_vars)) = self.aql_input_query() else {
+            return;
+        };
+
+        match profile_aql_query(
+            &app_state.http_client,
+            &app_state.arango_endpoint,
+            database,
+            &query_text,
+            bind_vars,
+            &app_state.username,
+            &app_state.password,
+        )
+        .await
+        {
+            Ok(response) => {
+                let extra = response.extra.unwrap_or_default();
+                if let Some(aql_state) = self.aql_tabs.get_mut(self.active_aql_tab) {
+                    aql_state.error = None;
+                }
+                self.query_plan = Some(QueryPlan::from_profile(&extra));
+                self.selected_plan_row = 0;
+                self.view = BrowserView::AqlExplain(database.to_string());
+            }
+            Err(e) => self.show_aql_error(&e),
+        }
+    }
+
+    fn open_query_history(&mut self, database: &str) {
+        self.history_filter.clear();
+        self.selected_history_index = 0;
+        self.view = BrowserView::AqlHistory(database.to_string());
+    }
+
+    // History (newest first) or saved queries, narrowed down by the search term
+    fn visible_history(&self) -> Vec<&history::QueryRecord> {
+        let records: Vec<&history::QueryRecord> = if self.history_show_saved {
+            self.query_history.saved.iter().collect()
+        } else {
+            self.query_history.entries.iter().rev().collect()
+        };
+        records
+            .into_iter()
+            .filter(|r| self.history_filter.is_empty() || r.matches(&self.history_filter))
+            .collect()
+    }
+
+    // Put a query from the history back into the AQL input view
+    fn load_history_entry(&mut self, record: &history::QueryRecord) {
+        self.load_query(&record.query, &record.bind_vars, Some(&record.options));
+    }
+
+    // Put a query into the AQL input view; without options the current ones stay
+    fn load_query(
+        &mut self,
+        query: &str,
+        bind_vars: &serde_json::Value,
+        options: Option<&serde_json::Value>,
+    ) {
+        if self.aql_tabs.is_empty() {
+            self.add_aql_tab();
+        }
+        let Some(aql_state) = self.aql_tabs.get_mut(self.active_aql_tab) else {
+            return;
+        };
+        let to_textarea = |value: &serde_json::Value| {
+            TextArea::from(
+                serde_json::to_string_pretty(value)
+                    .unwrap_or_default()
+                    .lines()
+                    .map(|s| s.to_string())
+                    .collect::<Vec<_>>(),
+            )
+        };
+
+        aql_state.query_textarea =
+            TextArea::from(query.lines().map(|s| s.to_string()).collect::<Vec<_>>());
+        aql_state.parameters_textarea = if bind_vars.is_object() {
+            to_textarea(bind_vars)
+        } else {
+            TextArea::from(vec!["{}".to_string()])
+        };
+        aql_state.parameters_valid = true;
+        aql_state.param_form = None;
+        if let Some(options) = options {
+            aql_state.options_textarea = to_textarea(options);
+            aql_state.validate_options();
+        }
+        aql_state.query_scroll = (0, 0);
+        aql_state.completion = None;
+        aql_state.active_field = AqlInputField::Query;
+    }
+
+    // Put the results of the active tab aside to compare later results with
+    fn pin_results(&mut self) {
+        let Some(aql_state) = self.aql_tabs.get(self.active_aql_tab) else {
+            return;
+        };
+        let label = format!("{} ({} results)", aql_state.name, aql_state.results.len());
+        let partial = if aql_state.offset > 0 || aql_state.has_more {
+            " (only the results in memory)"
+        } else {
+            ""
+        };
+        self.status_message = Some(format!(
+            "Pinned {}{} - press C on other results to compare",
+            label, partial
+        ));
+        self.pinned_results = Some(PinnedResults {
+            label,
+            results: aql_state.results.clone(),
+        });
+    }
+
+    fn compare_results(&mut self, attribute: String) {
+        let (Some(pinned), Some(aql_state)) =
+            (&self.pinned_results, self.aql_tabs.get(self.active_aql_tab))
+        else {
+            return;
+        };
+        self.result_comparison = Some(ResultComparison {
+            title: format!(
+                "{} → {} ({} results)",
+                pinned.label,
+                aql_state.name,
+                aql_state.results.len()
+            ),
+            diff: compare::compare(&pinned.results, &aql_state.results, &attribute),
+            attribute,
+            selected: 0,
+            scroll: 0,
+        });
+        if let BrowserView::AqlQueryResults(db) = self.view.clone() {
+            self.view = BrowserView::ResultDiff(db);
+        }
+    }
+
+    fn open_query_monitor(&mut self, database: &str) {
+        self.monitor_queries.clear();
+        self.selected_monitor_index = 0;
+        self.monitor_refreshed = None;
+        self.view = BrowserView::QueryMonitor(database.to_string());
+    }
+
+    fn query_monitor_due(&self) -> bool {
+        self.monitor_refreshed
+            .is_none_or(|t| t.elapsed() >= MONITOR_REFRESH_INTERVAL)
+    }
+
+    // Reload the running queries or the slow query log
+    async fn refresh_query_monitor(&mut self, app_state: &AppState, database: &str) {
+        self.monitor_refreshed = Some(std::time::Instant::now());
+        let queries = if self.monitor_show_slow {
+            get_slow_queries(
+                &app_state.http_client,
+                &app_state.arango_endpoint,
+                database,
+                &app_state.username,
+                &app_state.password,
+            )
+            .await
+        } else {
+            get_running_queries(
+                &app_state.http_client,
+                &app_state.arango_endpoint,
+                database,
+                &app_state.username,
+                &app_state.password,
+            )
+            .await
+        };
+        match queries {
+            Ok(mut queries) => {
+                // Longest running first
+                queries.sort_by(|a, b| b.run_time.total_cmp(&a.run_time));
+                // Keep the selection on the same query while the list changes
+                let selected_id = self
+                    .monitor_queries
+                    .get(self.selected_monitor_index)
+                    .map(|q| q.id.clone());
+                self.selected_monitor_index = selected_id
+                    .and_then(|id| queries.iter().position(|q| q.id == id))
+                    .unwrap_or(self.selected_monitor_index)
+                    .min(queries.len().saturating_sub(1));
+                self.monitor_queries = queries;
+            }
+            Err(e) => self.status_message = Some(e.to_string()),
+        }
+    }
+
+    async fn kill_monitored_query(&mut self, app_state: &AppState, database: &str, id: &str) {
+        match kill_query(
+            &app_state.http_client,
+            &app_state.arango_endpoint,
+            database,
+            id,
+            &app_state.username,
+            &app_state.password,
+        )
+        .await
+        {
+            Ok(()) => self.status_message = Some(format!("Query {} killed", id)),
+            Err(e) => self.status_message = Some(e.to_string()),
+        }
+        self.refresh_query_monitor(app_state, database).await;
+    }
+
+    fn database_names(&self) -> Vec<&str> {
+        self.database_stats
+            .iter()
+            .map(|d| d.name.as_str())
+            .collect()
+    }
+
+    async fn load_databases(&mut self, app_state: &AppState) -> Result<()> {
+        match get_databases(
+            &app_state.http_client,
+            &app_state.arango_endpoint,
+            &app_state.username,
+            &app_state.password,
+        )
+        .await
+        {
+            Ok(databases) => {
+                self.accessible = true;
+                let mut stats = Vec::new();
+                for db in databases {
+                    let db_stats = get_database_stats(
+                        &app_state.http_client,
+                        &app_state.arango_endpoint,
+                        &db,
+                        &app_state.username,
+                        &app_state.password,
+                    )
+                    .await;
+                    stats.push(db_stats);
+                }
+                self.database_stats = stats;
+                self.selected_db_index = 0;
+                Ok(())
+            }
+            Err(_) => {
+                self.accessible = false;
+                Ok(())
+            }
+        }
+    }
+
+    async fn load_collections(&mut self, app_state: &AppState, database: &str) -> Result<()> {
+        // Collections may have been created, dropped or renamed
+        self.completion_cache.remove(database);
+        let collections = get_collections(
+            &app_state.http_client,
+            &app_state.arango_endpoint,
+            database,
+            &app_state.username,
+            &app_state.password,
+        )
+        .await?;
+
+        let mut collections_with_count = Vec::new();
+        for coll in collections {
+            let count = get_collection_count(
+                &app_state.http_client,
+                &app_state.arango_endpoint,
+                database,
+                &coll.name,
+                &app_state.username,
+                &app_state.password,
+            )
+            .await
+            .ok()
+            .map(|c| c.count);
+
+            collections_with_count.push(CollectionWithCount { info: coll, count });
+        }
+
+        // Sort: non-system first (alphabetically), then system collections (alphabetically)
+        collections_with_count.sort_by(|a, b| match (a.info.is_system, b.info.is_system) {
+            (false, true) => std::cmp::Ordering::Less,
+            (true, false) => std::cmp::Ordering::Greater,
+            _ => a.info.name.cmp(&b.info.name),
+        });
+
+        self.collections = collections_with_count;
+        self.selected_coll_index = 0;
+        self.scroll_offset = 0;
+        Ok(())
+    }
+
+    // Reload the collection list, keeping the named collection (or the current row) selected
+    async fn reload_collections(
+        &mut self,
+        app_state: &AppState,
+        database: &str,
+        select: Option<&str>,
+    ) -> Result<()> {
+        let previous_index = self.selected_coll_index;
+        self.load_collections(app_state, database).await?;
+        self.selected_coll_index = select
+            .and_then(|name| self.collections.iter().position(|c| c.info.name == name))
+            .unwrap_or_else(|| previous_index.min(self.collections.len().saturating_sub(1)));
+        Ok(())
+    }
+
+    fn selected_collection(&self) -> Option<&CollectionInfo> {
+        self.collections
+            .get(self.selected_coll_index)
+            .map(|c| &c.info)
+    }
+
+    async fn submit_create_collection(
+        &mut self,
+        app_state: &AppState,
+        database: &str,
+    ) -> Result<()> {
+        let Some(form) = self.form_state.as_mut() else {
+            return Ok(());
+        };
+
+        let mut properties = match form.value() {
+            Ok(value) => value,
+            Err(e) => {
+                form.error_message = Some(e.to_string());
+                return Ok(());
+            }
+        };
+
+        // Accept "document"/"edge" in the form and map to the numeric collection type
+        match properties["type"].as_str() {
+            Some("document") => properties["type"] = serde_json::json!(2),
+            Some("edge") => properties["type"] = serde_json::json!(3),
+            Some(other) => {
+                form.error_message = Some(format!(
+                    "Unknown collection type '{}', use \"document\" or \"edge\"",
+                    other
+                ));
+                return Ok(());
+            }
+            None => {}
+        }
+        let name = properties["name"].as_str().unwrap_or_default().to_string();
+
+        match create_collection(
+            &app_state.http_client,
+            &app_state.arango_endpoint,
+            database,
+            &properties,
+            &app_state.username,
+            &app_state.password,
+        )
+        .await
+        {
+            Ok(()) => {
+                self.form_state = None;
+                self.view = BrowserView::CollectionList(database.to_string());
+                self.status_message = Some(
+                    match self
+                        .reload_collections(app_state, database, Some(&name))
+                        .await
+                    {
+                        Ok(()) => format!("Collection {} created", name),
+                        Err(e) => format!("Collection {} created; reloading failed: {}", name, e),
+                    },
+                );
+            }
+            Err(e) => form.error_message = Some(e.to_string()),
         }
+        Ok(())
     }
 
-    fn init_load_graph_state(&mut self) {
-        let default_json = serde_json::json!({
-            "database": "_system",
-            "vertex_collections": ["V"],
-            "vertex_attributes": [],
-            "vertex_attribute_types": [],
-            "edge_collections": ["E"],
-            "parallelism": 10,
-            "batch_size": 4000000
-        });
+    async fn load_indexes(
+        &mut self,
+        app_state: &AppState,
+        database: &str,
+        collection: &str,
+    ) -> Result<()> {
+        self.indexes = get_indexes(
+            &app_state.http_client,
+            &app_state.arango_endpoint,
+            database,
+            collection,
+            &app_state.username,
+            &app_state.password,
+        )
+        .await?;
+        self.selected_index_row = self
+            .selected_index_row
+            .min(self.indexes.len().saturating_sub(1));
+        Ok(())
+    }
+
+    async fn submit_create_index(
+        &mut self,
+        app_state: &AppState,
+        database: &str,
+        collection: &str,
+    ) -> Result<()> {
+        let Some(form) = self.form_state.as_mut() else {
+            return Ok(());
+        };
 
-        let json_str = serde_json::to_string_pretty(&default_json).unwrap_or_default();
-        let textarea = TextArea::from(json_str.lines().map(|s| s.to_string()).collect::<Vec<_>>());
+        let mut definition = match form.value() {
+            Ok(value) => value,
+            Err(e) => {
+                form.error_message = Some(e.to_string());
+                return Ok(());
+            }
+        };
 
-        self.load_graph_state = Some(LoadGraphState {
-            textarea,
-            json_valid: true,
-            active_field: LoadGraphField::JsonInput,
+        // Let the server pick a name when none was given
+        if definition["name"].as_str() == Some("")
+            && let Some(obj) = definition.as_object_mut()
+        {
+            obj.remove("name");
+        }
+
+        match create_index(
+            &app_state.http_client,
+            &app_state.arango_endpoint,
+            database,
+            collection,
+            &definition,
+            &app_state.username,
+            &app_state.password,
+        )
+        .await
+        {
+            Ok(()) => {
+                self.form_state = None;
+                self.view = BrowserView::Indexes(database.to_string(), collection.to_string());
+                self.status_message = Some(
+                    match self.load_indexes(app_state, database, collection).await {
+                        Ok(()) => "Index created".to_string(),
+                        Err(e) => format!("Index created; reloading failed: {}", e),
+                    },
+                );
+            }
+            Err(e) => form.error_message = Some(e.to_string()),
+        }
+        Ok(())
+    }
+
+    // Properties of the loaded collection that can be changed after creation
+    fn editable_collection_properties(&self) -> Option<serde_json::Value> {
+        let details = self.collection_details.as_ref()?;
+        let mut properties = serde_json::json!({
+            "waitForSync": details.wait_for_sync.unwrap_or(false),
+            "cacheEnabled": details.cache_enabled.unwrap_or(false),
+            "schema": details.schema,
+            "computedValues": details.computed_values,
         });
+        // Only reported (and changeable) in a cluster
+        if let Some(replication_factor) = &details.replication_factor {
+            properties["replicationFactor"] = replication_factor.clone();
+        }
+        if let (Some(write_concern), Some(_)) = (details.write_concern, &details.replication_factor)
+        {
+            properties["writeConcern"] = serde_json::json!(write_concern);
+        }
+        Some(properties)
     }
 
-    async fn load_graphs(&mut self, app_state: &AppState) -> Result<()> {
-        if let Some(ref gae_endpoint) = app_state.gae_endpoint {
-            match get_gae_graphs(&app_state.http_client, gae_endpoint).await {
-                Ok(graphs) => {
-                    self.graphs = graphs;
-                    self.selected_graph_index = 0;
-                    self.accessible = true;
-                    self.error_message = None;
-                    Ok(())
-                }
-                Err(e) => {
-                    self.accessible = false;
-                    self.error_message = Some(format!("Failed to fetch graphs: {}", e));
-                    Err(e)
-                }
+    async fn submit_collection_properties(
+        &mut self,
+        app_state: &AppState,
+        database: &str,
+        collection: &str,
+    ) -> Result<()> {
+        let Some(form) = self.form_state.as_mut() else {
+            return Ok(());
+        };
+
+        let properties = match form.value() {
+            Ok(value) => value,
+            Err(e) => {
+                form.error_message = Some(e.to_string());
+                return Ok(());
             }
-        } else {
-            self.accessible = false;
-            self.error_message = Some("GAE endpoint not configured".to_string());
-            anyhow::bail!("GAE endpoint not configured")
+        };
+
+        match update_collection_properties(
+            &app_state.http_client,
+            &app_state.arango_endpoint,
+            database,
+            collection,
+            &properties,
+            &app_state.username,
+            &app_state.password,
+        )
+        .await
+        {
+            Ok(()) => {
+                self.form_state = None;
+                self.view =
+                    BrowserView::CollectionProperties(database.to_string(), collection.to_string());
+                self.status_message = Some(
+                    match self
+                        .load_collection_details(app_state, database, collection)
+                        .await
+                    {
+                        Ok(()) => format!("Properties of {} updated", collection),
+                        Err(e) => format!(
+                            "Properties of {} updated; reloading failed: {}",
+                            collection, e
+                        ),
+                    },
+                );
+            }
+            // Keep the form open with the server's validation error
+            Err(e) => form.error_message = Some(e.to_string()),
         }
+        Ok(())
     }
 
-    async fn load_jobs(&mut self, app_state: &AppState) -> Result<()> {
-        if let Some(ref gae_endpoint) = app_state.gae_endpoint {
-            match get_gae_jobs(&app_state.http_client, gae_endpoint).await {
-                Ok(jobs) => {
-                    self.jobs = jobs;
-                    self.selected_job_index = 0;
-                    self.accessible = true;
-                    self.error_message = None;
-                    Ok(())
-                }
-                Err(e) => {
-                    self.accessible = false;
-                    self.error_message = Some(format!("Failed to fetch jobs: {}", e));
-                    Err(e)
+    // Open the schema workspace with the current schema, or a starting template
+    fn open_schema_editor(&mut self) {
+        let schema = self
+            .collection_details
+            .as_ref()
+            .and_then(|d| d.schema.clone())
+            .filter(|s| !s.is_null())
+            .unwrap_or_else(|| {
+                serde_json::json!({
+                    "rule": {
+                        "type": "object",
+                        "properties": {},
+                        "required": [],
+                    },
+                    "level": "moderate",
+                    "message": "Document does not match the collection schema",
+                })
+            });
+        self.form_state = Some(JsonFormState::new(&schema));
+        self.schema_report = None;
+        self.scroll_offset = 0;
+    }
+
+    // Validate the edited schema locally against the first documents of the collection
+    async fn test_schema_against_sample(
+        &mut self,
+        app_state: &AppState,
+        database: &str,
+        collection: &str,
+    ) -> Result<()> {
+        let Some(form) = self.form_state.as_mut() else {
+            return Ok(());
+        };
+        form.error_message = None;
+
+        let schema = match form.value() {
+            Ok(value) => value,
+            Err(e) => {
+                form.error_message = Some(e.to_string());
+                return Ok(());
+            }
+        };
+        let rule = match schema::schema_rule(&schema) {
+            Ok(rule) => rule,
+            Err(e) => {
+                form.error_message = Some(e.to_string());
+                return Ok(());
+            }
+        };
+
+        let paging = DocumentPaging {
+            page_size: self.schema_sample_size,
+            ..DocumentPaging::default()
+        };
+        let (sample, _) = match fetch_document_page(app_state, database, collection, &paging).await
+        {
+            Ok(page) => page,
+            Err(e) => {
+                form.error_message = Some(e.to_string());
+                return Ok(());
+            }
+        };
+
+        let failures = sample
+            .iter()
+            .filter_map(|document| {
+                let violations = schema::validate_document(rule, document);
+                if violations.is_empty() {
+                    return None;
                 }
+                let key = document["_key"].as_str().unwrap_or("?").to_string();
+                Some((key, violations))
+            })
+            .collect();
+
+        self.schema_report = Some(SchemaTestReport {
+            sampled: sample.len(),
+            failures,
+        });
+        self.scroll_offset = 0;
+        Ok(())
+    }
+
+    // Store the edited schema on the collection; `null` removes the schema
+    async fn submit_schema(
+        &mut self,
+        app_state: &AppState,
+        database: &str,
+        collection: &str,
+    ) -> Result<()> {
+        let Some(form) = self.form_state.as_mut() else {
+            return Ok(());
+        };
+
+        let schema = match form.value() {
+            Ok(value) => value,
+            Err(e) => {
+                form.error_message = Some(e.to_string());
+                return Ok(());
             }
-        } else {
-            self.accessible = false;
-            self.error_message = Some("GAE endpoint not configured".to_string());
-            anyhow::bail!("GAE endpoint not configured")
+        };
+        if !schema.is_null()
+            && let Err(e) = schema::schema_rule(&schema)
+        {
+            form.error_message = Some(e.to_string());
+            return Ok(());
+        }
+
+        match update_collection_properties(
+            &app_state.http_client,
+            &app_state.arango_endpoint,
+            database,
+            collection,
+            &serde_json::json!({ "schema": schema }),
+            &app_state.username,
+            &app_state.password,
+        )
+        .await
+        {
+            Ok(()) => {
+                self.form_state = None;
+                self.schema_report = None;
+                self.view =
+                    BrowserView::CollectionProperties(database.to_string(), collection.to_string());
+                let message = if schema.is_null() {
+                    format!("Schema of {} removed", collection)
+                } else {
+                    format!("Schema of {} applied", collection)
+                };
+                self.status_message = Some(
+                    match self
+                        .load_collection_details(app_state, database, collection)
+                        .await
+                    {
+                        Ok(()) => message,
+                        Err(e) => format!("{}; reloading failed: {}", message, e),
+                    },
+                );
+            }
+            Err(e) => form.error_message = Some(e.to_string()),
         }
+        Ok(())
     }
-}
 
-struct DatabaseBrowser {
-    view: BrowserView,
-    database_stats: Vec<DatabaseStats>,
-    selected_db_index: usize,
-    collections: Vec<CollectionWithCount>,
-    selected_coll_index: usize,
-    graphs: Vec<GraphInfo>,
-    selected_graph_index: usize,
-    collection_details: Option<CollectionCount>,
-    scroll_offset: usize,
-    accessible: bool,
-    input_state: InputState,
-    documents: Vec<serde_json::Value>,
-    navigation_stack: Vec<(BrowserView, usize)>, // Stack to track navigation history (view, selected_index)
-    graph_details: Option<GraphInfo>,
-    aql_state: Option<AqlState>,
-}
+    async fn load_collection_details(
+        &mut self,
+        app_state: &AppState,
+        database: &str,
+        collection: &str,
+    ) -> Result<()> {
+        let details = get_collection_count(
+            &app_state.http_client,
+            &app_state.arango_endpoint,
+            database,
+            collection,
+            &app_state.username,
+            &app_state.password,
+        )
+        .await?;
 
-impl DatabaseBrowser {
-    fn new() -> Self {
-        Self {
-            view: BrowserView::DatabaseList,
-            database_stats: Vec::new(),
-            selected_db_index: 0,
-            collections: Vec::new(),
-            selected_coll_index: 0,
-            graphs: Vec::new(),
-            selected_graph_index: 0,
-            collection_details: None,
-            scroll_offset: 0,
-            accessible: true,
-            input_state: InputState::None,
-            documents: Vec::new(),
-            navigation_stack: Vec::new(),
-            graph_details: None,
-            aql_state: None,
-        }
+        self.collection_details = Some(details);
+        self.scroll_offset = 0;
+        Ok(())
+    }
+
+    async fn load_graphs(&mut self, app_state: &AppState, database: &str) -> Result<()> {
+        let graphs = get_graphs(
+            &app_state.http_client,
+            &app_state.arango_endpoint,
+            database,
+            &app_state.username,
+            &app_state.password,
+        )
+        .await?;
+
+        self.graphs = graphs;
+        self.selected_graph_index = 0;
+        self.scroll_offset = 0;
+        Ok(())
     }
 
-    fn init_aql_state(&mut self) {
-        let default_options = AqlQueryOptions::default();
-        let options_json = serde_json::json!({
-            "batchSize": default_options.batch_size,
-            "stream": default_options.stream,
-            "maxDocuments": default_options.max_documents,
-        });
+    async fn load_documents(
+        &mut self,
+        app_state: &AppState,
+        database: &str,
+        collection: &str,
+        page_size: usize,
+    ) -> Result<()> {
+        self.document_paging = DocumentPaging {
+            page_size: page_size.max(1),
+            ..DocumentPaging::default()
+        };
+        self.reload_documents(app_state, database, collection).await
+    }
 
-        let query_textarea = TextArea::default();
-        let parameters_textarea = TextArea::from(["{}".to_string()]);
-        let options_textarea = TextArea::from(
-            serde_json::to_string_pretty(&options_json)
-                .unwrap_or_default()
-                .lines()
-                .map(|s| s.to_string())
-                .collect::<Vec<_>>(),
-        );
+    // Ask for the export file of the current results or collection
+    fn start_export(&mut self) {
+        let name = match &self.view {
+            BrowserView::DocumentViewer(_, coll) => coll.clone(),
+            _ => "query_results".to_string(),
+        };
+        let format = export::ExportFormat::Json;
+        self.input_state =
+            InputState::EnteringExportPath(format, format!("{}.{}", name, format.extension()));
+    }
 
-        self.aql_state = Some(AqlState {
-            query_textarea,
-            parameters_textarea,
-            options_textarea,
-            active_field: AqlInputField::Query,
-            parameters_valid: true,
-            options_valid: true,
-            results: Vec::new(),
-            total_fetched: 0,
-            has_more: false,
-            cursor_id: None,
-            current_page: 0,
-            scroll_offset: 0,
-            is_fetching: false,
+    // Columns offered for a CSV export, from the documents already loaded;
+    // attributes that only turn up while exporting are reported afterwards
+    fn export_columns(&self) -> Vec<String> {
+        match &self.view {
+            BrowserView::DocumentViewer(..) => export::csv_columns(&self.documents),
+            _ => self
+                .aql_tabs
+                .get(self.active_aql_tab)
+                .map(|s| export::csv_columns(&s.results[..s.results.len().min(100)]))
+                .unwrap_or_default(),
+        }
+    }
+
+    async fn export(
+        &mut self,
+        app_state: &AppState,
+        format: export::ExportFormat,
+        path: &str,
+        columns: Vec<String>,
+    ) {
+        let mut exporter = match export::Exporter::create(path, format, columns) {
+            Ok(exporter) => exporter,
+            Err(e) => {
+                self.status_message = Some(format!("{:#}", e));
+                return;
+            }
+        };
+        let written = match self.view.clone() {
+            BrowserView::DocumentViewer(db, coll) => {
+                self.export_collection(app_state, &db, &coll, &mut exporter)
+                    .await
+            }
+            BrowserView::AqlQueryResults(_) => {
+                self.export_query_results(app_state, &mut exporter).await
+            }
+            _ => Ok(()),
+        };
+        let dropped = exporter.dropped_columns().to_vec();
+        self.status_message = Some(match written.and_then(|_| exporter.finish()) {
+            Ok(count) if dropped.is_empty() => format!(
+                "Exported {} documents as {} to {}",
+                count,
+                format.label(),
+                path
+            ),
+            Ok(count) => format!(
+                "Exported {} documents as {} to {}; attributes not in the columns were left out: {}",
+                count,
+                format.label(),
+                path,
+                dropped.join(", ")
+            ),
+            Err(e) => format!("Export failed: {:#}", e),
         });
     }
 
-    async fn load_databases(&mut self, app_state: &AppState) -> Result<()> {
-        match get_databases(
+    // Stream all documents matching the current filter and sort order
+    async fn export_collection(
+        &self,
+        app_state: &AppState,
+        database: &str,
+        collection: &str,
+        exporter: &mut export::Exporter,
+    ) -> Result<()> {
+        let (query, bind_vars) = self.document_paging.export_query(collection);
+        let response = execute_aql_query_with_params(
             &app_state.http_client,
             &app_state.arango_endpoint,
+            database,
+            &query,
+            Some(bind_vars),
+            EXPORT_BATCH_SIZE,
+            true,
             &app_state.username,
             &app_state.password,
         )
-        .await
-        {
-            Ok(databases) => {
-                self.accessible = true;
-                let mut stats = Vec::new();
-                for db in databases {
-                    let db_stats = get_database_stats(
-                        &app_state.http_client,
-                        &app_state.arango_endpoint,
-                        &db,
-                        &app_state.username,
-                        &app_state.password,
-                    )
-                    .await;
-                    stats.push(db_stats);
-                }
-                self.database_stats = stats;
-                self.selected_db_index = 0;
-                Ok(())
-            }
-            Err(_) => {
-                self.accessible = false;
-                Ok(())
-            }
-        }
+        .await?;
+        export_cursor(app_state, database, exporter, response).await
     }
 
-    async fn load_collections(&mut self, app_state: &AppState, database: &str) -> Result<()> {
-        let collections = get_collections(
+    // Write the results; when they are not all in memory, run the query again
+    // on a cursor of its own so the browsing cursor stays usable
+    async fn export_query_results(
+        &self,
+        app_state: &AppState,
+        exporter: &mut export::Exporter,
+    ) -> Result<()> {
+        let Some(aql_state) = self.aql_tabs.get(self.active_aql_tab) else {
+            return Ok(());
+        };
+        if aql_state.offset == 0 && !aql_state.has_more {
+            exporter.write_batch(&aql_state.results)?;
+            return Ok(());
+        }
+
+        let missing = if aql_state.offset > 0 {
+            format!(
+                "The first {} results are no longer in memory",
+                aql_state.offset
+            )
+        } else {
+            "Not all results are in memory yet".to_string()
+        };
+        let Some((query, bind_vars, options)) = aql_state.results_query.clone() else {
+            anyhow::bail!(missing);
+        };
+        if aql::is_modification(&query) {
+            anyhow::bail!(
+                "{}, and the query modifies data, so it is not run again; raise maxDocuments and run it again to export everything",
+                missing
+            );
+        }
+        let database = aql_state.results_database.clone();
+        let options = AqlQueryOptions {
+            batch_size: EXPORT_BATCH_SIZE,
+            ..options
+        };
+        let response = create_cursor(
             &app_state.http_client,
             &app_state.arango_endpoint,
-            database,
+            &database,
+            &query,
+            bind_vars,
+            &options,
             &app_state.username,
             &app_state.password,
         )
         .await?;
+        export_cursor(app_state, &database, exporter, response).await
+    }
 
-        let mut collections_with_count = Vec::new();
-        for coll in collections {
-            let count = get_collection_count(
+    // Restart paging from the first page, e.g. after the filter or sort order changed
+    async fn reload_documents(
+        &mut self,
+        app_state: &AppState,
+        database: &str,
+        collection: &str,
+    ) -> Result<()> {
+        self.document_paging.page_after = vec![None];
+        self.document_paging.total = match self.document_paging.count_query(collection) {
+            Some((query, bind_vars)) => execute_aql_query_with_params(
                 &app_state.http_client,
                 &app_state.arango_endpoint,
                 database,
-                &coll.name,
+                &query,
+                Some(bind_vars),
+                1,
+                false,
+                &app_state.username,
+                &app_state.password,
+            )
+            .await?
+            .result
+            .first()
+            .and_then(|n| n.as_u64()),
+            None => get_collection_count(
+                &app_state.http_client,
+                &app_state.arango_endpoint,
+                database,
+                collection,
                 &app_state.username,
                 &app_state.password,
             )
             .await
             .ok()
-            .map(|c| c.count);
+            .map(|c| c.count),
+        };
 
-            collections_with_count.push(CollectionWithCount { info: coll, count });
-        }
+        self.load_document_page(app_state, database, collection)
+            .await?;
+        self.select_document(0);
+        Ok(())
+    }
 
-        // Sort: non-system first (alphabetically), then system collections (alphabetically)
-        collections_with_count.sort_by(|a, b| match (a.info.is_system, b.info.is_system) {
-            (false, true) => std::cmp::Ordering::Less,
-            (true, false) => std::cmp::Ordering::Greater,
-            _ => a.info.name.cmp(&b.info.name),
-        });
+    // Fetch the page that starts after the last key on the paging stack
+    async fn load_document_page(
+        &mut self,
+        app_state: &AppState,
+        database: &str,
+        collection: &str,
+    ) -> Result<()> {
+        let (documents, has_more) =
+            fetch_document_page(app_state, database, collection, &self.document_paging).await?;
+        self.document_paging.has_more = has_more;
 
-        self.collections = collections_with_count;
-        self.selected_coll_index = 0;
+        self.documents = documents;
+        self.selected_doc_index = 0;
         self.scroll_offset = 0;
         Ok(())
     }
 
-    async fn load_collection_details(
+    // Open a single document in the detail view, remembering where we came from
+    async fn open_document(
         &mut self,
         app_state: &AppState,
         database: &str,
         collection: &str,
+        key: &str,
     ) -> Result<()> {
-        let details = get_collection_count(
+        let document = get_document(
             &app_state.http_client,
             &app_state.arango_endpoint,
             database,
             collection,
+            key,
             &app_state.username,
             &app_state.password,
         )
         .await?;
 
-        self.collection_details = Some(details);
+        match document {
+            Some(document) => {
+                let index = match self.view {
+                    BrowserView::CollectionList(_) => self.selected_coll_index,
+                    BrowserView::DocumentViewer(_, _) => self.selected_doc_index,
+                    BrowserView::DocumentDetail(_, _, _) => self.selected_edge_index,
+                    _ => 0,
+                };
+                self.navigation_stack.push((self.view.clone(), index));
+                self.show_document_detail(app_state, database, collection, key, document)
+                    .await?;
+            }
+            None => {
+                self.status_message = Some(format!(
+                    "Document {}/{} not found in database {}",
+                    collection, key, database
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    async fn show_document_detail(
+        &mut self,
+        app_state: &AppState,
+        database: &str,
+        collection: &str,
+        key: &str,
+        document: serde_json::Value,
+    ) -> Result<()> {
+        self.document_edges.clear();
+        self.selected_edge_index = 0;
+        if !is_edge_document(&document)
+            && let Some(id) = document["_id"].as_str()
+        {
+            self.load_document_edges(app_state, database, collection, id)
+                .await?;
+        }
+
+        self.document_detail = Some(document);
         self.scroll_offset = 0;
+        self.view = BrowserView::DocumentDetail(
+            database.to_string(),
+            collection.to_string(),
+            key.to_string(),
+        );
         Ok(())
     }
 
-    async fn load_graphs(&mut self, app_state: &AppState, database: &str) -> Result<()> {
+    // Collect inbound and outbound edges of a vertex. Edge collections come from the
+    // edge definitions of the graphs the vertex collection is part of, falling back
+    // to all edge collections of the database.
+    async fn load_document_edges(
+        &mut self,
+        app_state: &AppState,
+        database: &str,
+        collection: &str,
+        vertex_id: &str,
+    ) -> Result<()> {
         let graphs = get_graphs(
             &app_state.http_client,
             &app_state.arango_endpoint,
@@ -1022,34 +4351,364 @@ impl DatabaseBrowser {
             &app_state.username,
             &app_state.password,
         )
-        .await?;
+        .await
+        .unwrap_or_default();
+
+        let mut edge_collections: Vec<String> = graphs
+            .iter()
+            .flat_map(|g| g.edge_definitions.iter())
+            .filter(|e| {
+                e.from.iter().any(|c| c == collection) || e.to.iter().any(|c| c == collection)
+            })
+            .map(|e| e.collection.clone())
+            .collect();
+
+        if edge_collections.is_empty() {
+            edge_collections = get_collections(
+                &app_state.http_client,
+                &app_state.arango_endpoint,
+                database,
+                &app_state.username,
+                &app_state.password,
+            )
+            .await?
+            .into_iter()
+            .filter(|c| c.collection_type == 3 && !c.is_system)
+            .map(|c| c.name)
+            .collect();
+        }
+        edge_collections.sort();
+        edge_collections.dedup();
+
+        let query = "LET outbound = (FOR e IN @@edges FILTER e._from == @vertex LIMIT @limit RETURN e) \
+                     LET inbound = (FOR e IN @@edges FILTER e._to == @vertex LIMIT @limit RETURN e) \
+                     RETURN { outbound, inbound }";
+
+        let mut edges = Vec::new();
+        for edge_collection in edge_collections {
+            let bind_vars = serde_json::json!({
+                "@edges": edge_collection,
+                "vertex": vertex_id,
+                "limit": MAX_EDGES_PER_DIRECTION,
+            });
+            let response = execute_aql_query_with_params(
+                &app_state.http_client,
+                &app_state.arango_endpoint,
+                database,
+                query,
+                Some(bind_vars),
+                1,
+                false,
+                &app_state.username,
+                &app_state.password,
+            )
+            .await?;
+
+            let Some(result) = response.result.into_iter().next() else {
+                continue;
+            };
+            for (direction, list) in [
+                (EdgeDirection::Outbound, &result["outbound"]),
+                (EdgeDirection::Inbound, &result["inbound"]),
+            ] {
+                for edge in list.as_array().into_iter().flatten() {
+                    edges.push(DocumentEdge {
+                        direction,
+                        edge: edge.clone(),
+                    });
+                }
+            }
+        }
+
+        self.document_edges = edges;
+        Ok(())
+    }
+
+    // Leave the document detail view, returning to the view it was opened from
+    async fn close_document(&mut self, app_state: &AppState, database: &str) -> Result<()> {
+        self.document_detail = None;
+        self.scroll_offset = 0;
+        match self.navigation_stack.pop() {
+            Some((BrowserView::DocumentDetail(db, coll, key), prev_index)) => {
+                let document = get_document(
+                    &app_state.http_client,
+                    &app_state.arango_endpoint,
+                    &db,
+                    &coll,
+                    &key,
+                    &app_state.username,
+                    &app_state.password,
+                )
+                .await;
+                let document = match document {
+                    Ok(document) => document,
+                    Err(e) => {
+                        // Still go back, showing the document as not loaded
+                        self.view = BrowserView::DocumentDetail(db, coll, key);
+                        return Err(e);
+                    }
+                };
+                match document {
+                    Some(document) => {
+                        self.show_document_detail(app_state, &db, &coll, &key, document)
+                            .await?;
+                        self.selected_edge_index =
+                            prev_index.min(self.document_edges.len().saturating_sub(1));
+                    }
+                    None => self.view = BrowserView::DocumentDetail(db, coll, key),
+                }
+            }
+            Some((prev_view @ BrowserView::DocumentViewer(_, _), prev_index)) => {
+                self.view = prev_view;
+                if prev_index < self.documents.len() {
+                    self.select_document(prev_index);
+                }
+            }
+            Some((prev_view @ BrowserView::CollectionList(_), prev_index)) => {
+                self.view = prev_view;
+                self.selected_coll_index = prev_index;
+            }
+            Some((prev_view, _)) => self.view = prev_view,
+            None => self.view = BrowserView::CollectionList(database.to_string()),
+        }
+        Ok(())
+    }
+
+    // Top-level attribute names of the loaded documents, for picking a sort attribute
+    fn document_attributes(&self) -> Vec<String> {
+        let mut attributes: Vec<String> = self
+            .documents
+            .iter()
+            .filter_map(|d| d.as_object())
+            .flat_map(|o| o.keys().cloned())
+            .collect();
+        attributes.sort();
+        attributes.dedup();
+        attributes
+    }
+
+    async fn next_document_page(
+        &mut self,
+        app_state: &AppState,
+        database: &str,
+        collection: &str,
+    ) -> Result<()> {
+        if !self.document_paging.has_more {
+            return Ok(());
+        }
+        let Some(last_key) = self
+            .documents
+            .last()
+            .and_then(|d| d["_key"].as_str())
+            .map(|s| s.to_string())
+        else {
+            return Ok(());
+        };
+
+        self.document_paging.page_after.push(Some(last_key));
+        if let Err(e) = self
+            .load_document_page(app_state, database, collection)
+            .await
+        {
+            self.document_paging.page_after.pop();
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    async fn previous_document_page(
+        &mut self,
+        app_state: &AppState,
+        database: &str,
+        collection: &str,
+    ) -> Result<()> {
+        if self.document_paging.page_after.len() <= 1 {
+            return Ok(());
+        }
+
+        let current = self.document_paging.page_after.pop();
+        if let Err(e) = self
+            .load_document_page(app_state, database, collection)
+            .await
+        {
+            self.document_paging.page_after.extend(current);
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    // First line of the given document in the rendered document list
+    fn document_line_offset(&self, index: usize) -> usize {
+        self.documents
+            .iter()
+            .take(index)
+            .map(|doc| {
+                serde_json::to_string_pretty(doc)
+                    .map(|s| s.lines().count())
+                    .unwrap_or(1)
+                    + 1
+            })
+            .sum()
+    }
+
+    fn select_document(&mut self, index: usize) {
+        self.selected_doc_index = index;
+        self.scroll_offset = self.document_line_offset(index);
+    }
+
+    async fn save_document(
+        &mut self,
+        app_state: &AppState,
+        database: &str,
+        collection: &str,
+        overwrite: bool,
+    ) -> Result<()> {
+        let Some(edit) = self.document_edit.as_mut() else {
+            return Ok(());
+        };
+
+        let document = match edit.form.value() {
+            Ok(doc) => doc,
+            Err(e) => {
+                edit.form.error_message = Some(format!("{:#}", e));
+                return Ok(());
+            }
+        };
+
+        let result = match edit.key.clone() {
+            Some(key) => {
+                // When overwriting after a conflict, base the write on the server revision
+                let rev = if overwrite {
+                    edit.conflict
+                        .as_ref()
+                        .and_then(|c| c["_rev"].as_str().map(|s| s.to_string()))
+                } else {
+                    edit.rev.clone()
+                };
+                replace_document(
+                    &app_state.http_client,
+                    &app_state.arango_endpoint,
+                    database,
+                    collection,
+                    &key,
+                    &document,
+                    rev.as_deref(),
+                    &app_state.username,
+                    &app_state.password,
+                )
+                .await
+            }
+            None => insert_document(
+                &app_state.http_client,
+                &app_state.arango_endpoint,
+                database,
+                collection,
+                &document,
+                &app_state.username,
+                &app_state.password,
+            )
+            .await
+            .map(DocumentWriteOutcome::Written),
+        };
 
-        self.graphs = graphs;
-        self.selected_graph_index = 0;
-        self.scroll_offset = 0;
+        match result {
+            Ok(DocumentWriteOutcome::Written(saved)) => {
+                let inserted = edit.key.is_none();
+                let key = saved["_key"].as_str().unwrap_or_default().to_string();
+                let mut message = format!("Document {}/{} saved", collection, key);
+                if inserted {
+                    // The new key may sort anywhere, so reload the page instead of
+                    // appending to it and breaking the keyset cursor
+                    self.document_paging.total = self.document_paging.total.map(|n| n + 1);
+                    match self
+                        .load_document_page(app_state, database, collection)
+                        .await
+                    {
+                        Ok(()) => match self.documents.iter().position(|d| d["_key"] == key) {
+                            Some(pos) => self.select_document(pos),
+                            None => message.push_str(" (not on this page)"),
+                        },
+                        Err(e) => message = format!("{}; reloading failed: {}", message, e),
+                    }
+                } else if let Some(pos) = self.documents.iter().position(|d| d["_key"] == key) {
+                    self.documents[pos] = saved;
+                    self.select_document(pos);
+                }
+                self.document_edit = None;
+                self.status_message = Some(message);
+                self.view =
+                    BrowserView::DocumentViewer(database.to_string(), collection.to_string());
+            }
+            Ok(DocumentWriteOutcome::Conflict) => {
+                let key = edit.key.clone().unwrap_or_default();
+                let server_version = get_document(
+                    &app_state.http_client,
+                    &app_state.arango_endpoint,
+                    database,
+                    collection,
+                    &key,
+                    &app_state.username,
+                    &app_state.password,
+                )
+                .await;
+                match server_version {
+                    Ok(Some(server_doc)) => {
+                        edit.conflict = Some(server_doc);
+                        edit.form.error_message =
+                            Some("Conflict: document was changed on the server (412)".to_string());
+                    }
+                    Ok(None) => {
+                        edit.form.error_message =
+                            Some("Conflict: document was deleted on the server".to_string());
+                    }
+                    Err(e) => edit.form.error_message = Some(e.to_string()),
+                }
+            }
+            Err(e) => edit.form.error_message = Some(e.to_string()),
+        }
         Ok(())
     }
 
-    async fn load_documents(
+    async fn delete_selected_document(
         &mut self,
         app_state: &AppState,
         database: &str,
         collection: &str,
-        count: usize,
     ) -> Result<()> {
-        let query = format!("FOR d IN {} LIMIT {} RETURN d", collection, count);
-        let documents = execute_aql_query(
+        let Some(doc) = self.documents.get(self.selected_doc_index) else {
+            return Ok(());
+        };
+        let key = doc["_key"].as_str().unwrap_or_default().to_string();
+        let rev = doc["_rev"].as_str().map(|s| s.to_string());
+
+        match delete_document(
             &app_state.http_client,
             &app_state.arango_endpoint,
             database,
-            &query,
+            collection,
+            &key,
+            rev.as_deref(),
             &app_state.username,
             &app_state.password,
         )
-        .await?;
-
-        self.documents = documents;
-        self.scroll_offset = 0;
+        .await
+        {
+            Ok(DocumentWriteOutcome::Written(_)) => {
+                self.documents.remove(self.selected_doc_index);
+                let index = self
+                    .selected_doc_index
+                    .min(self.documents.len().saturating_sub(1));
+                self.select_document(index);
+                self.status_message = Some(format!("Document {}/{} deleted", collection, key));
+            }
+            Ok(DocumentWriteOutcome::Conflict) => {
+                self.status_message = Some(format!(
+                    "Document {}/{} was changed on the server, reload before deleting",
+                    collection, key
+                ));
+            }
+            Err(e) => self.status_message = Some(e.to_string()),
+        }
         Ok(())
     }
 
@@ -1180,11 +4839,10 @@ fn render_collection_list(f: &mut Frame, area: Rect, browser: &DatabaseBrowser,
         let empty = Paragraph::new("No collections found")
             .style(Style::default().fg(Color::Yellow))
             .alignment(Alignment::Center)
-            .block(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .title(format!("Database: {} | G: Graphs | A: AQL Query", database)),
-            );
+            .block(Block::default().borders(Borders::ALL).title(format!(
+                "Database: {} | G: Graphs | A: AQL Query | N: new collection",
+                database
+            )));
         f.render_widget(empty, area);
         return;
     }
@@ -1193,7 +4851,7 @@ fn render_collection_list(f: &mut Frame, area: Rect, browser: &DatabaseBrowser,
     let total_docs: u64 = browser.collections.iter().filter_map(|c| c.count).sum();
 
     let title = format!(
-        "Database: {} | Collections: {} | Total Documents: {} | G: Graphs | A: AQL Query | SPACE: view documents",
+        "Database: {} | Collections: {} | Total Documents: {} | G: Graphs | A: AQL Query | M: query monitor | SPACE: view documents | O: go to document | I: indexes | N: new | R: rename | T: truncate | D: drop",
         database, total_collections, total_docs
     );
 
@@ -1402,6 +5060,107 @@ fn render_graph_list(f: &mut Frame, area: Rect, browser: &DatabaseBrowser, datab
     f.render_widget(table, area);
 }
 
+fn render_indexes(
+    f: &mut Frame,
+    area: Rect,
+    browser: &DatabaseBrowser,
+    database: &str,
+    collection: &str,
+) {
+    use ratatui::widgets::{Cell, Row, Table, TableState};
+
+    let title = format!(
+        "Indexes: {}.{} ({}) | P: persistent | T: TTL | G: geo | F: fulltext | I: inverted | V: vector | D: drop | R: refresh | Q/ESC: back",
+        database,
+        collection,
+        browser.indexes.len()
+    );
+
+    let header = Row::new(vec![
+        "Name",
+        "Type",
+        "Fields",
+        "Unique",
+        "Sparse",
+        "Selectivity",
+        "State",
+    ])
+    .style(
+        Style::default()
+            .fg(Color::Yellow)
+            .add_modifier(Modifier::BOLD),
+    )
+    .bottom_margin(1);
+
+    let rows: Vec<Row> = browser
+        .indexes
+        .iter()
+        .map(|index| {
+            let fields = index
+                .fields
+                .iter()
+                .map(|field| match field {
+                    serde_json::Value::String(name) => name.clone(),
+                    other => other["name"]
+                        .as_str()
+                        .map(|s| s.to_string())
+                        .unwrap_or_else(|| other.to_string()),
+                })
+                .collect::<Vec<_>>()
+                .join(", ");
+            let selectivity = index
+                .selectivity_estimate
+                .map(|s| format!("{:.2}%", s * 100.0))
+                .unwrap_or_else(|| "-".to_string());
+            let state = if index.is_building.unwrap_or(false) {
+                "building"
+            } else if index.in_background.unwrap_or(false) {
+                "ready (background)"
+            } else {
+                "ready"
+            };
+
+            Row::new(vec![
+                Cell::from(index.name.clone().unwrap_or_default()),
+                Cell::from(index.index_type.clone()),
+                Cell::from(fields),
+                Cell::from(if index.unique { "Yes" } else { "No" }),
+                Cell::from(if index.sparse { "Yes" } else { "No" }),
+                Cell::from(selectivity),
+                Cell::from(state),
+            ])
+            .style(Style::default().fg(Color::White))
+        })
+        .collect();
+
+    let widths = [
+        Constraint::Percentage(18),
+        Constraint::Percentage(10),
+        Constraint::Percentage(30),
+        Constraint::Percentage(7),
+        Constraint::Percentage(7),
+        Constraint::Percentage(11),
+        Constraint::Percentage(17),
+    ];
+
+    let table = Table::new(rows, widths)
+        .header(header)
+        .block(Block::default().borders(Borders::ALL).title(title))
+        .row_highlight_style(
+            Style::default()
+                .fg(Color::Black)
+                .bg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        )
+        .column_spacing(2);
+
+    let mut state = TableState::default();
+    if !browser.indexes.is_empty() {
+        state.select(Some(browser.selected_index_row));
+    }
+    f.render_stateful_widget(table, area, &mut state);
+}
+
 fn render_collection_properties(
     f: &mut Frame,
     area: Rect,
@@ -1417,7 +5176,10 @@ fn render_collection_properties(
             .map(|l| Line::from(l.to_string()))
             .collect();
 
-        let title = format!("Collection Properties: {}.{}", database, collection);
+        let title = format!(
+            "Collection Properties: {}.{} | E: edit | S: schema | Q/ESC: back",
+            database, collection
+        );
 
         let para = Paragraph::new(lines)
             .style(Style::default().fg(Color::White))
@@ -1438,6 +5200,94 @@ fn render_collection_properties(
     }
 }
 
+fn render_schema_editor(
+    f: &mut Frame,
+    area: Rect,
+    browser: &mut DatabaseBrowser,
+    database: &str,
+    collection: &str,
+) {
+    use ratatui::widgets::Wrap;
+
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(55), Constraint::Percentage(45)])
+        .split(area);
+
+    if let Some(form) = &mut browser.form_state {
+        render_json_form(
+            f,
+            chunks[0],
+            form,
+            &format!("Schema: {}.{}", database, collection),
+            FORM_KEYS,
+            "APPLY SCHEMA",
+        );
+    }
+
+    let title = format!(
+        "Sample Test | Ctrl+T: test {} documents | Ctrl+N: sample size",
+        browser.schema_sample_size
+    );
+    let lines: Vec<Line> = match &browser.schema_report {
+        None => vec![
+            Line::from("Press Ctrl+T to validate the schema against existing documents."),
+            Line::from(""),
+            Line::from("Nothing is changed on the server until the schema is applied."),
+            Line::from("Apply `null` to remove the schema."),
+        ],
+        Some(report) if report.failures.is_empty() => vec![Line::from(Span::styled(
+            format!("✓ All {} sampled documents pass", report.sampled),
+            Style::default().fg(Color::Green),
+        ))],
+        Some(report) => {
+            let mut lines = vec![
+                Line::from(Span::styled(
+                    format!(
+                        "✗ {} of {} sampled documents would fail",
+                        report.failures.len(),
+                        report.sampled
+                    ),
+                    Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+                )),
+                Line::from(""),
+            ];
+            for (key, violations) in &report.failures {
+                lines.push(Line::from(Span::styled(
+                    format!("{}/{}", collection, key),
+                    Style::default().fg(Color::Yellow),
+                )));
+                for violation in violations {
+                    lines.push(Line::from(format!(
+                        "  {}: {}",
+                        violation.path, violation.message
+                    )));
+                }
+            }
+            lines
+        }
+    };
+
+    let report_focused = browser
+        .form_state
+        .as_ref()
+        .is_some_and(|form| matches!(form.active_field, JsonFormField::Submit));
+    let para = Paragraph::new(lines)
+        .wrap(Wrap { trim: false })
+        .scroll((browser.scroll_offset as u16, 0))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(title)
+                .border_style(if report_focused {
+                    Style::default().fg(Color::Cyan)
+                } else {
+                    Style::default()
+                }),
+        );
+    f.render_widget(para, chunks[1]);
+}
+
 fn render_document_viewer(
     f: &mut Frame,
     area: Rect,
@@ -1446,14 +5296,17 @@ fn render_document_viewer(
     collection: &str,
 ) {
     if browser.documents.is_empty() {
+        let description = browser.document_paging.description();
         let empty = Paragraph::new("No documents found")
             .style(Style::default().fg(Color::Yellow))
             .alignment(Alignment::Center)
-            .block(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .title(format!("Documents: {}.{}", database, collection)),
-            );
+            .block(Block::default().borders(Borders::ALL).title(format!(
+                "Documents: {}.{}{}{} | /: filter | X: clear | I: insert | ESC/Q: back",
+                database,
+                collection,
+                if description.is_empty() { "" } else { " | " },
+                description
+            )));
         f.render_widget(empty, area);
         return;
     }
@@ -1463,17 +5316,40 @@ fn render_document_viewer(
         if i > 0 {
             lines.push(Line::from(""));
         }
+        let style = if i == browser.selected_doc_index {
+            Style::default().fg(Color::Cyan)
+        } else {
+            Style::default().fg(Color::White)
+        };
         let json_str = serde_json::to_string_pretty(doc).unwrap_or_else(|_| "Error".to_string());
         for line in json_str.lines() {
-            lines.push(Line::from(line.to_string()));
+            lines.push(Line::styled(line.to_string(), style));
         }
     }
 
+    let paging = &browser.document_paging;
+    let total = match paging.total {
+        Some(total) => format_count(total),
+        None => "?".to_string(),
+    };
+    let range = if browser.documents.is_empty() {
+        "0 documents".to_string()
+    } else {
+        format!(
+            "documents {}–{} of {}",
+            paging.offset() + 1,
+            paging.offset() + browser.documents.len(),
+            total
+        )
+    };
+    let description = paging.description();
     let title = format!(
-        "Documents: {}.{} ({} documents) | Press ESC or Q to go back",
+        "Documents: {}.{} | {}{}{} | ↑ ↓: select | ← →: pages | /: filter | S: sort | X: clear | V: view | E/ENTER: edit | I: insert | D: delete | O: go to | ESC/Q: back",
         database,
         collection,
-        browser.documents.len()
+        range,
+        if description.is_empty() { "" } else { " | " },
+        description
     );
 
     let para = Paragraph::new(lines)
@@ -1484,21 +5360,22 @@ fn render_document_viewer(
     f.render_widget(para, area);
 }
 
-fn render_input_dialog(f: &mut Frame, area: Rect, input_text: &str) {
+fn centered_dialog_area(area: Rect, width: u16, height: u16) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+    Rect {
+        x: area.x + (area.width.saturating_sub(width)) / 2,
+        y: area.y + (area.height.saturating_sub(height)) / 2,
+        width,
+        height,
+    }
+}
+
+fn render_input_dialog(f: &mut Frame, area: Rect, title: &str, prompt: &str, input_text: &str) {
     use ratatui::widgets::Clear;
 
     // Create a centered dialog box
-    let dialog_width = 50;
-    let dialog_height = 7;
-    let x = (area.width.saturating_sub(dialog_width)) / 2;
-    let y = (area.height.saturating_sub(dialog_height)) / 2;
-
-    let dialog_area = Rect {
-        x: area.x + x,
-        y: area.y + y,
-        width: dialog_width,
-        height: dialog_height,
-    };
+    let dialog_area = centered_dialog_area(area, 60, 6);
 
     // Clear the area behind the dialog
     f.render_widget(Clear, dialog_area);
@@ -1512,12 +5389,12 @@ fn render_input_dialog(f: &mut Frame, area: Rect, input_text: &str) {
         ])
         .split(dialog_area);
 
-    let prompt = Paragraph::new("Enter number of documents to fetch:")
+    let prompt = Paragraph::new(prompt.to_string())
         .style(Style::default().fg(Color::White))
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .title("Fetch Documents"),
+                .title(title.to_string()),
         );
 
     f.render_widget(prompt, chunks[0]);
@@ -1530,7 +5407,275 @@ fn render_input_dialog(f: &mut Frame, area: Rect, input_text: &str) {
                 .style(Style::default().fg(Color::Cyan)),
         );
 
-    f.render_widget(input, chunks[1]);
+    f.render_widget(input, chunks[1]);
+}
+
+fn render_confirm_dialog(f: &mut Frame, area: Rect, title: &str, message: &str) {
+    use ratatui::widgets::{Clear, Wrap};
+
+    let dialog_area = centered_dialog_area(area, 60, 7);
+    f.render_widget(Clear, dialog_area);
+
+    let lines = vec![
+        Line::from(message.to_string()),
+        Line::from(""),
+        Line::styled(
+            "Y: confirm | N/ESC: cancel",
+            Style::default().fg(Color::Yellow),
+        ),
+    ];
+
+    let dialog = Paragraph::new(lines)
+        .wrap(Wrap { trim: false })
+        .alignment(Alignment::Center)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Red))
+                .title(title.to_string()),
+        );
+    f.render_widget(dialog, dialog_area);
+}
+
+fn render_status_message(f: &mut Frame, area: Rect, message: &str) {
+    use ratatui::widgets::Clear;
+
+    let height = 3.min(area.height);
+    let bar_area = Rect {
+        x: area.x,
+        y: area.y + area.height - height,
+        width: area.width,
+        height,
+    };
+    f.render_widget(Clear, bar_area);
+
+    let bar = Paragraph::new(message.to_string())
+        .style(Style::default().fg(Color::Yellow))
+        .block(Block::default().borders(Borders::ALL));
+    f.render_widget(bar, bar_area);
+}
+
+fn render_json_form(
+    f: &mut Frame,
+    area: Rect,
+    form: &mut JsonFormState,
+    title: &str,
+    keys: &str, // Key hints for the title
+    submit_label: &str,
+) {
+    use ratatui::widgets::Wrap;
+
+    let mut constraints = vec![Constraint::Min(5)];
+    if form.error_message.is_some() {
+        constraints.push(Constraint::Length(4)); // Error message
+    }
+    constraints.push(Constraint::Length(3)); // Submit button
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(constraints)
+        .split(area);
+
+    let validation_msg = if form.json_valid {
+        "✓ Valid JSON"
+    } else {
+        "✗ Invalid JSON"
+    };
+
+    form.textarea.set_block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(format!("{} - {} | {}", title, validation_msg, keys))
+            .border_style(if !form.json_valid {
+                Style::default().fg(Color::Red)
+            } else if matches!(form.active_field, JsonFormField::JsonInput) {
+                Style::default().fg(Color::Cyan)
+            } else {
+                Style::default()
+            }),
+    );
+    form.textarea.set_cursor_line_style(Style::default());
+    form.textarea
+        .set_cursor_style(Style::default().add_modifier(Modifier::REVERSED));
+    f.render_widget(&form.textarea, chunks[0]);
+
+    if let Some(error) = &form.error_message {
+        let para = Paragraph::new(error.clone())
+            .style(Style::default().fg(Color::Red))
+            .wrap(Wrap { trim: true })
+            .block(Block::default().borders(Borders::ALL).title("Error"));
+        f.render_widget(para, chunks[1]);
+    }
+
+    let submit_text = if matches!(form.active_field, JsonFormField::Submit) {
+        format!(">>> [ {} - Press ENTER ] <<<", submit_label)
+    } else {
+        format!("[ {} - Press TAB then ENTER ]", submit_label)
+    };
+
+    let submit_widget = Paragraph::new(submit_text)
+        .style(if matches!(form.active_field, JsonFormField::Submit) {
+            Style::default()
+                .fg(Color::Black)
+                .bg(Color::Green)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(Color::Green)
+        })
+        .alignment(Alignment::Center)
+        .block(Block::default().borders(Borders::ALL));
+    f.render_widget(submit_widget, chunks[chunks.len() - 1]);
+}
+
+fn render_document_editor(
+    f: &mut Frame,
+    area: Rect,
+    browser: &mut DatabaseBrowser,
+    database: &str,
+    collection: &str,
+) {
+    let Some(edit) = &mut browser.document_edit else {
+        return;
+    };
+
+    let mut constraints = vec![Constraint::Min(8)];
+    if edit.conflict.is_some() {
+        constraints.push(Constraint::Percentage(45)); // Diff against server version
+    }
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(constraints)
+        .split(area);
+
+    let title = match &edit.key {
+        Some(key) => format!("Edit Document: {}.{}/{}", database, collection, key),
+        None => format!("New Document: {}.{}", database, collection),
+    };
+    render_json_form(
+        f,
+        chunks[0],
+        &mut edit.form,
+        &title,
+        "TAB: switch fields | Ctrl+S: save | ESC: cancel",
+        "SAVE DOCUMENT",
+    );
+
+    if let Some(server_doc) = &edit.conflict {
+        let server_json = serde_json::to_string_pretty(server_doc).unwrap_or_default();
+        let diff = diff_lines(&edit.form.textarea.lines().join("\n"), &server_json);
+        let para = Paragraph::new(diff_to_lines(&diff)).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Red))
+                .title("Server version (- yours, + server) | Ctrl+O: overwrite | Ctrl+L: load server version"),
+        );
+        f.render_widget(para, chunks[1]);
+    }
+}
+
+fn render_document_detail(
+    f: &mut Frame,
+    area: Rect,
+    browser: &DatabaseBrowser,
+    database: &str,
+    collection: &str,
+    key: &str,
+) {
+    use ratatui::widgets::ListState;
+
+    let is_edge = browser
+        .document_detail
+        .as_ref()
+        .is_some_and(is_edge_document);
+
+    let title = if is_edge {
+        format!(
+            "Edge: {}:{}/{} | F: open _from | T: open _to | ↑ ↓ PgUp PgDn: scroll | O: go to document | ESC/Q/BACKSPACE: back",
+            database, collection, key
+        )
+    } else {
+        format!(
+            "Document: {}:{}/{} | PgUp PgDn: scroll | O: go to document | ESC/Q/BACKSPACE: back",
+            database, collection, key
+        )
+    };
+
+    let lines: Vec<Line> = match &browser.document_detail {
+        Some(document) => serde_json::to_string_pretty(document)
+            .unwrap_or_else(|_| "Error".to_string())
+            .lines()
+            .map(|l| Line::from(l.to_string()))
+            .collect(),
+        None => vec![Line::styled(
+            "Document not found",
+            Style::default().fg(Color::Yellow),
+        )],
+    };
+
+    let (document_area, edges_area) = if is_edge || browser.document_detail.is_none() {
+        (area, None)
+    } else {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Percentage(55), Constraint::Percentage(45)])
+            .split(area);
+        (chunks[0], Some(chunks[1]))
+    };
+
+    let para = Paragraph::new(lines)
+        .style(Style::default().fg(Color::White))
+        .block(Block::default().borders(Borders::ALL).title(title))
+        .scroll((browser.scroll_offset as u16, 0));
+
+    f.render_widget(para, document_area);
+
+    if let Some(edges_area) = edges_area {
+        let items: Vec<ListItem> = browser
+            .document_edges
+            .iter()
+            .map(|e| {
+                let id = e.edge["_id"].as_str().unwrap_or("?");
+                let text = match e.direction {
+                    EdgeDirection::Outbound => {
+                        format!("→ {}  (to {})", id, e.edge["_to"].as_str().unwrap_or("?"))
+                    }
+                    EdgeDirection::Inbound => format!(
+                        "← {}  (from {})",
+                        id,
+                        e.edge["_from"].as_str().unwrap_or("?")
+                    ),
+                };
+                ListItem::new(text)
+            })
+            .collect();
+
+        let outbound = browser
+            .document_edges
+            .iter()
+            .filter(|e| e.direction == EdgeDirection::Outbound)
+            .count();
+        let edges_title = format!(
+            "Edges: {} outbound, {} inbound | ↑ ↓: select | ENTER: open edge",
+            outbound,
+            browser.document_edges.len() - outbound
+        );
+
+        let list = List::new(items)
+            .block(Block::default().borders(Borders::ALL).title(edges_title))
+            .style(Style::default().fg(Color::White))
+            .highlight_style(
+                Style::default()
+                    .fg(Color::Black)
+                    .bg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            );
+
+        let mut state = ListState::default();
+        if !browser.document_edges.is_empty() {
+            state.select(Some(browser.selected_edge_index));
+        }
+        f.render_stateful_widget(list, edges_area, &mut state);
+    }
 }
 
 fn render_graph_properties(
@@ -1569,57 +5714,360 @@ fn render_graph_properties(
     }
 }
 
+// Draw the content of an AQL textarea with highlighting, cursor and bracket matching.
+// The textarea still owns the text and handles the input; `scroll` keeps the cursor visible.
+fn render_aql_editor(
+    f: &mut Frame,
+    area: Rect,
+    textarea: &TextArea,
+    scroll: &mut (usize, usize),
+    focused: bool,
+    error: Option<(usize, usize)>, // Char range marked as erroneous
+    block: Block,
+) {
+    let inner = block.inner(area);
+    let (row, col) = textarea.cursor();
+    let height = (inner.height as usize).max(1);
+    let width = (inner.width as usize).max(1);
+    if row < scroll.0 {
+        scroll.0 = row;
+    } else if row >= scroll.0 + height {
+        scroll.0 = row + 1 - height;
+    }
+    if col < scroll.1 {
+        scroll.1 = col;
+    } else if col >= scroll.1 + width {
+        scroll.1 = col + 1 - width;
+    }
+
+    let text = textarea.lines().join("\n");
+    let cursor = textarea.lines()[..row]
+        .iter()
+        .map(|l| l.chars().count() + 1)
+        .sum::<usize>()
+        + col;
+
+    let mut overlays = Vec::new();
+    if let Some((start, end)) = error {
+        let style = Style::default()
+            .fg(Color::White)
+            .bg(Color::Red)
+            .add_modifier(Modifier::UNDERLINED);
+        overlays.extend((start..end).map(|pos| (pos, style)));
+    }
+    if focused {
+        // Match the bracket under the cursor, or the one just before it
+        let tokens = aql::tokenize(&text);
+        let bracket = aql::matching_bracket(&tokens, cursor)
+            .map(|partner| (cursor, partner))
+            .or_else(|| {
+                let before = cursor.checked_sub(1)?;
+                aql::matching_bracket(&tokens, before).map(|partner| (before, partner))
+            });
+        match bracket {
+            Some((pos, Some(partner))) => {
+                let style = Style::default()
+                    .bg(Color::DarkGray)
+                    .add_modifier(Modifier::BOLD);
+                overlays.push((pos, style));
+                overlays.push((partner, style));
+            }
+            Some((pos, None)) => overlays.push((pos, Style::default().bg(Color::Red))),
+            None => {}
+        }
+        overlays.push((cursor, Style::default().add_modifier(Modifier::REVERSED)));
+    }
+
+    let para = Paragraph::new(aql::highlight_with(&text, &overlays))
+        .block(block)
+        .scroll((scroll.0 as u16, scroll.1 as u16));
+    f.render_widget(para, area);
+}
+
+// Draw the completion list just below the cursor of the query editor
+fn render_completion_popup(
+    f: &mut Frame,
+    editor: Rect,
+    aql_state: &AqlState,
+    popup: &CompletionPopup,
+) {
+    use ratatui::widgets::{Clear, ListState};
+
+    let (row, col) = aql_state.query_textarea.cursor();
+    let (top, left) = aql_state.query_scroll;
+    let frame = f.area();
+
+    let width = popup
+        .items
+        .iter()
+        .map(|c| c.text.chars().count() + c.kind.len() + 3)
+        .max()
+        .unwrap_or(0)
+        .clamp(20, 60) as u16
+        + 2;
+    let height = popup.items.len().min(8) as u16 + 2;
+
+    let cursor_x = editor.x + 1 + col.saturating_sub(left + popup.prefix.chars().count()) as u16;
+    let cursor_y = editor.y + 1 + row.saturating_sub(top) as u16;
+    // Open above the cursor when there is no room below
+    let y = if cursor_y + 1 + height <= frame.bottom() {
+        cursor_y + 1
+    } else {
+        cursor_y.saturating_sub(height)
+    };
+    let x = cursor_x.min(frame.right().saturating_sub(width));
+    let area = Rect::new(x, y, width.min(frame.width), height.min(frame.height));
+
+    let items: Vec<ListItem> = popup
+        .items
+        .iter()
+        .map(|c| {
+            ListItem::new(Line::from(vec![
+                Span::raw(c.text.clone()),
+                Span::raw(" "),
+                Span::styled(c.kind, Style::default().fg(Color::DarkGray)),
+            ]))
+        })
+        .collect();
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Cyan)),
+        )
+        .highlight_style(
+            Style::default()
+                .bg(Color::Blue)
+                .add_modifier(Modifier::BOLD),
+        );
+    let mut state = ListState::default().with_selected(Some(popup.selected));
+
+    f.render_widget(Clear, area);
+    f.render_stateful_widget(list, area, &mut state);
+}
+
+// One row per bind parameter: name, type and input, with invalid inputs and
+// unused parameters marked
+fn render_param_form(
+    f: &mut Frame,
+    area: Rect,
+    form: &params::ParamForm,
+    unused: &[String],
+    focused: bool,
+    block: Block,
+) {
+    use ratatui::widgets::{Cell, Row, Table, TableState};
+
+    let rows: Vec<Row> = form
+        .fields
+        .iter()
+        .enumerate()
+        .map(|(i, field)| {
+            let input = if focused && i == form.selected {
+                format!("{}█", field.input)
+            } else {
+                field.input.clone()
+            };
+            let (note, style) = match field.value() {
+                Err(e) => (e, Style::default().fg(Color::Red)),
+                Ok(None) => ("unbound".to_string(), Style::default().fg(Color::Yellow)),
+                Ok(_) if unused.contains(&field.name) => {
+                    ("unused".to_string(), Style::default().fg(Color::DarkGray))
+                }
+                Ok(_) => (String::new(), Style::default()),
+            };
+            Row::new(vec![
+                Cell::from(format!("@{}", field.name))
+                    .style(aql::token_style(aql::TokenKind::BindParameter)),
+                Cell::from(field.kind.label()),
+                Cell::from(input),
+                Cell::from(note).style(style),
+            ])
+        })
+        .collect();
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(20),
+            Constraint::Length(8),
+            Constraint::Min(20),
+            Constraint::Length(24),
+        ],
+    )
+    .header(
+        Row::new(vec!["Parameter", "Type", "Value", ""])
+            .style(Style::default().add_modifier(Modifier::BOLD)),
+    )
+    .block(block)
+    .row_highlight_style(if focused {
+        Style::default().bg(Color::Blue)
+    } else {
+        Style::default()
+    });
+    let mut state = TableState::default().with_selected(Some(form.selected));
+    f.render_stateful_widget(table, area, &mut state);
+}
+
 fn render_aql_query_input(
     f: &mut Frame,
     area: Rect,
     browser: &mut DatabaseBrowser,
     database: &str,
 ) {
-    if let Some(aql_state) = &mut browser.aql_state {
+    use ratatui::widgets::Tabs;
+
+    // Tab bar with one entry per query tab
+    let bar = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Min(0)])
+        .split(area);
+    let tabs = Tabs::new(browser.aql_tabs.iter().map(|t| t.name.clone()))
+        .select(browser.active_aql_tab)
+        .style(Style::default().fg(Color::DarkGray))
+        .highlight_style(
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD | Modifier::REVERSED),
+        );
+    f.render_widget(tabs, bar[0]);
+    let hint = Paragraph::new("Ctrl+T: new | Alt+←/→: switch | F2: rename | F8: close")
+        .style(Style::default().fg(Color::DarkGray))
+        .alignment(Alignment::Right);
+    f.render_widget(hint, bar[0]);
+    let area = bar[1];
+
+    if let Some(aql_state) = browser.aql_tabs.get_mut(browser.active_aql_tab) {
         let chunks = Layout::default()
             .direction(Direction::Vertical)
             .constraints([
                 Constraint::Percentage(35), // Query input
                 Constraint::Percentage(25), // Parameters input
                 Constraint::Percentage(25), // Options input
+                Constraint::Length(if aql_state.error.is_some() { 5 } else { 0 }), // Error
                 Constraint::Length(3),      // Submit button
             ])
             .split(area);
 
-        // Query textarea
-        aql_state.query_textarea.set_block(
+        // Query editor, drawn with syntax highlighting instead of the plain textarea
+        let query_focused = matches!(aql_state.active_field, AqlInputField::Query);
+        let query_text = aql_state.query_textarea.lines().join("\n");
+        let error_range = aql_state
+            .error
+            .as_ref()
+            .and_then(|e| aql::error_position(&e.message))
+            .and_then(|(line, column)| aql::token_range_at(&query_text, line, column));
+        render_aql_editor(
+            f,
+            chunks[0],
+            &aql_state.query_textarea,
+            &mut aql_state.query_scroll,
+            query_focused,
+            error_range,
             Block::default()
                 .borders(Borders::ALL)
                 .title("AQL Query (TAB to switch fields)")
-                .border_style(if matches!(aql_state.active_field, AqlInputField::Query) {
+                .border_style(if query_focused {
                     Style::default().fg(Color::Cyan)
                 } else {
                     Style::default()
                 }),
         );
-        aql_state
-            .query_textarea
-            .set_cursor_line_style(Style::default());
-        aql_state
-            .query_textarea
-            .set_cursor_style(Style::default().add_modifier(Modifier::REVERSED));
-        f.render_widget(&aql_state.query_textarea, chunks[0]);
 
-        // Parameters textarea
-        let validation_msg = if aql_state.parameters_valid {
-            "✓ Valid JSON"
+        if let Some(popup) = &aql_state.completion {
+            render_completion_popup(f, chunks[0], aql_state, popup);
+        }
+
+        // Parameters, as JSON or as a form; unbound and unused ones are flagged
+        let names = aql::bind_parameters(&query_text);
+        let bind_vars = aql_state.bind_vars_object().unwrap_or_default();
+        let unused = params::unused(&bind_vars, &names);
+        let mut notes = Vec::new();
+        let unbound = params::unbound(&bind_vars, &names);
+        if !unbound.is_empty() {
+            notes.push(format!("unbound: @{}", unbound.join(", @")));
+        }
+        if !unused.is_empty() {
+            notes.push(format!("unused: @{}", unused.join(", @")));
+        }
+        let params_focused = matches!(aql_state.active_field, AqlInputField::Parameters);
+        let params_border = if params_focused {
+            Style::default().fg(Color::Cyan)
+        } else if aql_state.parameters_valid {
+            Style::default()
+        } else {
+            Style::default().fg(Color::Red)
+        };
+
+        if let Some(form) = &aql_state.param_form {
+            render_param_form(
+                f,
+                chunks[1],
+                form,
+                &unused,
+                params_focused,
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(format!(
+                        "Query Parameters (form) - ↑/↓: select | F4: type | F3: JSON{}",
+                        notes
+                            .iter()
+                            .map(|n| format!(" | {}", n))
+                            .collect::<String>()
+                    ))
+                    .border_style(params_border),
+            );
+        } else {
+            let validation_msg = if aql_state.parameters_valid {
+                "✓ Valid JSON"
+            } else {
+                "✗ Invalid JSON"
+            };
+            aql_state.parameters_textarea.set_block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(format!(
+                        "Query Parameters (JSON) - {}{} | F3: form",
+                        validation_msg,
+                        notes
+                            .iter()
+                            .map(|n| format!(" | {}", n))
+                            .collect::<String>()
+                    ))
+                    .border_style(params_border),
+            );
+            aql_state
+                .parameters_textarea
+                .set_cursor_line_style(Style::default());
+            aql_state
+                .parameters_textarea
+                .set_cursor_style(Style::default().add_modifier(Modifier::REVERSED));
+            f.render_widget(&aql_state.parameters_textarea, chunks[1]);
+        }
+
+        // Options textarea, with the schema violations below it
+        let options_valid = aql_state.options_errors.is_empty();
+        let options_validation_msg = if options_valid {
+            "✓ Valid".to_string()
         } else {
-            "✗ Invalid JSON"
+            format!("✗ {} problem(s)", aql_state.options_errors.len())
         };
+        let options_chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Min(3),
+                Constraint::Length(aql_state.options_errors.len().min(3) as u16),
+            ])
+            .split(chunks[2]);
 
-        aql_state.parameters_textarea.set_block(
+        aql_state.options_textarea.set_block(
             Block::default()
                 .borders(Borders::ALL)
-                .title(format!("Query Parameters (JSON) - {}", validation_msg))
+                .title(format!("Query Options (JSON) - {}", options_validation_msg))
                 .border_style(
-                    if matches!(aql_state.active_field, AqlInputField::Parameters) {
+                    if matches!(aql_state.active_field, AqlInputField::Options) {
                         Style::default().fg(Color::Cyan)
-                    } else if aql_state.parameters_valid {
+                    } else if options_valid {
                         Style::default()
                     } else {
                         Style::default().fg(Color::Red)
@@ -1627,76 +6075,736 @@ fn render_aql_query_input(
                 ),
         );
         aql_state
-            .parameters_textarea
+            .options_textarea
             .set_cursor_line_style(Style::default());
         aql_state
-            .parameters_textarea
+            .options_textarea
             .set_cursor_style(Style::default().add_modifier(Modifier::REVERSED));
-        f.render_widget(&aql_state.parameters_textarea, chunks[1]);
+        f.render_widget(&aql_state.options_textarea, options_chunks[0]);
+        let problems: Vec<Line> = aql_state
+            .options_errors
+            .iter()
+            .map(|e| Line::from(format!("✗ {}", e)))
+            .collect();
+        f.render_widget(
+            Paragraph::new(problems).style(Style::default().fg(Color::Red)),
+            options_chunks[1],
+        );
+
+        if let Some(error) = &aql_state.error {
+            let mut lines = Vec::new();
+            if let Some(num) = error.error_num {
+                lines.push(Line::from(Span::styled(
+                    format!("errorNum {}", num),
+                    Style::default().add_modifier(Modifier::BOLD),
+                )));
+            }
+            lines.push(Line::from(error.message.clone()));
+            let para = Paragraph::new(lines)
+                .style(Style::default().fg(Color::Red))
+                .wrap(ratatui::widgets::Wrap { trim: false })
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .title("Query Error")
+                        .border_style(Style::default().fg(Color::Red)),
+                );
+            f.render_widget(para, chunks[3]);
+        }
+
+        // Submit and explain buttons
+        let buttons = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([
+                Constraint::Percentage(50),
+                Constraint::Percentage(25),
+                Constraint::Percentage(25),
+            ])
+            .split(chunks[4]);
+        let button_style = |active: bool, color: Color| {
+            if active {
+                Style::default()
+                    .fg(Color::Black)
+                    .bg(color)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(color)
+            }
+        };
+
+        let submit_active = matches!(aql_state.active_field, AqlInputField::Submit);
+        let submit_text = if submit_active {
+            ">>> [ SUBMIT QUERY - Press ENTER ] <<<"
+        } else {
+            "[ SUBMIT QUERY - Ctrl+Enter / F5 ]"
+        };
+        let submit_widget = Paragraph::new(submit_text)
+            .style(button_style(submit_active, Color::Green))
+            .alignment(Alignment::Center)
+            .block(Block::default().borders(Borders::ALL));
+        f.render_widget(submit_widget, buttons[0]);
+
+        let explain_active = matches!(aql_state.active_field, AqlInputField::Explain);
+        let explain_text = if explain_active {
+            ">>> [ EXPLAIN - Press ENTER ] <<<"
+        } else {
+            "[ EXPLAIN - F6 ]"
+        };
+        let explain_widget = Paragraph::new(explain_text)
+            .style(button_style(explain_active, Color::Yellow))
+            .alignment(Alignment::Center)
+            .block(Block::default().borders(Borders::ALL));
+        f.render_widget(explain_widget, buttons[1]);
+
+        let profile_active = matches!(aql_state.active_field, AqlInputField::Profile);
+        let profile_text = if profile_active {
+            ">>> [ PROFILE - Press ENTER ] <<<"
+        } else {
+            "[ PROFILE - F7 ]"
+        };
+        let profile_widget = Paragraph::new(profile_text)
+            .style(button_style(profile_active, Color::Magenta))
+            .alignment(Alignment::Center)
+            .block(Block::default().borders(Borders::ALL));
+        f.render_widget(profile_widget, buttons[2]);
+    } else {
+        let error = Paragraph::new("AQL state not initialized")
+            .style(Style::default().fg(Color::Red))
+            .alignment(Alignment::Center)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(format!("AQL Query - {}", database)),
+            );
+        f.render_widget(error, area);
+    }
+}
+
+fn render_query_plan(f: &mut Frame, area: Rect, browser: &DatabaseBrowser, database: &str) {
+    use ratatui::widgets::{Cell, Row, Table, TableState, Wrap};
+
+    let Some(plan) = &browser.query_plan else {
+        return;
+    };
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(8), Constraint::Length(12)])
+        .split(area);
+
+    let rows: Vec<Row> = plan
+        .rows
+        .iter()
+        .map(|row| {
+            let mut cells = vec![
+                Cell::from(row.id.to_string()),
+                Cell::from(Line::from(vec![
+                    Span::styled(
+                        if row.depth == 0 {
+                            String::new()
+                        } else {
+                            format!("{}└ ", " ".repeat(row.depth - 1))
+                        },
+                        Style::default().fg(Color::DarkGray),
+                    ),
+                    Span::styled(
+                        row.node_type.clone(),
+                        Style::default()
+                            .fg(Color::Cyan)
+                            .add_modifier(Modifier::BOLD),
+                    ),
+                    Span::raw(" "),
+                    Span::raw(row.details.clone()),
+                ])),
+                Cell::from(row.estimated_items.map_or(String::new(), |n| n.to_string())),
+                Cell::from(
+                    row.estimated_cost
+                        .map_or(String::new(), |c| format!("{:.2}", c)),
+                ),
+            ];
+            if let Some(profile) = &plan.profile {
+                // Measured values of the node, empty if the node was optimized away
+                let measured = profile.node_stats.get(&row.id);
+                let value = |name: &str| {
+                    measured
+                        .and_then(|m| m[name].as_u64())
+                        .map_or(String::new(), |n| n.to_string())
+                };
+                cells.push(Cell::from(value("calls")));
+                cells.push(Cell::from(value("items")));
+                cells.push(Cell::from(
+                    measured
+                        .and_then(|m| m["runtime"].as_f64())
+                        .map_or(String::new(), |r| format!("{:.5} s", r)),
+                ));
+            }
+            Row::new(cells)
+        })
+        .collect();
+
+    let title = match &plan.profile {
+        Some(profile) => format!(
+            "Query Profile - {} | execution time {:.3} s | Q/ESC: back",
+            database,
+            profile.stats["executionTime"].as_f64().unwrap_or(0.0)
+        ),
+        None => format!(
+            "Execution Plan - {} | estimated cost {} | estimated items {} | Q/ESC: back",
+            database,
+            plan.estimated_cost
+                .map_or("?".to_string(), |c| format!("{:.2}", c)),
+            plan.estimated_items
+                .map_or("?".to_string(), |n| n.to_string())
+        ),
+    };
+    let mut widths = vec![
+        Constraint::Length(4),
+        Constraint::Min(30),
+        Constraint::Length(12),
+        Constraint::Length(12),
+    ];
+    let mut header = vec!["Id", "Node", "Est. items", "Est. cost"];
+    if plan.profile.is_some() {
+        widths.extend([
+            Constraint::Length(8),
+            Constraint::Length(10),
+            Constraint::Length(12),
+        ]);
+        header.extend(["Calls", "Items", "Runtime"]);
+    }
+    let table = Table::new(rows, widths)
+        .header(Row::new(header).style(Style::default().add_modifier(Modifier::BOLD)))
+        .block(Block::default().borders(Borders::ALL).title(title))
+        .row_highlight_style(Style::default().bg(Color::Blue));
+    let mut state = TableState::default().with_selected(Some(browser.selected_plan_row));
+    f.render_stateful_widget(table, chunks[0], &mut state);
+
+    let mut lines = Vec::new();
+    if let Some(profile) = &plan.profile {
+        lines.push(query_stats_line(&profile.stats));
+        let mut phases = vec![Span::styled(
+            "Phases: ",
+            Style::default().add_modifier(Modifier::BOLD),
+        )];
+        for (phase, seconds) in &profile.phases {
+            phases.push(Span::raw(format!("{} {:.5} s  ", phase, seconds)));
+        }
+        lines.push(Line::from(phases));
+    }
+    lines.push(Line::from(Span::styled(
+        "Indexes used:",
+        Style::default().add_modifier(Modifier::BOLD),
+    )));
+    if plan.indexes.is_empty() {
+        lines.push(Line::from(Span::styled(
+            "  none (full collection scans only)",
+            Style::default().fg(Color::Yellow),
+        )));
+    }
+    for index in &plan.indexes {
+        lines.push(Line::from(format!("  {}", index)));
+    }
+    lines.push(Line::from(vec![
+        Span::styled(
+            "Optimizer rules applied: ",
+            Style::default().add_modifier(Modifier::BOLD),
+        ),
+        Span::raw(if plan.rules.is_empty() {
+            "none".to_string()
+        } else {
+            plan.rules.join(", ")
+        }),
+    ]));
+    for warning in &plan.warnings {
+        lines.push(Line::from(Span::styled(
+            format!("Warning: {}", warning),
+            Style::default().fg(Color::Yellow),
+        )));
+    }
+    let para = Paragraph::new(lines).wrap(Wrap { trim: false }).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title("Indexes and Rules"),
+    );
+    f.render_widget(para, chunks[1]);
+}
+
+fn render_result_diff(f: &mut Frame, area: Rect, browser: &DatabaseBrowser, database: &str) {
+    use ratatui::widgets::{ListState, Wrap};
+
+    let Some(comparison) = &browser.result_comparison else {
+        return;
+    };
+    let diff = &comparison.diff;
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(35), Constraint::Percentage(65)])
+        .split(area);
+
+    let items: Vec<ListItem> = diff
+        .changes
+        .iter()
+        .map(|change| {
+            let color = match change.kind {
+                compare::ChangeKind::Added => Color::Green,
+                compare::ChangeKind::Removed => Color::Red,
+                compare::ChangeKind::Changed => Color::Yellow,
+            };
+            ListItem::new(Line::from(vec![
+                Span::styled(
+                    format!("{} ", change.kind.marker()),
+                    Style::default().fg(color).add_modifier(Modifier::BOLD),
+                ),
+                Span::raw(change.key.clone()),
+            ]))
+        })
+        .collect();
+    let title = format!(
+        "Result Diff - {} | by {} | +{} -{} ~{} ={} | A: attribute | Q/ESC: back",
+        database,
+        comparison.attribute,
+        diff.count(compare::ChangeKind::Added),
+        diff.count(compare::ChangeKind::Removed),
+        diff.count(compare::ChangeKind::Changed),
+        diff.unchanged
+    );
+    let list = List::new(items)
+        .block(Block::default().borders(Borders::ALL).title(title))
+        .highlight_style(Style::default().bg(Color::Blue));
+    let mut state = ListState::default();
+    state.select((!diff.changes.is_empty()).then_some(comparison.selected));
+    f.render_stateful_widget(list, chunks[0], &mut state);
+
+    let pretty = |value: &Option<serde_json::Value>| {
+        value
+            .as_ref()
+            .map(|v| serde_json::to_string_pretty(v).unwrap_or_default())
+            .unwrap_or_default()
+    };
+    let lines = match diff.changes.get(comparison.selected) {
+        Some(change) => diff_to_lines(&diff_lines(&pretty(&change.old), &pretty(&change.new))),
+        None => vec![Line::styled(
+            "No differences",
+            Style::default().fg(Color::Green),
+        )],
+    };
+    let para = Paragraph::new(lines)
+        .wrap(Wrap { trim: false })
+        .scroll((comparison.scroll, 0))
+        .block(Block::default().borders(Borders::ALL).title(format!(
+            "{} | - pinned, + current | PgUp/PgDn: scroll",
+            comparison.title
+        )));
+    f.render_widget(para, chunks[1]);
+}
+
+fn render_query_monitor(f: &mut Frame, area: Rect, browser: &DatabaseBrowser, database: &str) {
+    use ratatui::widgets::{Cell, Row, Table, TableState, Wrap};
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Percentage(55), Constraint::Percentage(45)])
+        .split(area);
+
+    let rows: Vec<Row> = browser
+        .monitor_queries
+        .iter()
+        .map(|q| {
+            let first_line = q.query.lines().find(|l| !l.trim().is_empty()).unwrap_or("");
+            Row::new(vec![
+                Cell::from(q.id.clone()),
+                Cell::from(format!("{:.2} s", q.run_time)),
+                Cell::from(q.state.clone()),
+                Cell::from(q.user.clone()),
+                Cell::from(q.started.clone()),
+                Cell::from(first_line.trim().to_string()),
+            ])
+        })
+        .collect();
+
+    let (label, other, actions) = if browser.monitor_show_slow {
+        ("Slow Queries", "running", "")
+    } else {
+        ("Running Queries", "slow", " | K/Del: kill")
+    };
+    let title = format!(
+        "{} ({}) - {} | refreshed every {} s | TAB: {} | Enter: copy to editor{} | R: refresh | Q/ESC: back",
+        label,
+        browser.monitor_queries.len(),
+        database,
+        MONITOR_REFRESH_INTERVAL.as_secs(),
+        other,
+        actions
+    );
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(10),
+            Constraint::Length(11),
+            Constraint::Length(12),
+            Constraint::Length(12),
+            Constraint::Length(20),
+            Constraint::Min(20),
+        ],
+    )
+    .header(
+        Row::new(vec!["Id", "Runtime", "State", "User", "Started", "Query"])
+            .style(Style::default().add_modifier(Modifier::BOLD)),
+    )
+    .block(Block::default().borders(Borders::ALL).title(title))
+    .row_highlight_style(Style::default().bg(Color::Blue));
+    let mut state = TableState::default().with_selected(Some(browser.selected_monitor_index));
+    f.render_stateful_widget(table, chunks[0], &mut state);
+
+    let mut details = Vec::new();
+    match browser.monitor_queries.get(browser.selected_monitor_index) {
+        Some(query) => {
+            details.extend(aql::highlight(&query.query));
+            if query.bind_vars.as_object().is_some_and(|o| !o.is_empty()) {
+                details.push(Line::from(""));
+                details.push(Line::from(Span::styled(
+                    format!("Bind parameters: {}", query.bind_vars),
+                    Style::default().fg(Color::Cyan),
+                )));
+            }
+        }
+        None => details.push(Line::from(Span::styled(
+            if browser.monitor_show_slow {
+                "No slow queries recorded"
+            } else {
+                "No queries running"
+            },
+            Style::default().fg(Color::Yellow),
+        ))),
+    }
+    let para = Paragraph::new(details)
+        .wrap(Wrap { trim: false })
+        .block(Block::default().borders(Borders::ALL).title("Query"));
+    f.render_widget(para, chunks[1]);
+}
+
+fn render_query_history(f: &mut Frame, area: Rect, browser: &DatabaseBrowser, database: &str) {
+    use ratatui::widgets::{Cell, Row, Table, TableState, Wrap};
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Percentage(55), Constraint::Percentage(45)])
+        .split(area);
+
+    let records = browser.visible_history();
+    let rows: Vec<Row> = records
+        .iter()
+        .map(|r| {
+            let first_line = r.query.lines().find(|l| !l.trim().is_empty()).unwrap_or("");
+            let status = match &r.error {
+                Some(_) => Cell::from("error").style(Style::default().fg(Color::Red)),
+                None if r.partial => Cell::from(format!("{}+", r.result_count)),
+                None => Cell::from(r.result_count.to_string()),
+            };
+            Row::new(vec![
+                Cell::from(r.name.clone().unwrap_or_default())
+                    .style(Style::default().fg(Color::Yellow)),
+                Cell::from(history::format_timestamp(r.timestamp)),
+                Cell::from(r.database.clone()),
+                Cell::from(if r.partial {
+                    format!("{} ms+", r.duration_ms)
+                } else {
+                    format!("{} ms", r.duration_ms)
+                }),
+                status,
+                Cell::from(first_line.trim().to_string()),
+            ])
+        })
+        .collect();
+
+    let (label, other) = if browser.history_show_saved {
+        ("Saved Queries", "history")
+    } else {
+        ("Query History", "saved")
+    };
+    let mut title = format!(
+        "{} ({}) - {} | TAB: {} | /: search | Enter: load | S: save",
+        label,
+        records.len(),
+        database,
+        other
+    );
+    if browser.history_show_saved {
+        title.push_str(" | D: delete");
+    }
+    title.push_str(" | Q/ESC: back");
+    if !browser.history_filter.is_empty() {
+        title.push_str(&format!(" | search: {}", browser.history_filter));
+    }
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(if browser.history_show_saved { 16 } else { 0 }),
+            Constraint::Length(19),
+            Constraint::Length(12),
+            Constraint::Length(10),
+            Constraint::Length(8),
+            Constraint::Min(20),
+        ],
+    )
+    .header(
+        Row::new(vec![
+            "Name",
+            "Executed (UTC)",
+            "Database",
+            "Duration",
+            "Results",
+            "Query",
+        ])
+        .style(Style::default().add_modifier(Modifier::BOLD)),
+    )
+    .block(Block::default().borders(Borders::ALL).title(title))
+    .row_highlight_style(Style::default().bg(Color::Blue));
+    let mut state = TableState::default().with_selected(Some(browser.selected_history_index));
+    f.render_stateful_widget(table, chunks[0], &mut state);
+
+    let mut preview = Vec::new();
+    if let Some(record) = records.get(browser.selected_history_index) {
+        preview.extend(aql::highlight(&record.query));
+        if record.bind_vars.as_object().is_some_and(|o| !o.is_empty()) {
+            preview.push(Line::from(""));
+            preview.push(Line::from(Span::styled(
+                format!("Bind parameters: {}", record.bind_vars),
+                Style::default().fg(Color::Cyan),
+            )));
+        }
+        if let Some(error) = &record.error {
+            preview.push(Line::from(""));
+            preview.push(Line::from(Span::styled(
+                error.clone(),
+                Style::default().fg(Color::Red),
+            )));
+        }
+    }
+    let para = Paragraph::new(preview)
+        .wrap(Wrap { trim: false })
+        .block(Block::default().borders(Borders::ALL).title("Query"));
+    f.render_widget(para, chunks[1]);
+}
+
+// Results as a table, with the selected cell expanded in a side pane on demand
+// Note on the results dropped from memory, if any
+fn results_window_note(aql_state: &AqlState) -> String {
+    if aql_state.offset > 0 {
+        format!(" (first {} dropped from memory)", aql_state.offset)
+    } else {
+        String::new()
+    }
+}
 
-        // Options textarea
-        let options_validation_msg = if aql_state.options_valid {
-            "✓ Valid JSON"
+fn render_result_table(f: &mut Frame, area: Rect, aql_state: &AqlState, database: &str) {
+    use ratatui::widgets::{Cell, Row, Table, TableState};
+
+    let table = &aql_state.table;
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints(if table.show_detail {
+            [Constraint::Percentage(60), Constraint::Percentage(40)]
         } else {
-            "✗ Invalid JSON"
-        };
+            [Constraint::Percentage(100), Constraint::Percentage(0)]
+        })
+        .split(area);
 
-        aql_state.options_textarea.set_block(
-            Block::default()
-                .borders(Borders::ALL)
-                .title(format!("Query Options (JSON) - {}", options_validation_msg))
-                .border_style(
-                    if matches!(aql_state.active_field, AqlInputField::Options) {
-                        Style::default().fg(Color::Cyan)
-                    } else if aql_state.options_valid {
+    // Scroll horizontally just far enough to show the selected column
+    let visible = table.visible_columns();
+    let available = chunks[0].width.saturating_sub(2);
+    let selected = visible
+        .iter()
+        .position(|&i| i == table.selected_column)
+        .unwrap_or(0);
+    let mut first = selected;
+    let mut used = 0;
+    while let Some(&column) = visible.get(first) {
+        used += table.columns[column].width + 1;
+        if used > available || first == 0 {
+            if used > available && first < selected {
+                first += 1;
+            }
+            break;
+        }
+        first -= 1;
+    }
+    let mut shown = Vec::new();
+    let mut used = 0;
+    for &column in &visible[first.min(visible.len())..] {
+        used += table.columns[column].width + 1;
+        if used > available && !shown.is_empty() {
+            break;
+        }
+        shown.push(column);
+    }
+
+    // Only build the rows of the page holding the selection
+    let height = (chunks[0].height as usize).saturating_sub(3).max(1);
+    let page_start = table.selected_row / height * height;
+    let rows: Vec<Row> = table
+        .order
+        .iter()
+        .skip(page_start)
+        .take(height)
+        .enumerate()
+        .map(|(i, &index)| {
+            let result = &aql_state.results[index];
+            Row::new(shown.iter().map(|&column| {
+                let cell = Cell::from(results::cell_text(result, &table.columns[column].name));
+                if column == table.selected_column && page_start + i == table.selected_row {
+                    cell.style(
                         Style::default()
-                    } else {
-                        Style::default().fg(Color::Red)
-                    },
-                ),
-        );
-        aql_state
-            .options_textarea
-            .set_cursor_line_style(Style::default());
-        aql_state
-            .options_textarea
-            .set_cursor_style(Style::default().add_modifier(Modifier::REVERSED));
-        f.render_widget(&aql_state.options_textarea, chunks[2]);
+                            .fg(Color::Black)
+                            .bg(Color::Cyan)
+                            .add_modifier(Modifier::BOLD),
+                    )
+                } else {
+                    cell
+                }
+            }))
+        })
+        .collect();
 
-        // Submit button
-        let submit_text = if matches!(aql_state.active_field, AqlInputField::Submit) {
-            ">>> [ SUBMIT QUERY - Press ENTER ] <<<"
+    let header = Row::new(shown.iter().map(|&column| {
+        let marker = match table.sort {
+            Some((c, results::SortDirection::Ascending)) if c == column => " ▲",
+            Some((c, results::SortDirection::Descending)) if c == column => " ▼",
+            _ => "",
+        };
+        let style = if column == table.selected_column {
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD | Modifier::UNDERLINED)
         } else {
-            "[ SUBMIT QUERY - Press TAB then ENTER ]"
+            Style::default().add_modifier(Modifier::BOLD)
         };
+        Cell::from(format!("{}{}", table.columns[column].name, marker)).style(style)
+    }));
 
-        let submit_widget = Paragraph::new(submit_text)
-            .style(if matches!(aql_state.active_field, AqlInputField::Submit) {
-                Style::default()
-                    .fg(Color::Black)
-                    .bg(Color::Green)
-                    .add_modifier(Modifier::BOLD)
-            } else {
-                Style::default().fg(Color::Green)
-            })
-            .alignment(Alignment::Center)
-            .block(Block::default().borders(Borders::ALL));
-        f.render_widget(submit_widget, chunks[3]);
-    } else {
-        let error = Paragraph::new("AQL state not initialized")
-            .style(Style::default().fg(Color::Red))
-            .alignment(Alignment::Center)
+    let hidden = table.columns.len() - visible.len();
+    let title = format!(
+        "AQL Query Results - {} | Row {}/{}{}{} | Column {}/{}{} | ←→↑↓: move | +/-: width | x: hide | X: show all | s: sort | Enter: expand | T: JSON | P: pin | C: compare | Q/ESC: back",
+        database,
+        aql_state.offset + table.selected_row + 1,
+        aql_state.total_fetched,
+        if aql_state.has_more { "+" } else { "" },
+        results_window_note(aql_state),
+        selected + 1,
+        visible.len(),
+        if hidden > 0 {
+            format!(" ({} hidden)", hidden)
+        } else {
+            String::new()
+        },
+    );
+    let widths: Vec<Constraint> = shown
+        .iter()
+        .map(|&column| Constraint::Length(table.columns[column].width))
+        .collect();
+    let widget = Table::new(rows, widths)
+        .header(header)
+        .block(Block::default().borders(Borders::ALL).title(title))
+        .row_highlight_style(Style::default().bg(Color::DarkGray));
+    let mut state = TableState::default().with_selected(Some(table.selected_row - page_start));
+    f.render_stateful_widget(widget, chunks[0], &mut state);
+
+    if table.show_detail {
+        let column = &table.columns[table.selected_column].name;
+        let value = table
+            .selected_result()
+            .and_then(|index| results::cell_value(&aql_state.results[index], column));
+        let text = match value {
+            Some(value) => serde_json::to_string_pretty(value).unwrap_or_default(),
+            None => "(attribute not set)".to_string(),
+        };
+        let para = Paragraph::new(text)
+            .wrap(ratatui::widgets::Wrap { trim: false })
             .block(
                 Block::default()
                     .borders(Borders::ALL)
-                    .title(format!("AQL Query - {}", database)),
+                    .title(format!("{} | Enter/ESC: close", column))
+                    .border_style(Style::default().fg(Color::Cyan)),
             );
-        f.render_widget(error, area);
+        f.render_widget(para, chunks[1]);
     }
 }
 
 fn render_aql_query_results(f: &mut Frame, area: Rect, browser: &DatabaseBrowser, database: &str) {
-    if let Some(aql_state) = &browser.aql_state {
+    if let Some(aql_state) = browser.aql_tabs.get(browser.active_aql_tab) {
+        // Show the executed query above its results
+        let query = aql_state.query_textarea.lines().join("\n");
+        let query_height = query.lines().count().clamp(1, 5) as u16 + 2;
+        // Stats, warnings and a failed follow-up batch below the results
+        let mut info = Vec::new();
+        let mut has_warnings = aql_state.error.is_some();
+        if let Some(extra) = &aql_state.extra {
+            if extra["stats"].is_object() {
+                info.push(query_stats_line(&extra["stats"]));
+            }
+            for warning in extra["warnings"].as_array().into_iter().flatten() {
+                has_warnings = true;
+                info.push(Line::from(Span::styled(
+                    format!(
+                        "Warning {}: {}",
+                        warning["code"],
+                        warning["message"].as_str().unwrap_or("?")
+                    ),
+                    Style::default().fg(Color::Yellow),
+                )));
+            }
+        }
+        if let Some(error) = &aql_state.error {
+            info.push(Line::from(Span::styled(
+                format!("Error: {}", error),
+                Style::default().fg(Color::Red),
+            )));
+        }
+        let info_height = if info.is_empty() {
+            0
+        } else {
+            info.len().min(6) as u16 + 2
+        };
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(query_height),
+                Constraint::Min(0),
+                Constraint::Length(info_height),
+            ])
+            .split(area);
+        let mut title = format!("Query - {}", aql_state.name);
+        if let Some(count) = aql_state.count {
+            title.push_str(&format!(" | count: {}", count));
+        }
+        if let Some(full_count) = aql_state
+            .extra
+            .as_ref()
+            .and_then(|extra| extra["stats"]["fullCount"].as_u64())
+        {
+            title.push_str(&format!(" | fullCount: {}", full_count));
+        }
+        if aql_state.cached {
+            title.push_str(" | cached");
+        }
+        let query_para = Paragraph::new(aql::highlight(&query))
+            .block(Block::default().borders(Borders::ALL).title(title));
+        f.render_widget(query_para, chunks[0]);
+        if !info.is_empty() {
+            let info_para =
+                Paragraph::new(info).block(Block::default().borders(Borders::ALL).title(
+                    if has_warnings {
+                        "Stats and Warnings"
+                    } else {
+                        "Stats"
+                    },
+                ));
+            f.render_widget(info_para, chunks[2]);
+        }
+        let area = chunks[1];
+
         if aql_state.is_fetching {
             // Show progress bar
             let chunks = Layout::default()
@@ -1707,7 +6815,14 @@ fn render_aql_query_results(f: &mut Frame, area: Rect, browser: &DatabaseBrowser
                 ])
                 .split(area);
 
-            let progress_text = format!("Fetching documents: {} fetched", aql_state.total_fetched);
+            let elapsed = browser
+                .query_execution
+                .as_ref()
+                .map_or(0.0, |e| e.started.elapsed().as_secs_f64());
+            let progress_text = format!(
+                "Fetching documents: {} fetched | {:.1} s | ESC/Ctrl+C: cancel",
+                aql_state.total_fetched, elapsed
+            );
             let progress_para = Paragraph::new(progress_text)
                 .style(Style::default().fg(Color::Yellow))
                 .alignment(Alignment::Center)
@@ -1733,14 +6848,17 @@ fn render_aql_query_results(f: &mut Frame, area: Rect, browser: &DatabaseBrowser
                         .title(format!("AQL Query Results - {}", database)),
                 );
             f.render_widget(empty, area);
+        } else if aql_state.table_mode {
+            render_result_table(f, area, aql_state, database);
         } else {
             // Display results with pagination
-            let page_size = 100; // ~100 lines per page, but complete documents
+            let page_size = RESULT_PAGE_SIZE; // ~100 lines per page, but complete documents
 
             // Calculate which documents to show
             let mut lines_in_page = Vec::new();
             let mut current_line_count = 0;
-            let start_doc_idx = aql_state.current_page * page_size;
+            let start_doc_idx =
+                (aql_state.current_page * page_size).saturating_sub(aql_state.offset);
             let mut docs_in_page = 0;
 
             for (idx, doc) in aql_state.results.iter().enumerate().skip(start_doc_idx) {
@@ -1762,14 +6880,16 @@ fn render_aql_query_results(f: &mut Frame, area: Rect, browser: &DatabaseBrowser
                 docs_in_page += 1;
             }
 
-            let total_pages = (aql_state.results.len() + page_size - 1) / page_size;
+            let total_pages = aql_state.total_fetched.div_ceil(page_size);
 
             let title = format!(
-                "AQL Query Results - {} | Page {}/{} | {} docs | ← → : pages | ↑ ↓ PgUp PgDn: scroll | Q/ESC: back",
+                "AQL Query Results - {} | Page {}/{}{} | {} docs{} | ← → : pages | ↑ ↓ PgUp PgDn: scroll | T: table | W: export | P: pin | C: compare | Q/ESC: back",
                 database,
                 aql_state.current_page + 1,
                 total_pages.max(1),
-                aql_state.results.len()
+                if aql_state.has_more { "+" } else { "" },
+                aql_state.total_fetched,
+                results_window_note(aql_state),
             );
 
             let para = Paragraph::new(lines_in_page)
@@ -2004,67 +7124,14 @@ fn render_gae_jobs(f: &mut Frame, area: Rect, browser: &GaeBrowser) {
 
 fn render_gae_load_graph(f: &mut Frame, area: Rect, browser: &mut GaeBrowser) {
     if let Some(load_state) = &mut browser.load_graph_state {
-        let chunks = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([
-                Constraint::Min(10),   // JSON input
-                Constraint::Length(3), // Submit button
-            ])
-            .split(area);
-
-        // JSON textarea
-        let validation_msg = if load_state.json_valid {
-            "✓ Valid JSON"
-        } else {
-            "✗ Invalid JSON"
-        };
-
-        load_state.textarea.set_block(
-            Block::default()
-                .borders(Borders::ALL)
-                .title(format!(
-                    "Load Graph Configuration (JSON) - {} | TAB: Switch fields | Q/ESC: Back",
-                    validation_msg
-                ))
-                .border_style(
-                    if matches!(load_state.active_field, LoadGraphField::JsonInput) {
-                        if load_state.json_valid {
-                            Style::default().fg(Color::Cyan)
-                        } else {
-                            Style::default().fg(Color::Red)
-                        }
-                    } else {
-                        Style::default()
-                    },
-                ),
+        render_json_form(
+            f,
+            area,
+            load_state,
+            "Load Graph Configuration (JSON)",
+            "TAB: switch fields | ESC: back",
+            "LOAD GRAPH",
         );
-        load_state.textarea.set_cursor_line_style(Style::default());
-        load_state
-            .textarea
-            .set_cursor_style(Style::default().add_modifier(Modifier::REVERSED));
-        f.render_widget(&load_state.textarea, chunks[0]);
-
-        // Submit button
-        let submit_text = if matches!(load_state.active_field, LoadGraphField::Submit) {
-            ">>> [ SUBMIT - Press ENTER to load graph ] <<<"
-        } else {
-            "[ SUBMIT - Press TAB then ENTER ]"
-        };
-
-        let submit_widget = Paragraph::new(submit_text)
-            .style(
-                if matches!(load_state.active_field, LoadGraphField::Submit) {
-                    Style::default()
-                        .fg(Color::Black)
-                        .bg(Color::Green)
-                        .add_modifier(Modifier::BOLD)
-                } else {
-                    Style::default().fg(Color::Green)
-                },
-            )
-            .alignment(Alignment::Center)
-            .block(Block::default().borders(Borders::ALL));
-        f.render_widget(submit_widget, chunks[1]);
     } else {
         let error = Paragraph::new("Load graph state not initialized")
             .style(Style::default().fg(Color::Red))
@@ -2083,41 +7150,690 @@ async fn run_database_browser(
     app_state: &AppState,
 ) -> Result<()> {
     let mut browser = DatabaseBrowser::new();
+    if app_state.restore_tabs {
+        browser.restore_aql_tabs();
+    }
+    let result = browse_databases(terminal, app_state, &mut browser).await;
+
+    // However the browser is left, even on an error, stop the running query
+    // and release the result cursors on the server
+    browser.cancel_query_execution(app_state).await;
+    browser.delete_result_cursors(app_state).await;
+    result
+}
+
+async fn browse_databases(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    app_state: &AppState,
+    browser: &mut DatabaseBrowser,
+) -> Result<()> {
     browser.load_databases(app_state).await?;
 
-    loop {
-        terminal.draw(|f| {
-            match &browser.view.clone() {
-                BrowserView::DatabaseList => render_database_list(f, f.area(), &browser),
-                BrowserView::CollectionList(db) => {
-                    render_collection_list(f, f.area(), &browser, db)
+    loop {
+        browser.poll_query_execution();
+        if let BrowserView::QueryMonitor(db) = browser.view.clone()
+            && browser.query_monitor_due()
+        {
+            browser.refresh_query_monitor(app_state, &db).await;
+        }
+
+        terminal.draw(|f| {
+            match &browser.view.clone() {
+                BrowserView::DatabaseList => render_database_list(f, f.area(), browser),
+                BrowserView::CollectionList(db) => render_collection_list(f, f.area(), browser, db),
+                BrowserView::GraphList(db) => render_graph_list(f, f.area(), browser, db),
+                BrowserView::CollectionProperties(db, coll) => {
+                    render_collection_properties(f, f.area(), browser, db, coll)
+                }
+                BrowserView::DocumentViewer(db, coll) => {
+                    render_document_viewer(f, f.area(), browser, db, coll)
+                }
+                BrowserView::DocumentEditor(db, coll) => {
+                    render_document_editor(f, f.area(), browser, db, coll)
+                }
+                BrowserView::DocumentDetail(db, coll, key) => {
+                    render_document_detail(f, f.area(), browser, db, coll, key)
+                }
+                BrowserView::Indexes(db, coll) => render_indexes(f, f.area(), browser, db, coll),
+                BrowserView::CreateIndex(db, coll) => {
+                    if let Some(form) = &mut browser.form_state {
+                        render_json_form(
+                            f,
+                            f.area(),
+                            form,
+                            &format!("Create Index on {}.{}", db, coll),
+                            FORM_KEYS,
+                            "CREATE INDEX",
+                        );
+                    }
+                }
+                BrowserView::EditCollectionProperties(db, coll) => {
+                    if let Some(form) = &mut browser.form_state {
+                        render_json_form(
+                            f,
+                            f.area(),
+                            form,
+                            &format!("Edit Collection Properties: {}.{}", db, coll),
+                            FORM_KEYS,
+                            "SAVE PROPERTIES",
+                        );
+                    }
+                }
+                BrowserView::SchemaEditor(db, coll) => {
+                    render_schema_editor(f, f.area(), browser, db, coll)
+                }
+                BrowserView::CreateCollection(db) => {
+                    if let Some(form) = &mut browser.form_state {
+                        render_json_form(
+                            f,
+                            f.area(),
+                            form,
+                            &format!(
+                                "Create Collection in {} (type: \"document\" or \"edge\")",
+                                db
+                            ),
+                            FORM_KEYS,
+                            "CREATE COLLECTION",
+                        );
+                    }
+                }
+                BrowserView::GraphProperties(db, graph) => {
+                    render_graph_properties(f, f.area(), browser, db, graph)
+                }
+                BrowserView::AqlQueryInput(db) => render_aql_query_input(f, f.area(), browser, db),
+                BrowserView::AqlQueryResults(db) => {
+                    render_aql_query_results(f, f.area(), browser, db)
+                }
+                BrowserView::AqlHistory(db) => render_query_history(f, f.area(), browser, db),
+                BrowserView::AqlExplain(db) => render_query_plan(f, f.area(), browser, db),
+                BrowserView::QueryMonitor(db) => render_query_monitor(f, f.area(), browser, db),
+                BrowserView::ResultDiff(db) => render_result_diff(f, f.area(), browser, db),
+            }
+
+            // Render input dialog on top if active
+            match &browser.input_state {
+                InputState::None => {}
+                InputState::EnteringDocumentCount(input) => render_input_dialog(
+                    f,
+                    f.area(),
+                    "Browse Documents",
+                    "Enter number of documents per page:",
+                    input,
+                ),
+                InputState::EditingDocumentFilter(input) => render_input_dialog(
+                    f,
+                    f.area(),
+                    "Filter Documents",
+                    "e.g. status == \"active\" && age > 30 (empty clears)",
+                    input,
+                ),
+                InputState::EditingDocumentSort(input) => render_input_dialog(
+                    f,
+                    f.area(),
+                    "Sort Documents",
+                    "Attribute [asc|desc], TAB: next attribute (empty clears)",
+                    input,
+                ),
+                InputState::EnteringDocumentHandle(input) => render_input_dialog(
+                    f,
+                    f.area(),
+                    "Go to Document",
+                    "Enter [db:]collection/key or _key:",
+                    input,
+                ),
+                InputState::ConfirmTruncateCollection(name) => render_confirm_dialog(
+                    f,
+                    f.area(),
+                    "Truncate Collection",
+                    &format!("Remove all documents from collection {}?", name),
+                ),
+                InputState::ConfirmDropCollection(name, input) => render_input_dialog(
+                    f,
+                    f.area(),
+                    "Drop Collection",
+                    &format!("Type '{}' to drop the collection:", name),
+                    input,
+                ),
+                InputState::RenamingCollection(name, input) => render_input_dialog(
+                    f,
+                    f.area(),
+                    "Rename Collection",
+                    &format!("New name for collection {}:", name),
+                    input,
+                ),
+                InputState::SearchingHistory(input) => render_input_dialog(
+                    f,
+                    f.area(),
+                    "Search Queries",
+                    "Search query text, name or database:",
+                    input,
+                ),
+                InputState::EnteringCompareAttribute(input) => render_input_dialog(
+                    f,
+                    f.area(),
+                    "Compare Results",
+                    "Match results by attribute (e.g. _key or address.city):",
+                    input,
+                ),
+                InputState::RenamingAqlTab(input) => render_input_dialog(
+                    f,
+                    f.area(),
+                    "Rename Query Tab",
+                    "Name of the query tab:",
+                    input,
+                ),
+                InputState::NamingSavedQuery(input) => render_input_dialog(
+                    f,
+                    f.area(),
+                    "Save Query",
+                    "Name for the saved query (an existing name is replaced):",
+                    input,
+                ),
+                InputState::EnteringExportPath(format, input) => render_input_dialog(
+                    f,
+                    f.area(),
+                    "Export",
+                    &format!("Export as {} to (TAB: change format):", format.label()),
+                    input,
+                ),
+                InputState::EnteringExportColumns(_, input) => render_input_dialog(
+                    f,
+                    f.area(),
+                    "Export CSV Columns",
+                    "Comma separated columns, nested as a.b (empty: from the first documents):",
+                    input,
+                ),
+                InputState::EnteringSchemaSampleSize(input) => render_input_dialog(
+                    f,
+                    f.area(),
+                    "Schema Sample",
+                    "Number of documents to test the schema against:",
+                    input,
+                ),
+                InputState::ConfirmKillQuery(id) => {
+                    render_confirm_dialog(f, f.area(), "Kill Query", &format!("Kill query {}?", id))
+                }
+                InputState::ConfirmDropIndex(id) => {
+                    render_confirm_dialog(f, f.area(), "Drop Index", &format!("Drop index {}?", id))
+                }
+                InputState::ConfirmDeleteDocument(key) => {
+                    if let BrowserView::DocumentViewer(_, coll) = &browser.view {
+                        render_confirm_dialog(
+                            f,
+                            f.area(),
+                            "Delete Document",
+                            &format!("Delete document {}/{}?", coll, key),
+                        );
+                    }
+                }
+            }
+
+            if let Some(message) = &browser.status_message {
+                render_status_message(f, f.area(), message);
+            }
+        })?;
+
+        // Redraw regularly while a query runs or the query monitor is open
+        let ticking = browser.query_execution.is_some()
+            || matches!(browser.view, BrowserView::QueryMonitor(_));
+        if ticking && !event::poll(std::time::Duration::from_millis(100))? {
+            continue;
+        }
+
+        if let Event::Key(key) = event::read()? {
+            if key.kind == KeyEventKind::Press {
+                // Any key dismisses the status message
+                browser.status_message = None;
+
+                if let InputState::ConfirmDeleteDocument(_) = browser.input_state {
+                    if let KeyCode::Char('y') | KeyCode::Char('Y') = key.code
+                        && let BrowserView::DocumentViewer(db, coll) = browser.view.clone()
+                    {
+                        browser
+                            .delete_selected_document(app_state, &db, &coll)
+                            .await?;
+                    }
+                    browser.input_state = InputState::None;
+                    continue;
+                }
+
+                if let InputState::ConfirmKillQuery(ref id) = browser.input_state {
+                    let id = id.clone();
+                    browser.input_state = InputState::None;
+                    if let KeyCode::Char('y') | KeyCode::Char('Y') = key.code
+                        && let BrowserView::QueryMonitor(db) = browser.view.clone()
+                    {
+                        browser.kill_monitored_query(app_state, &db, &id).await;
+                    }
+                    continue;
+                }
+
+                if let InputState::ConfirmDropIndex(ref id) = browser.input_state {
+                    let id = id.clone();
+                    browser.input_state = InputState::None;
+                    if let KeyCode::Char('y') | KeyCode::Char('Y') = key.code
+                        && let BrowserView::Indexes(db, coll) = browser.view.clone()
+                    {
+                        browser.status_message = Some(
+                            match drop_index(
+                                &app_state.http_client,
+                                &app_state.arango_endpoint,
+                                &db,
+                                &id,
+                                &app_state.username,
+                                &app_state.password,
+                            )
+                            .await
+                            {
+                                Ok(()) => match browser.load_indexes(app_state, &db, &coll).await {
+                                    Ok(()) => format!("Index {} dropped", id),
+                                    Err(e) => {
+                                        format!("Index {} dropped; reloading failed: {}", id, e)
+                                    }
+                                },
+                                Err(e) => e.to_string(),
+                            },
+                        );
+                    }
+                    continue;
+                }
+
+                if let InputState::ConfirmTruncateCollection(ref name) = browser.input_state {
+                    let name = name.clone();
+                    browser.input_state = InputState::None;
+                    if let KeyCode::Char('y') | KeyCode::Char('Y') = key.code
+                        && let BrowserView::CollectionList(db) = browser.view.clone()
+                    {
+                        browser.status_message = Some(
+                            match truncate_collection(
+                                &app_state.http_client,
+                                &app_state.arango_endpoint,
+                                &db,
+                                &name,
+                                &app_state.username,
+                                &app_state.password,
+                            )
+                            .await
+                            {
+                                Ok(()) => match browser
+                                    .reload_collections(app_state, &db, Some(&name))
+                                    .await
+                                {
+                                    Ok(()) => format!("Collection {} truncated", name),
+                                    Err(e) => format!(
+                                        "Collection {} truncated; reloading failed: {}",
+                                        name, e
+                                    ),
+                                },
+                                Err(e) => e.to_string(),
+                            },
+                        );
+                    }
+                    continue;
+                }
+
+                if let InputState::ConfirmDropCollection(ref name, ref mut input)
+                | InputState::RenamingCollection(ref name, ref mut input) = browser.input_state
+                {
+                    match key.code {
+                        KeyCode::Esc => browser.input_state = InputState::None,
+                        KeyCode::Backspace => {
+                            input.pop();
+                        }
+                        KeyCode::Char(c) => input.push(c),
+                        KeyCode::Enter => {
+                            let name = name.clone();
+                            let input = input.trim().to_string();
+                            let is_drop = matches!(
+                                browser.input_state,
+                                InputState::ConfirmDropCollection(..)
+                            );
+                            let BrowserView::CollectionList(db) = browser.view.clone() else {
+                                browser.input_state = InputState::None;
+                                continue;
+                            };
+
+                            if is_drop && input != name {
+                                browser.status_message = Some(format!(
+                                    "Type the collection name '{}' exactly to drop it",
+                                    name
+                                ));
+                                continue;
+                            }
+                            if !is_drop && input.is_empty() {
+                                continue;
+                            }
+
+                            browser.input_state = InputState::None;
+                            let result = if is_drop {
+                                let is_system = browser
+                                    .collections
+                                    .iter()
+                                    .any(|c| c.info.name == name && c.info.is_system);
+                                drop_collection(
+                                    &app_state.http_client,
+                                    &app_state.arango_endpoint,
+                                    &db,
+                                    &name,
+                                    is_system,
+                                    &app_state.username,
+                                    &app_state.password,
+                                )
+                                .await
+                                .map(|()| (None, format!("Collection {} dropped", name)))
+                            } else {
+                                rename_collection(
+                                    &app_state.http_client,
+                                    &app_state.arango_endpoint,
+                                    &db,
+                                    &name,
+                                    &input,
+                                    &app_state.username,
+                                    &app_state.password,
+                                )
+                                .await
+                                .map(|()| {
+                                    (
+                                        Some(input.clone()),
+                                        format!("Collection {} renamed to {}", name, input),
+                                    )
+                                })
+                            };
+
+                            match result {
+                                Ok((select, message)) => {
+                                    browser.status_message = Some(
+                                        match browser
+                                            .reload_collections(app_state, &db, select.as_deref())
+                                            .await
+                                        {
+                                            Ok(()) => message,
+                                            Err(e) => {
+                                                format!("{}; reloading failed: {}", message, e)
+                                            }
+                                        },
+                                    );
+                                }
+                                Err(e) => browser.status_message = Some(e.to_string()),
+                            }
+                        }
+                        _ => {}
+                    }
+                    continue;
+                }
+
+                if let InputState::EnteringDocumentHandle(ref mut input) = browser.input_state {
+                    match key.code {
+                        KeyCode::Esc => browser.input_state = InputState::None,
+                        KeyCode::Backspace => {
+                            input.pop();
+                        }
+                        KeyCode::Char(c) => input.push(c),
+                        KeyCode::Enter => {
+                            let (database, collection) = match &browser.view {
+                                BrowserView::CollectionList(db) => (db.clone(), None),
+                                BrowserView::DocumentViewer(db, coll)
+                                | BrowserView::DocumentDetail(db, coll, _) => {
+                                    (db.clone(), Some(coll.clone()))
+                                }
+                                _ => (String::new(), None),
+                            };
+                            let input = input.clone();
+                            match parse_document_handle(
+                                &input,
+                                &database,
+                                collection.as_deref(),
+                                &browser.database_names(),
+                            ) {
+                                Some((db, coll, key)) => {
+                                    browser.input_state = InputState::None;
+                                    if let Err(e) =
+                                        browser.open_document(app_state, &db, &coll, &key).await
+                                    {
+                                        browser.status_message = Some(e.to_string());
+                                    }
+                                }
+                                None => {
+                                    browser.status_message = Some(
+                                        "Expected db:collection/key, collection/key or a _key"
+                                            .to_string(),
+                                    )
+                                }
+                            }
+                        }
+                        _ => {}
+                    }
+                    continue;
+                }
+
+                let is_filter = matches!(browser.input_state, InputState::EditingDocumentFilter(_));
+                let attributes = browser.document_attributes();
+                if let InputState::EditingDocumentFilter(ref mut input)
+                | InputState::EditingDocumentSort(ref mut input) = browser.input_state
+                {
+                    match key.code {
+                        KeyCode::Esc => browser.input_state = InputState::None,
+                        KeyCode::Backspace => {
+                            input.pop();
+                        }
+                        KeyCode::Tab if !is_filter => {
+                            // Cycle through the attributes of the loaded documents
+                            let current = input.split_whitespace().next().unwrap_or_default();
+                            let next = attributes
+                                .iter()
+                                .position(|a| a == current)
+                                .map(|pos| (pos + 1) % attributes.len())
+                                .unwrap_or(0);
+                            if let Some(attribute) = attributes.get(next) {
+                                *input = attribute.clone();
+                            }
+                        }
+                        KeyCode::Char(c) => input.push(c),
+                        KeyCode::Enter => {
+                            let text = input.trim().to_string();
+                            let parsed = if text.is_empty() {
+                                Ok(())
+                            } else if is_filter {
+                                filter::parse_filter(&text, "d").map(|aql| {
+                                    browser.document_paging.filter =
+                                        Some(DocumentFilter { text, aql });
+                                })
+                            } else {
+                                DocumentSort::parse(&text).map(|sort| {
+                                    browser.document_paging.sort = Some(sort);
+                                })
+                            };
+
+                            match parsed {
+                                Ok(()) => {
+                                    if input.trim().is_empty() {
+                                        if is_filter {
+                                            browser.document_paging.filter = None;
+                                        } else {
+                                            browser.document_paging.sort = None;
+                                        }
+                                    }
+                                    browser.input_state = InputState::None;
+                                    if let BrowserView::DocumentViewer(db, coll) =
+                                        browser.view.clone()
+                                        && let Err(e) =
+                                            browser.reload_documents(app_state, &db, &coll).await
+                                    {
+                                        browser.status_message = Some(e.to_string());
+                                    }
+                                }
+                                // Keep the dialog open so the expression can be corrected
+                                Err(e) => browser.status_message = Some(e.to_string()),
+                            }
+                        }
+                        _ => {}
+                    }
+                    continue;
+                }
+
+                if let InputState::SearchingHistory(ref mut input) = browser.input_state {
+                    // The list is narrowed down while typing
+                    match key.code {
+                        KeyCode::Char(c) => input.push(c),
+                        KeyCode::Backspace => {
+                            input.pop();
+                        }
+                        KeyCode::Enter => browser.input_state = InputState::None,
+                        KeyCode::Esc => {
+                            input.clear();
+                            browser.input_state = InputState::None;
+                        }
+                        _ => {}
+                    }
+                    if let InputState::SearchingHistory(input) = &browser.input_state {
+                        browser.history_filter = input.clone();
+                    } else if key.code == KeyCode::Esc {
+                        browser.history_filter.clear();
+                    }
+                    browser.selected_history_index = 0;
+                    continue;
                 }
-                BrowserView::GraphList(db) => render_graph_list(f, f.area(), &browser, db),
-                BrowserView::CollectionProperties(db, coll) => {
-                    render_collection_properties(f, f.area(), &browser, db, coll)
+
+                if let InputState::EnteringExportPath(ref mut format, ref mut input) =
+                    browser.input_state
+                {
+                    match key.code {
+                        KeyCode::Char(c) => input.push(c),
+                        KeyCode::Backspace => {
+                            input.pop();
+                        }
+                        KeyCode::Tab => {
+                            *format = format.next();
+                            *input = export::with_extension(input, *format);
+                        }
+                        KeyCode::Enter if !input.trim().is_empty() => {
+                            let (format, path) = (*format, input.trim().to_string());
+                            if format == export::ExportFormat::Csv {
+                                let columns = browser.export_columns().join(", ");
+                                browser.input_state =
+                                    InputState::EnteringExportColumns(path, columns);
+                            } else {
+                                browser.input_state = InputState::None;
+                                browser.export(app_state, format, &path, Vec::new()).await;
+                            }
+                        }
+                        KeyCode::Esc => browser.input_state = InputState::None,
+                        _ => {}
+                    }
+                    continue;
                 }
-                BrowserView::DocumentViewer(db, coll) => {
-                    render_document_viewer(f, f.area(), &browser, db, coll)
+
+                if let InputState::EnteringExportColumns(ref path, ref mut input) =
+                    browser.input_state
+                {
+                    match key.code {
+                        KeyCode::Char(c) => input.push(c),
+                        KeyCode::Backspace => {
+                            input.pop();
+                        }
+                        KeyCode::Enter => {
+                            let path = path.clone();
+                            let columns = input
+                                .split(',')
+                                .map(|c| c.trim().to_string())
+                                .filter(|c| !c.is_empty())
+                                .collect();
+                            browser.input_state = InputState::None;
+                            browser
+                                .export(app_state, export::ExportFormat::Csv, &path, columns)
+                                .await;
+                        }
+                        KeyCode::Esc => browser.input_state = InputState::None,
+                        _ => {}
+                    }
+                    continue;
                 }
-                BrowserView::GraphProperties(db, graph) => {
-                    render_graph_properties(f, f.area(), &browser, db, graph)
+
+                if let InputState::EnteringCompareAttribute(ref mut input) = browser.input_state {
+                    match key.code {
+                        KeyCode::Char(c) => input.push(c),
+                        KeyCode::Backspace => {
+                            input.pop();
+                        }
+                        KeyCode::Enter if !input.trim().is_empty() => {
+                            let attribute = input.trim().to_string();
+                            browser.input_state = InputState::None;
+                            browser.compare_results(attribute);
+                        }
+                        KeyCode::Esc => browser.input_state = InputState::None,
+                        _ => {}
+                    }
+                    continue;
                 }
-                BrowserView::AqlQueryInput(db) => {
-                    render_aql_query_input(f, f.area(), &mut browser, db)
+
+                if let InputState::RenamingAqlTab(ref mut input) = browser.input_state {
+                    match key.code {
+                        KeyCode::Char(c) => input.push(c),
+                        KeyCode::Backspace => {
+                            input.pop();
+                        }
+                        KeyCode::Enter if !input.trim().is_empty() => {
+                            let name = input.trim().to_string();
+                            browser.input_state = InputState::None;
+                            if let Some(tab) = browser.aql_tabs.get_mut(browser.active_aql_tab) {
+                                tab.name = name;
+                            }
+                            browser.save_aql_tabs();
+                        }
+                        KeyCode::Esc => browser.input_state = InputState::None,
+                        _ => {}
+                    }
+                    continue;
                 }
-                BrowserView::AqlQueryResults(db) => {
-                    render_aql_query_results(f, f.area(), &browser, db)
+
+                if let InputState::NamingSavedQuery(ref mut input) = browser.input_state {
+                    match key.code {
+                        KeyCode::Char(c) => input.push(c),
+                        KeyCode::Backspace => {
+                            input.pop();
+                        }
+                        KeyCode::Enter if !input.trim().is_empty() => {
+                            let name = input.trim().to_string();
+                            browser.input_state = InputState::None;
+                            let selected = browser
+                                .visible_history()
+                                .get(browser.selected_history_index)
+                                .map(|r| (*r).clone());
+                            if let Some(record) = selected {
+                                browser.status_message =
+                                    Some(match browser.query_history.save_query(record, &name) {
+                                        Ok(()) => format!("Query saved as '{}'", name),
+                                        Err(e) => e.to_string(),
+                                    });
+                            }
+                        }
+                        KeyCode::Esc => browser.input_state = InputState::None,
+                        _ => {}
+                    }
+                    continue;
                 }
-            }
 
-            // Render input dialog on top if active
-            if let InputState::EnteringDocumentCount(input) = &browser.input_state {
-                render_input_dialog(f, f.area(), input);
-            }
-        })?;
+                if let InputState::EnteringSchemaSampleSize(ref mut input) = browser.input_state {
+                    match key.code {
+                        KeyCode::Char(c) if c.is_ascii_digit() => input.push(c),
+                        KeyCode::Backspace => {
+                            input.pop();
+                        }
+                        KeyCode::Enter => {
+                            browser.schema_sample_size = input.parse().unwrap_or(100).max(1);
+                            browser.input_state = InputState::None;
+                            if let BrowserView::SchemaEditor(db, coll) = browser.view.clone() {
+                                browser
+                                    .test_schema_against_sample(app_state, &db, &coll)
+                                    .await?;
+                            }
+                        }
+                        KeyCode::Esc => browser.input_state = InputState::None,
+                        _ => {}
+                    }
+                    continue;
+                }
 
-        if let Event::Key(key) = event::read()? {
-            if key.kind == KeyEventKind::Press {
                 // Handle input dialog first if active
                 if let InputState::EnteringDocumentCount(ref mut input) = browser.input_state {
                     match key.code {
@@ -2128,7 +7844,7 @@ async fn run_database_browser(
                             input.pop();
                         }
                         KeyCode::Enter => {
-                            let count: usize = input.parse().unwrap_or(10);
+                            let page_size: usize = input.parse().unwrap_or(100);
                             browser.input_state = InputState::None;
 
                             // Load documents based on current view
@@ -2141,7 +7857,7 @@ async fn run_database_browser(
                                         .clone();
                                     let db_clone = db.clone();
                                     browser
-                                        .load_documents(app_state, &db_clone, &coll_name, count)
+                                        .load_documents(app_state, &db_clone, &coll_name, page_size)
                                         .await?;
                                     browser.view = BrowserView::DocumentViewer(db_clone, coll_name);
                                 }
@@ -2187,6 +7903,7 @@ async fn run_database_browser(
                         _ => {}
                     },
                     BrowserView::CollectionList(db) => match key.code {
+                        KeyCode::Char('m') => browser.open_query_monitor(&db),
                         KeyCode::Char('q') | KeyCode::Esc => {
                             browser.view = BrowserView::DatabaseList;
                             browser.collections.clear();
@@ -2213,15 +7930,69 @@ async fn run_database_browser(
                         }
                         KeyCode::Char('a') | KeyCode::Char('A') => {
                             // Open AQL query view (initialize state only if needed)
-                            if browser.aql_state.is_none() {
-                                browser.init_aql_state();
+                            if browser.aql_tabs.is_empty() {
+                                browser.add_aql_tab();
                             }
+                            // Fetch the completion names now rather than on the first keystroke
+                            browser.completion_data(app_state, &db).await;
                             browser.view = BrowserView::AqlQueryInput(db.clone());
                         }
+                        KeyCode::Char('o') | KeyCode::Char('O') => {
+                            browser.input_state = InputState::EnteringDocumentHandle(String::new());
+                        }
+                        KeyCode::Char('i') | KeyCode::Char('I') => {
+                            if let Some(coll) = browser.selected_collection() {
+                                let coll_name = coll.name.clone();
+                                browser.selected_index_row = 0;
+                                if let Err(e) =
+                                    browser.load_indexes(app_state, &db, &coll_name).await
+                                {
+                                    browser.status_message = Some(e.to_string());
+                                } else {
+                                    browser.view = BrowserView::Indexes(db.clone(), coll_name);
+                                }
+                            }
+                        }
+                        KeyCode::Char('n') | KeyCode::Char('N') => {
+                            browser.form_state = Some(JsonFormState::new(&serde_json::json!({
+                                "name": "",
+                                "type": "document",
+                                "keyOptions": {
+                                    "type": "traditional",
+                                    "allowUserKeys": true
+                                },
+                                "numberOfShards": 1,
+                                "replicationFactor": 1,
+                                "waitForSync": false
+                            })));
+                            browser.view = BrowserView::CreateCollection(db.clone());
+                        }
+                        KeyCode::Char('t') | KeyCode::Char('T') => {
+                            if let Some(coll) = browser.selected_collection() {
+                                browser.input_state =
+                                    InputState::ConfirmTruncateCollection(coll.name.clone());
+                            }
+                        }
+                        KeyCode::Char('d') | KeyCode::Char('D') | KeyCode::Delete => {
+                            if let Some(coll) = browser.selected_collection() {
+                                browser.input_state = InputState::ConfirmDropCollection(
+                                    coll.name.clone(),
+                                    String::new(),
+                                );
+                            }
+                        }
+                        KeyCode::Char('r') | KeyCode::Char('R') => {
+                            if let Some(coll) = browser.selected_collection() {
+                                browser.input_state = InputState::RenamingCollection(
+                                    coll.name.clone(),
+                                    coll.name.clone(),
+                                );
+                            }
+                        }
                         KeyCode::Char(' ') => {
                             // Open input dialog for document count
                             browser.input_state =
-                                InputState::EnteringDocumentCount("10".to_string());
+                                InputState::EnteringDocumentCount("100".to_string());
                         }
                         KeyCode::Down | KeyCode::Char('j') => {
                             if !browser.collections.is_empty() {
@@ -2263,9 +8034,11 @@ async fn run_database_browser(
                         }
                         KeyCode::Char('a') | KeyCode::Char('A') => {
                             // Open AQL query view (initialize state only if needed)
-                            if browser.aql_state.is_none() {
-                                browser.init_aql_state();
+                            if browser.aql_tabs.is_empty() {
+                                browser.add_aql_tab();
                             }
+                            // Fetch the completion names now rather than on the first keystroke
+                            browser.completion_data(app_state, &db).await;
                             browser.view = BrowserView::AqlQueryInput(db.clone());
                         }
                         KeyCode::Enter => {
@@ -2348,59 +8121,427 @@ async fn run_database_browser(
                                 }
                             }
                         }
-                        KeyCode::Up | KeyCode::Char('k') => {
-                            if !browser.graphs.is_empty() {
-                                // Calculate total number of rows
-                                let mut total_rows = 0;
-                                for graph in &browser.graphs {
-                                    total_rows += 1; // graph name row
-                                    total_rows += graph.edge_definitions.len(); // edge definition rows
+                        KeyCode::Up | KeyCode::Char('k') => {
+                            if !browser.graphs.is_empty() {
+                                // Calculate total number of rows
+                                let mut total_rows = 0;
+                                for graph in &browser.graphs {
+                                    total_rows += 1; // graph name row
+                                    total_rows += graph.edge_definitions.len(); // edge definition rows
+                                }
+                                total_rows += browser.graphs.len().saturating_sub(1); // spacing rows between graphs
+
+                                if total_rows > 0 {
+                                    browser.selected_graph_index =
+                                        if browser.selected_graph_index == 0 {
+                                            total_rows - 1
+                                        } else {
+                                            browser.selected_graph_index - 1
+                                        };
+                                }
+                            }
+                        }
+                        _ => {}
+                    },
+                    BrowserView::CollectionProperties(db, coll) => match key.code {
+                        KeyCode::Char('q') | KeyCode::Esc => {
+                            browser.view = BrowserView::CollectionList(db.clone());
+                            browser.collection_details = None;
+                            browser.scroll_offset = 0;
+                        }
+                        KeyCode::Char('s') | KeyCode::Char('S')
+                            if browser.collection_details.is_some() =>
+                        {
+                            browser.open_schema_editor();
+                            browser.view = BrowserView::SchemaEditor(db, coll);
+                        }
+                        KeyCode::Char('e') | KeyCode::Char('E') => {
+                            if let Some(properties) = browser.editable_collection_properties() {
+                                browser.form_state = Some(JsonFormState::new(&properties));
+                                browser.view = BrowserView::EditCollectionProperties(db, coll);
+                            }
+                        }
+                        KeyCode::Down | KeyCode::Char('j') => {
+                            browser.scroll_offset = browser.scroll_offset.saturating_add(1);
+                        }
+                        KeyCode::Up | KeyCode::Char('k') => {
+                            browser.scroll_offset = browser.scroll_offset.saturating_sub(1);
+                        }
+                        KeyCode::PageDown => {
+                            browser.scroll_offset = browser.scroll_offset.saturating_add(10);
+                        }
+                        KeyCode::PageUp => {
+                            browser.scroll_offset = browser.scroll_offset.saturating_sub(10);
+                        }
+                        _ => {}
+                    },
+                    BrowserView::DocumentViewer(db, coll) => match key.code {
+                        KeyCode::Char('w') => browser.start_export(),
+                        KeyCode::Char('q') | KeyCode::Esc => {
+                            browser.view = BrowserView::CollectionList(db.clone());
+                            browser.documents.clear();
+                            browser.scroll_offset = 0;
+                        }
+                        KeyCode::Down | KeyCode::Char('j') => {
+                            if browser.selected_doc_index + 1 < browser.documents.len() {
+                                browser.select_document(browser.selected_doc_index + 1);
+                            } else if browser.document_paging.has_more {
+                                // Reached the end of the page, load the next one lazily
+                                if let Err(e) =
+                                    browser.next_document_page(app_state, &db, &coll).await
+                                {
+                                    browser.status_message = Some(e.to_string());
+                                }
+                            }
+                        }
+                        KeyCode::Up | KeyCode::Char('k') => {
+                            if browser.selected_doc_index > 0 {
+                                browser.select_document(browser.selected_doc_index - 1);
+                            } else if browser.document_paging.offset() > 0 {
+                                match browser.previous_document_page(app_state, &db, &coll).await {
+                                    Ok(()) => browser
+                                        .select_document(browser.documents.len().saturating_sub(1)),
+                                    Err(e) => browser.status_message = Some(e.to_string()),
+                                }
+                            }
+                        }
+                        KeyCode::Right => {
+                            if let Err(e) = browser.next_document_page(app_state, &db, &coll).await
+                            {
+                                browser.status_message = Some(e.to_string());
+                            }
+                        }
+                        KeyCode::Left => {
+                            if let Err(e) =
+                                browser.previous_document_page(app_state, &db, &coll).await
+                            {
+                                browser.status_message = Some(e.to_string());
+                            }
+                        }
+                        KeyCode::Enter | KeyCode::Char('e') | KeyCode::Char('E') => {
+                            if let Some(doc) = browser.documents.get(browser.selected_doc_index) {
+                                browser.document_edit = Some(DocumentEditState::new(Some(doc)));
+                                browser.view =
+                                    BrowserView::DocumentEditor(db.clone(), coll.clone());
+                            }
+                        }
+                        KeyCode::Char('o') | KeyCode::Char('O') => {
+                            browser.input_state = InputState::EnteringDocumentHandle(String::new());
+                        }
+                        KeyCode::Char('v') | KeyCode::Char('V') => {
+                            // Open the selected document in the detail view to follow its edges
+                            let key = browser
+                                .documents
+                                .get(browser.selected_doc_index)
+                                .and_then(|d| d["_key"].as_str())
+                                .map(|k| k.to_string());
+                            if let Some(key) = key
+                                && let Err(e) =
+                                    browser.open_document(app_state, &db, &coll, &key).await
+                            {
+                                browser.status_message = Some(e.to_string());
+                            }
+                        }
+                        KeyCode::Char('/') | KeyCode::Char('f') | KeyCode::Char('F') => {
+                            let current = browser
+                                .document_paging
+                                .filter
+                                .as_ref()
+                                .map(|f| f.text.clone())
+                                .unwrap_or_default();
+                            browser.input_state = InputState::EditingDocumentFilter(current);
+                        }
+                        KeyCode::Char('s') | KeyCode::Char('S') => {
+                            let current = browser
+                                .document_paging
+                                .sort
+                                .as_ref()
+                                .map(|s| s.text.clone())
+                                .unwrap_or_default();
+                            browser.input_state = InputState::EditingDocumentSort(current);
+                        }
+                        KeyCode::Char('x') | KeyCode::Char('X')
+                            if browser.document_paging.filter.is_some()
+                                || browser.document_paging.sort.is_some() =>
+                        {
+                            browser.document_paging.filter = None;
+                            browser.document_paging.sort = None;
+                            if let Err(e) = browser.reload_documents(app_state, &db, &coll).await {
+                                browser.status_message = Some(e.to_string());
+                            }
+                        }
+                        KeyCode::Char('i') | KeyCode::Char('I') => {
+                            browser.document_edit = Some(DocumentEditState::new(None));
+                            browser.view = BrowserView::DocumentEditor(db.clone(), coll.clone());
+                        }
+                        KeyCode::Char('d') | KeyCode::Char('D') | KeyCode::Delete => {
+                            if let Some(key) = browser
+                                .documents
+                                .get(browser.selected_doc_index)
+                                .and_then(|d| d["_key"].as_str())
+                            {
+                                browser.input_state =
+                                    InputState::ConfirmDeleteDocument(key.to_string());
+                            }
+                        }
+                        KeyCode::PageDown => {
+                            browser.scroll_offset = browser.scroll_offset.saturating_add(10);
+                        }
+                        KeyCode::PageUp => {
+                            browser.scroll_offset = browser.scroll_offset.saturating_sub(10);
+                        }
+                        _ => {}
+                    },
+                    BrowserView::DocumentEditor(db, coll) => {
+                        use crossterm::event::KeyModifiers;
+
+                        if let Some(edit) = &mut browser.document_edit {
+                            let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+                            match key.code {
+                                KeyCode::Esc => {
+                                    browser.document_edit = None;
+                                    browser.view = BrowserView::DocumentViewer(db, coll);
+                                }
+                                KeyCode::Char('s') if ctrl => {
+                                    browser.save_document(app_state, &db, &coll, false).await?;
+                                }
+                                KeyCode::Char('o') if ctrl && edit.conflict.is_some() => {
+                                    browser.save_document(app_state, &db, &coll, true).await?;
+                                }
+                                KeyCode::Char('l') if ctrl => {
+                                    // Replace the local edit with the server version
+                                    if let Some(server_doc) = edit.conflict.take() {
+                                        *edit = DocumentEditState::new(Some(&server_doc));
+                                    }
+                                }
+                                KeyCode::Tab => edit.form.toggle_field(),
+                                KeyCode::Enter
+                                    if matches!(edit.form.active_field, JsonFormField::Submit) =>
+                                {
+                                    browser.save_document(app_state, &db, &coll, false).await?;
+                                }
+                                _ => edit.form.input(key),
+                            }
+                        }
+                    }
+                    BrowserView::Indexes(db, coll) => match key.code {
+                        KeyCode::Char('q') | KeyCode::Esc => {
+                            browser.indexes.clear();
+                            browser.view = BrowserView::CollectionList(db);
+                        }
+                        KeyCode::Down | KeyCode::Char('j') if !browser.indexes.is_empty() => {
+                            browser.selected_index_row =
+                                (browser.selected_index_row + 1) % browser.indexes.len();
+                        }
+                        KeyCode::Up | KeyCode::Char('k') if !browser.indexes.is_empty() => {
+                            browser.selected_index_row = if browser.selected_index_row == 0 {
+                                browser.indexes.len() - 1
+                            } else {
+                                browser.selected_index_row - 1
+                            };
+                        }
+                        KeyCode::Char('r') | KeyCode::Char('R') => {
+                            if let Err(e) = browser.load_indexes(app_state, &db, &coll).await {
+                                browser.status_message = Some(e.to_string());
+                            }
+                        }
+                        KeyCode::Char('d') | KeyCode::Char('D') | KeyCode::Delete => {
+                            if let Some(index) = browser.indexes.get(browser.selected_index_row) {
+                                browser.input_state =
+                                    InputState::ConfirmDropIndex(index.id.clone());
+                            }
+                        }
+                        KeyCode::Char(c) => {
+                            let index_type = match c.to_ascii_lowercase() {
+                                'p' => "persistent",
+                                't' => "ttl",
+                                'g' => "geo",
+                                'f' => "fulltext",
+                                'i' => "inverted",
+                                'v' => "vector",
+                                _ => continue,
+                            };
+                            browser.form_state =
+                                Some(JsonFormState::new(&index_template(index_type)));
+                            browser.view = BrowserView::CreateIndex(db, coll);
+                        }
+                        _ => {}
+                    },
+                    BrowserView::CreateIndex(db, coll) => {
+                        if let Some(form) = &mut browser.form_state {
+                            match key.code {
+                                KeyCode::Esc => {
+                                    browser.form_state = None;
+                                    browser.view = BrowserView::Indexes(db, coll);
+                                }
+                                KeyCode::Tab => form.toggle_field(),
+                                KeyCode::Enter
+                                    if matches!(form.active_field, JsonFormField::Submit) =>
+                                {
+                                    if let Err(e) =
+                                        browser.submit_create_index(app_state, &db, &coll).await
+                                    {
+                                        browser.status_message = Some(e.to_string());
+                                    }
+                                }
+                                _ => form.input(key),
+                            }
+                        }
+                    }
+                    BrowserView::EditCollectionProperties(db, coll) => {
+                        if let Some(form) = &mut browser.form_state {
+                            match key.code {
+                                KeyCode::Esc => {
+                                    browser.form_state = None;
+                                    browser.view = BrowserView::CollectionProperties(db, coll);
+                                }
+                                KeyCode::Tab => form.toggle_field(),
+                                KeyCode::Enter
+                                    if matches!(form.active_field, JsonFormField::Submit) =>
+                                {
+                                    if let Err(e) = browser
+                                        .submit_collection_properties(app_state, &db, &coll)
+                                        .await
+                                    {
+                                        browser.status_message = Some(e.to_string());
+                                    }
+                                }
+                                _ => form.input(key),
+                            }
+                        }
+                    }
+                    BrowserView::SchemaEditor(db, coll) => {
+                        use crossterm::event::KeyModifiers;
+
+                        if let Some(form) = &mut browser.form_state {
+                            let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+                            let on_submit = matches!(form.active_field, JsonFormField::Submit);
+                            match key.code {
+                                KeyCode::Esc => {
+                                    browser.form_state = None;
+                                    browser.schema_report = None;
+                                    browser.scroll_offset = 0;
+                                    browser.view = BrowserView::CollectionProperties(db, coll);
+                                }
+                                KeyCode::Char('t') if ctrl => {
+                                    browser
+                                        .test_schema_against_sample(app_state, &db, &coll)
+                                        .await?;
+                                }
+                                KeyCode::Char('n') if ctrl => {
+                                    browser.input_state = InputState::EnteringSchemaSampleSize(
+                                        browser.schema_sample_size.to_string(),
+                                    );
+                                }
+                                KeyCode::Tab => form.toggle_field(),
+                                KeyCode::Enter if on_submit => {
+                                    if let Err(e) =
+                                        browser.submit_schema(app_state, &db, &coll).await
+                                    {
+                                        browser.status_message = Some(e.to_string());
+                                    }
+                                }
+                                // With the focus off the editor, scroll the sample report
+                                KeyCode::Down | KeyCode::Char('j') if on_submit => {
+                                    browser.scroll_offset = browser.scroll_offset.saturating_add(1);
+                                }
+                                KeyCode::Up | KeyCode::Char('k') if on_submit => {
+                                    browser.scroll_offset = browser.scroll_offset.saturating_sub(1);
+                                }
+                                KeyCode::PageDown if on_submit => {
+                                    browser.scroll_offset =
+                                        browser.scroll_offset.saturating_add(10);
+                                }
+                                KeyCode::PageUp if on_submit => {
+                                    browser.scroll_offset =
+                                        browser.scroll_offset.saturating_sub(10);
+                                }
+                                _ => form.input(key),
+                            }
+                        }
+                    }
+                    BrowserView::CreateCollection(db) => {
+                        if let Some(form) = &mut browser.form_state {
+                            match key.code {
+                                KeyCode::Esc => {
+                                    browser.form_state = None;
+                                    browser.view = BrowserView::CollectionList(db);
                                 }
-                                total_rows += browser.graphs.len().saturating_sub(1); // spacing rows between graphs
-
-                                if total_rows > 0 {
-                                    browser.selected_graph_index =
-                                        if browser.selected_graph_index == 0 {
-                                            total_rows - 1
-                                        } else {
-                                            browser.selected_graph_index - 1
-                                        };
+                                KeyCode::Tab => form.toggle_field(),
+                                KeyCode::Enter
+                                    if matches!(form.active_field, JsonFormField::Submit) =>
+                                {
+                                    if let Err(e) =
+                                        browser.submit_create_collection(app_state, &db).await
+                                    {
+                                        browser.status_message = Some(e.to_string());
+                                    }
                                 }
+                                _ => form.input(key),
                             }
                         }
-                        _ => {}
-                    },
-                    BrowserView::CollectionProperties(db, _coll) => match key.code {
-                        KeyCode::Char('q') | KeyCode::Esc => {
-                            browser.view = BrowserView::CollectionList(db.clone());
-                            browser.collection_details = None;
-                            browser.scroll_offset = 0;
-                        }
-                        KeyCode::Down | KeyCode::Char('j') => {
-                            browser.scroll_offset = browser.scroll_offset.saturating_add(1);
-                        }
-                        KeyCode::Up | KeyCode::Char('k') => {
-                            browser.scroll_offset = browser.scroll_offset.saturating_sub(1);
+                    }
+                    BrowserView::DocumentDetail(db, _coll, _key) => match key.code {
+                        KeyCode::Char('q') | KeyCode::Esc | KeyCode::Backspace => {
+                            if let Err(e) = browser.close_document(app_state, &db).await {
+                                browser.status_message = Some(e.to_string());
+                            }
                         }
-                        KeyCode::PageDown => {
-                            browser.scroll_offset = browser.scroll_offset.saturating_add(10);
+                        KeyCode::Char('o') | KeyCode::Char('O') => {
+                            browser.input_state = InputState::EnteringDocumentHandle(String::new());
                         }
-                        KeyCode::PageUp => {
-                            browser.scroll_offset = browser.scroll_offset.saturating_sub(10);
+                        KeyCode::Char(c @ ('f' | 'F' | 't' | 'T')) => {
+                            // Follow _from or _to of an edge to its vertex
+                            let attribute = if c.eq_ignore_ascii_case(&'f') {
+                                "_from"
+                            } else {
+                                "_to"
+                            };
+                            let target = browser
+                                .document_detail
+                                .as_ref()
+                                .filter(|d| is_edge_document(d))
+                                .and_then(|d| d[attribute].as_str())
+                                .and_then(|id| {
+                                    parse_document_handle(id, &db, None, &browser.database_names())
+                                });
+                            if let Some((db, coll, key)) = target
+                                && let Err(e) =
+                                    browser.open_document(app_state, &db, &coll, &key).await
+                            {
+                                browser.status_message = Some(e.to_string());
+                            }
                         }
-                        _ => {}
-                    },
-                    BrowserView::DocumentViewer(db, _coll) => match key.code {
-                        KeyCode::Char('q') | KeyCode::Esc => {
-                            browser.view = BrowserView::CollectionList(db.clone());
-                            browser.documents.clear();
-                            browser.scroll_offset = 0;
+                        KeyCode::Enter => {
+                            let target = browser
+                                .document_edges
+                                .get(browser.selected_edge_index)
+                                .and_then(|e| e.edge["_id"].as_str())
+                                .and_then(|id| {
+                                    parse_document_handle(id, &db, None, &browser.database_names())
+                                });
+                            if let Some((db, coll, key)) = target
+                                && let Err(e) =
+                                    browser.open_document(app_state, &db, &coll, &key).await
+                            {
+                                browser.status_message = Some(e.to_string());
+                            }
                         }
                         KeyCode::Down | KeyCode::Char('j') => {
-                            browser.scroll_offset = browser.scroll_offset.saturating_add(1);
+                            if browser.document_edges.is_empty() {
+                                browser.scroll_offset = browser.scroll_offset.saturating_add(1);
+                            } else if browser.selected_edge_index + 1 < browser.document_edges.len()
+                            {
+                                browser.selected_edge_index += 1;
+                            }
                         }
                         KeyCode::Up | KeyCode::Char('k') => {
-                            browser.scroll_offset = browser.scroll_offset.saturating_sub(1);
+                            if browser.document_edges.is_empty() {
+                                browser.scroll_offset = browser.scroll_offset.saturating_sub(1);
+                            } else {
+                                browser.selected_edge_index =
+                                    browser.selected_edge_index.saturating_sub(1);
+                            }
                         }
                         KeyCode::PageDown => {
                             browser.scroll_offset = browser.scroll_offset.saturating_add(10);
@@ -2434,342 +8575,471 @@ async fn run_database_browser(
                         // Handle AQL input view keys
                         use crossterm::event::KeyModifiers;
 
-                        if let Some(aql_state) = &mut browser.aql_state {
-                            // Check for Ctrl+Enter first (before other key handling)
-                            if key.code == KeyCode::Enter
-                                && key.modifiers.contains(KeyModifiers::CONTROL)
-                            {
-                                // Execute query
-                                if aql_state.parameters_valid && aql_state.options_valid {
-                                    let query_text = aql_state.query_textarea.lines().join("\n");
-                                    let params_text =
-                                        aql_state.parameters_textarea.lines().join("\n");
-                                    let options_text =
-                                        aql_state.options_textarea.lines().join("\n");
-
-                                    // Parse parameters
-                                    let bind_vars = if params_text.trim() == "{}" {
-                                        None
-                                    } else {
-                                        serde_json::from_str(&params_text).ok()
-                                    };
+                        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+                        let alt = key.modifiers.contains(KeyModifiers::ALT);
+                        if key.code == KeyCode::Char(' ') && ctrl {
+                            // Asking explicitly also refreshes the cached names
+                            browser.completion_cache.remove(&db);
+                            browser.update_aql_completion(app_state, &db, true).await;
+                            continue;
+                        }
 
-                                    // Parse options
-                                    let options: Result<serde_json::Value, _> =
-                                        serde_json::from_str(&options_text);
-
-                                    if let Ok(opts) = options {
-                                        let batch_size =
-                                            opts["batchSize"].as_u64().unwrap_or(1000) as usize;
-                                        let stream = opts["stream"].as_bool().unwrap_or(true);
-                                        let max_documents =
-                                            opts["maxDocuments"].as_u64().unwrap_or(100000)
-                                                as usize;
-
-                                        // Execute query
-                                        aql_state.is_fetching = true;
-                                        aql_state.results.clear();
-                                        aql_state.total_fetched = 0;
-                                        aql_state.has_more = false;
-                                        aql_state.cursor_id = None;
-
-                                        browser.view = BrowserView::AqlQueryResults(db.clone());
-
-                                        // Start fetching - first batch
-                                        match execute_aql_query_with_params(
-                                            &app_state.http_client,
-                                            &app_state.arango_endpoint,
-                                            &db,
-                                            &query_text,
-                                            bind_vars.clone(),
-                                            batch_size,
-                                            stream,
-                                            &app_state.username,
-                                            &app_state.password,
-                                        )
-                                        .await
-                                        {
-                                            Ok(response) => {
-                                                // Store initial results
-                                                let mut all_results = response.result;
-                                                let mut has_more = response.has_more;
-                                                let mut cursor_id = response.id;
-
-                                                // Continue fetching if there's more
-                                                while has_more && all_results.len() < max_documents
-                                                {
-                                                    if let Some(ref cursor) = cursor_id {
-                                                        match fetch_cursor_next(
-                                                            &app_state.http_client,
-                                                            &app_state.arango_endpoint,
-                                                            &db,
-                                                            cursor,
-                                                            &app_state.username,
-                                                            &app_state.password,
-                                                        )
-                                                        .await
-                                                        {
-                                                            Ok(cursor_response) => {
-                                                                all_results
-                                                                    .extend(cursor_response.result);
-                                                                has_more = cursor_response.has_more;
-                                                                cursor_id = cursor_response.id;
-                                                            }
-                                                            Err(_) => {
-                                                                break;
-                                                            }
-                                                        }
-                                                    } else {
-                                                        break;
-                                                    }
-                                                }
+                        // Query tabs; results of a running query belong to the active tab
+                        let tab_key = matches!(key.code, KeyCode::Char('t') | KeyCode::PageUp | KeyCode::PageDown if ctrl)
+                            || matches!(key.code, KeyCode::Left | KeyCode::Right if alt)
+                            || matches!(key.code, KeyCode::F(2) | KeyCode::F(8));
+                        if tab_key && browser.query_execution.is_none() {
+                            browser.close_aql_completion();
+                            match key.code {
+                                KeyCode::Char('t') => browser.add_aql_tab(),
+                                KeyCode::Right | KeyCode::PageDown => browser.switch_aql_tab(1),
+                                KeyCode::Left | KeyCode::PageUp => browser.switch_aql_tab(-1),
+                                KeyCode::F(8) => browser.close_aql_tab(app_state),
+                                _ => {
+                                    let name = browser
+                                        .aql_tabs
+                                        .get(browser.active_aql_tab)
+                                        .map(|t| t.name.clone())
+                                        .unwrap_or_default();
+                                    browser.input_state = InputState::RenamingAqlTab(name);
+                                }
+                            }
+                            browser.save_aql_tabs();
+                            continue;
+                        }
+                        // The completion popup takes the navigation and accept keys while open
+                        if let Some(aql_state) = browser.aql_tabs.get_mut(browser.active_aql_tab)
+                            && let Some(popup) = &mut aql_state.completion
+                        {
+                            match key.code {
+                                KeyCode::Down => {
+                                    popup.selected = (popup.selected + 1) % popup.items.len();
+                                    continue;
+                                }
+                                KeyCode::Up => {
+                                    popup.selected = popup
+                                        .selected
+                                        .checked_sub(1)
+                                        .unwrap_or(popup.items.len() - 1);
+                                    continue;
+                                }
+                                KeyCode::Tab | KeyCode::Enter if !ctrl => {
+                                    aql_state.accept_completion();
+                                    continue;
+                                }
+                                KeyCode::Esc => {
+                                    aql_state.completion = None;
+                                    continue;
+                                }
+                                _ => {}
+                            }
+                        }
+
+                        let submit = matches!(key.code, KeyCode::F(5))
+                            || (key.code == KeyCode::Enter
+                                && (ctrl
+                                    || browser.aql_tabs.get(browser.active_aql_tab).is_some_and(
+                                        |s| matches!(s.active_field, AqlInputField::Submit),
+                                    )));
+                        if submit {
+                            browser.execute_aql_input(app_state, &db);
+                            continue;
+                        }
+                        let explain =
+                            matches!(key.code, KeyCode::F(6))
+                                || (key.code == KeyCode::Enter
+                                    && browser.aql_tabs.get(browser.active_aql_tab).is_some_and(
+                                        |s| matches!(s.active_field, AqlInputField::Explain),
+                                    ));
+                        if explain {
+                            browser.explain_aql_input(app_state, &db).await;
+                            continue;
+                        }
+                        let profile =
+                            matches!(key.code, KeyCode::F(7))
+                                || (key.code == KeyCode::Enter
+                                    && browser.aql_tabs.get(browser.active_aql_tab).is_some_and(
+                                        |s| matches!(s.active_field, AqlInputField::Profile),
+                                    ));
+                        if profile {
+                            browser.profile_aql_input(app_state, &db).await;
+                            continue;
+                        }
 
-                                                // Update state once with all results
-                                                if let Some(aql_state) = browser.aql_state.as_mut()
+                        if let Some(aql_state) = browser.aql_tabs.get_mut(browser.active_aql_tab) {
+                            if key.code == KeyCode::F(3) {
+                                aql_state.toggle_param_form();
+                                aql_state.active_field = AqlInputField::Parameters;
+                                continue;
+                            }
+                            // The parameter form takes the editing keys
+                            if matches!(aql_state.active_field, AqlInputField::Parameters)
+                                && let Some(form) = &mut aql_state.param_form
+                            {
+                                let count = form.fields.len();
+                                let handled = match key.code {
+                                    KeyCode::Down if count > 0 => {
+                                        form.selected = (form.selected + 1) % count;
+                                        true
+                                    }
+                                    KeyCode::Up if count > 0 => {
+                                        form.selected =
+                                            form.selected.checked_sub(1).unwrap_or(count - 1);
+                                        true
+                                    }
+                                    KeyCode::F(4) | KeyCode::Char(_) | KeyCode::Backspace
+                                        if !ctrl =>
+                                    {
+                                        if let Some(field) = form.selected_field() {
+                                            match key.code {
+                                                KeyCode::F(4) => field.cycle_type(),
+                                                KeyCode::Char(' ')
+                                                    if field.kind == params::ParamType::Bool =>
                                                 {
-                                                    aql_state.results = all_results;
-                                                    aql_state.total_fetched =
-                                                        aql_state.results.len();
-                                                    aql_state.has_more = has_more;
-                                                    aql_state.cursor_id = cursor_id;
-                                                    aql_state.is_fetching = false;
+                                                    field.input = if field.input == "true" {
+                                                        "false".to_string()
+                                                    } else {
+                                                        "true".to_string()
+                                                    };
                                                 }
-                                            }
-                                            Err(_e) => {
-                                                // Query failed - go back to input
-                                                browser.view =
-                                                    BrowserView::AqlQueryInput(db.clone());
-                                                if let Some(aql_state) = &mut browser.aql_state {
-                                                    aql_state.is_fetching = false;
+                                                KeyCode::Char(c) => field.input.push(c),
+                                                _ => {
+                                                    field.input.pop();
                                                 }
                                             }
                                         }
+                                        aql_state.apply_param_form();
+                                        true
                                     }
+                                    _ => false,
+                                };
+                                if handled {
+                                    continue;
                                 }
-                            } else {
-                                // Handle other keys
-                                match key.code {
-                                    KeyCode::Char('q') | KeyCode::Esc => {
-                                        // Return to collection list but keep AQL state
-                                        browser.view = BrowserView::CollectionList(db.clone());
-                                    }
-                                    KeyCode::Tab => {
-                                        // Switch between fields including Submit button
-                                        aql_state.active_field = match aql_state.active_field {
-                                            AqlInputField::Query => AqlInputField::Parameters,
-                                            AqlInputField::Parameters => AqlInputField::Options,
-                                            AqlInputField::Options => AqlInputField::Submit,
-                                            AqlInputField::Submit => AqlInputField::Query,
-                                        };
+                            }
+                            let on_submit = matches!(
+                                aql_state.active_field,
+                                AqlInputField::Submit
+                                    | AqlInputField::Explain
+                                    | AqlInputField::Profile
+                            );
+                            match key.code {
+                                KeyCode::Esc => {
+                                    // Return to collection list but keep the query tabs
+                                    browser.view = BrowserView::CollectionList(db.clone());
+                                    browser.save_aql_tabs();
+                                }
+                                KeyCode::Char('q') if on_submit => {
+                                    browser.view = BrowserView::CollectionList(db.clone());
+                                    browser.save_aql_tabs();
+                                }
+                                KeyCode::Char('r') if ctrl => {
+                                    browser.open_query_history(&db);
+                                }
+                                KeyCode::Tab => {
+                                    if matches!(aql_state.active_field, AqlInputField::Query) {
+                                        aql_state.sync_bind_parameters();
                                     }
-                                    KeyCode::Enter => {
-                                        // Check if we're on the Submit button
-                                        if matches!(aql_state.active_field, AqlInputField::Submit) {
-                                            // Execute query when Enter is pressed on Submit button
-                                            if aql_state.parameters_valid && aql_state.options_valid
-                                            {
-                                                let query_text =
-                                                    aql_state.query_textarea.lines().join("\n");
-                                                let params_text = aql_state
-                                                    .parameters_textarea
-                                                    .lines()
-                                                    .join("\n");
-                                                let options_text =
-                                                    aql_state.options_textarea.lines().join("\n");
-
-                                                // Parse parameters
-                                                let bind_vars = if params_text.trim() == "{}" {
-                                                    None
-                                                } else {
-                                                    serde_json::from_str(&params_text).ok()
-                                                };
-
-                                                // Parse options
-                                                let options: Result<serde_json::Value, _> =
-                                                    serde_json::from_str(&options_text);
-
-                                                if let Ok(opts) = options {
-                                                    let batch_size =
-                                                        opts["batchSize"].as_u64().unwrap_or(1000)
-                                                            as usize;
-                                                    let stream =
-                                                        opts["stream"].as_bool().unwrap_or(true);
-                                                    let max_documents = opts["maxDocuments"]
-                                                        .as_u64()
-                                                        .unwrap_or(100000)
-                                                        as usize;
-
-                                                    // Execute query (same logic as before)
-                                                    aql_state.is_fetching = true;
-                                                    aql_state.results.clear();
-                                                    aql_state.total_fetched = 0;
-                                                    aql_state.has_more = false;
-                                                    aql_state.cursor_id = None;
-
-                                                    browser.view =
-                                                        BrowserView::AqlQueryResults(db.clone());
-
-                                                    // Start fetching
-                                                    match execute_aql_query_with_params(
-                                                        &app_state.http_client,
-                                                        &app_state.arango_endpoint,
-                                                        &db,
-                                                        &query_text,
-                                                        bind_vars.clone(),
-                                                        batch_size,
-                                                        stream,
-                                                        &app_state.username,
-                                                        &app_state.password,
-                                                    )
-                                                    .await
-                                                    {
-                                                        Ok(response) => {
-                                                            let mut all_results = response.result;
-                                                            let mut has_more = response.has_more;
-                                                            let mut cursor_id = response.id;
-
-                                                            while has_more
-                                                                && all_results.len() < max_documents
-                                                            {
-                                                                if let Some(ref cursor) = cursor_id
-                                                                {
-                                                                    match fetch_cursor_next(
-                                                                        &app_state.http_client,
-                                                                        &app_state.arango_endpoint,
-                                                                        &db,
-                                                                        cursor,
-                                                                        &app_state.username,
-                                                                        &app_state.password,
-                                                                    )
-                                                                    .await
-                                                                    {
-                                                                        Ok(cursor_response) => {
-                                                                            all_results.extend(
-                                                                                cursor_response
-                                                                                    .result,
-                                                                            );
-                                                                            has_more =
-                                                                                cursor_response
-                                                                                    .has_more;
-                                                                            cursor_id =
-                                                                                cursor_response.id;
-                                                                        }
-                                                                        Err(_) => {
-                                                                            break;
-                                                                        }
-                                                                    }
-                                                                } else {
-                                                                    break;
-                                                                }
-                                                            }
-
-                                                            if let Some(aql_state) =
-                                                                browser.aql_state.as_mut()
-                                                            {
-                                                                aql_state.results = all_results;
-                                                                aql_state.total_fetched =
-                                                                    aql_state.results.len();
-                                                                aql_state.has_more = has_more;
-                                                                aql_state.cursor_id = cursor_id;
-                                                                aql_state.is_fetching = false;
-                                                            }
-                                                        }
-                                                        Err(_e) => {
-                                                            browser.view =
-                                                                BrowserView::AqlQueryInput(
-                                                                    db.clone(),
-                                                                );
-                                                            if let Some(aql_state) =
-                                                                &mut browser.aql_state
-                                                            {
-                                                                aql_state.is_fetching = false;
-                                                            }
-                                                        }
-                                                    }
-                                                }
-                                            }
-                                        } else {
-                                            // Pass Enter to the active TextArea for newline
-                                            match aql_state.active_field {
-                                                AqlInputField::Query => {
-                                                    aql_state.query_textarea.input(key);
-                                                }
-                                                AqlInputField::Parameters => {
-                                                    aql_state.parameters_textarea.input(key);
-                                                    let text = aql_state
-                                                        .parameters_textarea
-                                                        .lines()
-                                                        .join("\n");
-                                                    aql_state.parameters_valid =
-                                                        serde_json::from_str::<serde_json::Value>(
-                                                            &text,
-                                                        )
-                                                        .is_ok();
-                                                }
-                                                AqlInputField::Options => {
-                                                    aql_state.options_textarea.input(key);
-                                                    let text = aql_state
-                                                        .options_textarea
-                                                        .lines()
-                                                        .join("\n");
-                                                    aql_state.options_valid =
-                                                        serde_json::from_str::<serde_json::Value>(
-                                                            &text,
-                                                        )
-                                                        .is_ok();
-                                                }
-                                                AqlInputField::Submit => {
-                                                    // No input on submit button
-                                                }
-                                            }
+                                    // Switch between fields including Submit button
+                                    aql_state.active_field = match aql_state.active_field {
+                                        AqlInputField::Query => AqlInputField::Parameters,
+                                        AqlInputField::Parameters => AqlInputField::Options,
+                                        AqlInputField::Options => AqlInputField::Submit,
+                                        AqlInputField::Submit => AqlInputField::Explain,
+                                        AqlInputField::Explain => AqlInputField::Profile,
+                                        AqlInputField::Profile => AqlInputField::Query,
+                                    };
+                                }
+                                _ => {
+                                    // Pass all other keys to the active TextArea
+                                    match aql_state.active_field {
+                                        AqlInputField::Query => {
+                                            aql_state.query_textarea.input(key);
                                         }
-                                    }
-                                    _ => {
-                                        // Pass all other keys to the active TextArea
-                                        match aql_state.active_field {
-                                            AqlInputField::Query => {
-                                                aql_state.query_textarea.input(key);
-                                            }
-                                            AqlInputField::Parameters => {
-                                                aql_state.parameters_textarea.input(key);
-                                                // Validate JSON after input
-                                                let text = aql_state
-                                                    .parameters_textarea
-                                                    .lines()
-                                                    .join("\n");
-                                                aql_state.parameters_valid =
-                                                    serde_json::from_str::<serde_json::Value>(
-                                                        &text,
-                                                    )
+                                        AqlInputField::Parameters => {
+                                            aql_state.parameters_textarea.input(key);
+                                            // Validate JSON after input
+                                            let text =
+                                                aql_state.parameters_textarea.lines().join("\n");
+                                            aql_state.parameters_valid =
+                                                serde_json::from_str::<serde_json::Value>(&text)
                                                     .is_ok();
-                                            }
-                                            AqlInputField::Options => {
-                                                aql_state.options_textarea.input(key);
-                                                // Validate JSON after input
-                                                let text =
-                                                    aql_state.options_textarea.lines().join("\n");
-                                                aql_state.options_valid =
-                                                    serde_json::from_str::<serde_json::Value>(
-                                                        &text,
-                                                    )
-                                                    .is_ok();
-                                            }
-                                            AqlInputField::Submit => {
-                                                // No input on submit button
-                                            }
+                                        }
+                                        AqlInputField::Options => {
+                                            aql_state.options_textarea.input(key);
+                                            aql_state.validate_options();
+                                        }
+                                        AqlInputField::Submit
+                                        | AqlInputField::Explain
+                                        | AqlInputField::Profile => {
+                                            // No input on the buttons
                                         }
                                     }
                                 }
                             }
                         }
+
+                        // Typing in the query refreshes the completion popup, anything else closes it
+                        let typing = matches!(key.code, KeyCode::Char(_) | KeyCode::Backspace)
+                            && !ctrl
+                            && browser
+                                .aql_tabs
+                                .get(browser.active_aql_tab)
+                                .is_some_and(|s| matches!(s.active_field, AqlInputField::Query));
+                        if typing && matches!(browser.view, BrowserView::AqlQueryInput(_)) {
+                            browser.update_aql_completion(app_state, &db, false).await;
+                        } else {
+                            browser.close_aql_completion();
+                        }
+                    }
+                    BrowserView::AqlExplain(db) => {
+                        let count = browser.query_plan.as_ref().map_or(0, |p| p.rows.len());
+                        match key.code {
+                            KeyCode::Char('q') | KeyCode::Esc => {
+                                browser.view = BrowserView::AqlQueryInput(db);
+                            }
+                            KeyCode::Down | KeyCode::Char('j') if count > 0 => {
+                                browser.selected_plan_row =
+                                    (browser.selected_plan_row + 1).min(count - 1);
+                            }
+                            KeyCode::Up | KeyCode::Char('k') => {
+                                browser.selected_plan_row =
+                                    browser.selected_plan_row.saturating_sub(1);
+                            }
+                            KeyCode::PageDown if count > 0 => {
+                                browser.selected_plan_row =
+                                    (browser.selected_plan_row + 10).min(count - 1);
+                            }
+                            KeyCode::PageUp => {
+                                browser.selected_plan_row =
+                                    browser.selected_plan_row.saturating_sub(10);
+                            }
+                            _ => {}
+                        }
+                    }
+                    BrowserView::ResultDiff(db) => {
+                        let Some(comparison) = &mut browser.result_comparison else {
+                            browser.view = BrowserView::AqlQueryResults(db);
+                            continue;
+                        };
+                        let count = comparison.diff.changes.len();
+                        match key.code {
+                            KeyCode::Char('q') | KeyCode::Esc => {
+                                browser.view = BrowserView::AqlQueryResults(db);
+                            }
+                            KeyCode::Down | KeyCode::Char('j') if count > 0 => {
+                                comparison.selected = (comparison.selected + 1) % count;
+                                comparison.scroll = 0;
+                            }
+                            KeyCode::Up | KeyCode::Char('k') if count > 0 => {
+                                comparison.selected =
+                                    comparison.selected.checked_sub(1).unwrap_or(count - 1);
+                                comparison.scroll = 0;
+                            }
+                            KeyCode::PageDown => {
+                                comparison.scroll = comparison.scroll.saturating_add(10);
+                            }
+                            KeyCode::PageUp => {
+                                comparison.scroll = comparison.scroll.saturating_sub(10);
+                            }
+                            KeyCode::Char('a') => {
+                                browser.input_state = InputState::EnteringCompareAttribute(
+                                    comparison.attribute.clone(),
+                                );
+                            }
+                            _ => {}
+                        }
+                    }
+                    BrowserView::QueryMonitor(db) => {
+                        let count = browser.monitor_queries.len();
+                        match key.code {
+                            KeyCode::Char('q') | KeyCode::Esc => {
+                                browser.view = BrowserView::CollectionList(db);
+                            }
+                            KeyCode::Tab => {
+                                browser.monitor_show_slow = !browser.monitor_show_slow;
+                                browser.monitor_queries.clear();
+                                browser.selected_monitor_index = 0;
+                                browser.refresh_query_monitor(app_state, &db).await;
+                            }
+                            KeyCode::Char('r') => {
+                                browser.refresh_query_monitor(app_state, &db).await;
+                            }
+                            KeyCode::Down | KeyCode::Char('j') if count > 0 => {
+                                browser.selected_monitor_index =
+                                    (browser.selected_monitor_index + 1) % count;
+                            }
+                            KeyCode::Up | KeyCode::Char('k') if count > 0 => {
+                                browser.selected_monitor_index = browser
+                                    .selected_monitor_index
+                                    .checked_sub(1)
+                                    .unwrap_or(count - 1);
+                            }
+                            KeyCode::Char('K') | KeyCode::Delete
+                                if !browser.monitor_show_slow && count > 0 =>
+                            {
+                                let id = browser.monitor_queries[browser.selected_monitor_index]
+                                    .id
+                                    .clone();
+                                browser.input_state = InputState::ConfirmKillQuery(id);
+                            }
+                            KeyCode::Enter | KeyCode::Char('e') if count > 0 => {
+                                // Copy into the editor, ready to be explained
+                                let info =
+                                    browser.monitor_queries[browser.selected_monitor_index].clone();
+                                browser.load_query(&info.query, &info.bind_vars, None);
+                                if let Some(aql_state) =
+                                    browser.aql_tabs.get_mut(browser.active_aql_tab)
+                                {
+                                    aql_state.active_field = AqlInputField::Explain;
+                                }
+                                browser.view = BrowserView::AqlQueryInput(db);
+                            }
+                            _ => {}
+                        }
+                    }
+                    BrowserView::AqlHistory(db) => {
+                        let count = browser.visible_history().len();
+                        match key.code {
+                            KeyCode::Char('q') | KeyCode::Esc => {
+                                browser.view = BrowserView::AqlQueryInput(db);
+                            }
+                            KeyCode::Tab => {
+                                browser.history_show_saved = !browser.history_show_saved;
+                                browser.selected_history_index = 0;
+                            }
+                            KeyCode::Char('/') => {
+                                browser.input_state =
+                                    InputState::SearchingHistory(browser.history_filter.clone());
+                            }
+                            KeyCode::Down | KeyCode::Char('j') if count > 0 => {
+                                browser.selected_history_index =
+                                    (browser.selected_history_index + 1) % count;
+                            }
+                            KeyCode::Up | KeyCode::Char('k') if count > 0 => {
+                                browser.selected_history_index = browser
+                                    .selected_history_index
+                                    .checked_sub(1)
+                                    .unwrap_or(count - 1);
+                            }
+                            KeyCode::Enter => {
+                                let selected = browser
+                                    .visible_history()
+                                    .get(browser.selected_history_index)
+                                    .map(|r| (*r).clone());
+                                if let Some(record) = selected {
+                                    browser.load_history_entry(&record);
+                                    browser.view = BrowserView::AqlQueryInput(db);
+                                }
+                            }
+                            KeyCode::Char('s') | KeyCode::Char('S') if count > 0 => {
+                                let name = browser.visible_history()
+                                    [browser.selected_history_index]
+                                    .name
+                                    .clone()
+                                    .unwrap_or_default();
+                                browser.input_state = InputState::NamingSavedQuery(name);
+                            }
+                            KeyCode::Char('d') | KeyCode::Delete
+                                if browser.history_show_saved && count > 0 =>
+                            {
+                                let name = browser.visible_history()
+                                    [browser.selected_history_index]
+                                    .name
+                                    .clone()
+                                    .unwrap_or_default();
+                                if let Err(e) = browser.query_history.delete_saved_query(&name) {
+                                    browser.status_message = Some(e.to_string());
+                                }
+                                browser.selected_history_index =
+                                    browser.selected_history_index.min(count.saturating_sub(2));
+                            }
+                            _ => {}
+                        }
                     }
                     BrowserView::AqlQueryResults(db) => {
-                        if let Some(aql_state) = &mut browser.aql_state {
+                        if browser.query_execution.is_some() {
+                            let ctrl_c = key.code == KeyCode::Char('c')
+                                && key
+                                    .modifiers
+                                    .contains(crossterm::event::KeyModifiers::CONTROL);
+                            if key.code == KeyCode::Esc || ctrl_c {
+                                browser.cancel_query_execution(app_state).await;
+                            }
+                            continue;
+                        }
+                        // Fetch the next batch before paging past the results in memory
+                        if browser
+                            .aql_tabs
+                            .get(browser.active_aql_tab)
+                            .is_some_and(|s| s.needs_more_results(key.code))
+                        {
+                            browser.fetch_more_results(app_state).await;
+                        }
+                        if let Some(aql_state) = browser.aql_tabs.get_mut(browser.active_aql_tab) {
+                            if key.code == KeyCode::Char('t') {
+                                aql_state.table_mode = !aql_state.table_mode;
+                                continue;
+                            }
+                            if key.code == KeyCode::Char('w') && !aql_state.is_fetching {
+                                browser.start_export();
+                                continue;
+                            }
+                            if key.code == KeyCode::Char('p') {
+                                browser.pin_results();
+                                continue;
+                            }
+                            let ctrl = key
+                                .modifiers
+                                .contains(crossterm::event::KeyModifiers::CONTROL);
+                            if matches!(key.code, KeyCode::Char('c') | KeyCode::Char('C')) && !ctrl
+                            {
+                                if browser.pinned_results.is_some() {
+                                    let attribute = browser
+                                        .result_comparison
+                                        .as_ref()
+                                        .map_or("_key".to_string(), |c| c.attribute.clone());
+                                    browser.input_state =
+                                        InputState::EnteringCompareAttribute(attribute);
+                                } else {
+                                    browser.status_message =
+                                        Some("Pin results with P first".to_string());
+                                }
+                                continue;
+                            }
+                            let leaving = matches!(key.code, KeyCode::Char('q') | KeyCode::Esc)
+                                && !aql_state.table.show_detail;
+                            if aql_state.table_mode && !leaving {
+                                let table = &mut aql_state.table;
+                                match key.code {
+                                    KeyCode::Down | KeyCode::Char('j') => table.move_rows(1),
+                                    KeyCode::Up | KeyCode::Char('k') => table.move_rows(-1),
+                                    KeyCode::PageDown => table.move_rows(20),
+                                    KeyCode::PageUp => table.move_rows(-20),
+                                    KeyCode::Home => table.selected_row = 0,
+                                    KeyCode::End => table.move_rows(isize::MAX),
+                                    KeyCode::Right | KeyCode::Char('l') => table.move_columns(1),
+                                    KeyCode::Left | KeyCode::Char('h') => table.move_columns(-1),
+                                    KeyCode::Char('+') | KeyCode::Char('>') => {
+                                        table.resize_column(2)
+                                    }
+                                    KeyCode::Char('-') | KeyCode::Char('<') => {
+                                        table.resize_column(-2)
+                                    }
+                                    KeyCode::Char('x') => table.hide_column(),
+                                    KeyCode::Char('X') => table.show_all_columns(),
+                                    KeyCode::Char('s') => table.toggle_sort(&aql_state.results),
+                                    KeyCode::Enter => table.show_detail = !table.show_detail,
+                                    KeyCode::Char('q') | KeyCode::Esc => table.show_detail = false,
+                                    _ => {}
+                                }
+                                continue;
+                            }
                             match key.code {
                                 KeyCode::Char('q') | KeyCode::Esc => {
                                     browser.view = BrowserView::AqlQueryInput(db.clone());
                                     aql_state.scroll_offset = 0;
-                                    aql_state.current_page = 0;
+                                    aql_state.current_page = aql_state.offset / RESULT_PAGE_SIZE;
                                 }
                                 KeyCode::Down | KeyCode::Char('j') => {
                                     aql_state.scroll_offset =
@@ -2788,15 +9058,16 @@ async fn run_database_browser(
                                         aql_state.scroll_offset.saturating_sub(10);
                                 }
                                 KeyCode::Left => {
-                                    if aql_state.current_page > 0 {
+                                    // Pages dropped from memory cannot be revisited
+                                    if aql_state.current_page > aql_state.offset / RESULT_PAGE_SIZE
+                                    {
                                         aql_state.current_page -= 1;
                                         aql_state.scroll_offset = 0;
                                     }
                                 }
                                 KeyCode::Right => {
-                                    let page_size = 100;
                                     let total_pages =
-                                        (aql_state.results.len() + page_size - 1) / page_size;
+                                        aql_state.total_fetched.div_ceil(RESULT_PAGE_SIZE);
                                     if aql_state.current_page + 1 < total_pages {
                                         aql_state.current_page += 1;
                                         aql_state.scroll_offset = 0;
@@ -2842,98 +9113,63 @@ async fn run_gae_browser(
                     GaeView::LoadGraphInput => {
                         // Handle load graph input view
                         if let Some(load_state) = &mut browser.load_graph_state {
+                            let on_submit =
+                                matches!(load_state.active_field, JsonFormField::Submit);
                             match key.code {
-                                KeyCode::Char('q') | KeyCode::Esc => {
+                                KeyCode::Esc => {
                                     // Go back to graphs view
                                     browser.view = GaeView::Graphs;
                                     browser.load_graph_state = None;
                                 }
-                                KeyCode::Tab => {
-                                    // Switch between fields
-                                    load_state.active_field = match load_state.active_field {
-                                        LoadGraphField::JsonInput => LoadGraphField::Submit,
-                                        LoadGraphField::Submit => LoadGraphField::JsonInput,
-                                    };
+                                KeyCode::Char('q') if on_submit => {
+                                    browser.view = GaeView::Graphs;
+                                    browser.load_graph_state = None;
                                 }
-                                KeyCode::Enter => {
-                                    // Check if we're on the Submit button
-                                    if matches!(load_state.active_field, LoadGraphField::Submit) {
-                                        // Submit the load graph request
-                                        if load_state.json_valid {
-                                            let json_text = load_state.textarea.lines().join("\n");
-
-                                            // Parse the JSON
-                                            if let Ok(config) =
-                                                serde_json::from_str::<serde_json::Value>(
-                                                    &json_text,
-                                                )
-                                            {
-                                                // Call the GAE API to load the graph
-                                                if let Some(ref gae_endpoint) =
-                                                    app_state.gae_endpoint
-                                                {
-                                                    let url = format!(
-                                                        "{}/v1/loaddata",
-                                                        gae_endpoint.trim_end_matches('/')
-                                                    );
-
-                                                    let response = app_state
-                                                        .http_client
-                                                        .post(&url)
-                                                        .json(&config)
-                                                        .send()
-                                                        .await;
-
-                                                    match response {
-                                                        Ok(resp) if resp.status().is_success() => {
-                                                            // Successfully created the job, switch to jobs view
-                                                            browser.view = GaeView::Jobs;
-                                                            let _ =
-                                                                browser.load_jobs(app_state).await;
-                                                            browser.load_graph_state = None;
-                                                        }
-                                                        Ok(resp) => {
-                                                            // Error response - stay in load view
-                                                            eprintln!(
-                                                                "Failed to load graph: {}",
-                                                                resp.status()
-                                                            );
-                                                        }
-                                                        Err(e) => {
-                                                            // Network error - stay in load view
-                                                            eprintln!(
-                                                                "Failed to load graph: {}",
-                                                                e
-                                                            );
-                                                        }
-                                                    }
-                                                }
+                                KeyCode::Tab => load_state.toggle_field(),
+                                KeyCode::Enter if on_submit => {
+                                    let config = match load_state.value() {
+                                        Ok(config) => config,
+                                        Err(e) => {
+                                            load_state.error_message = Some(format!("{:#}", e));
+                                            continue;
+                                        }
+                                    };
+                                    // Call the GAE API to load the graph
+                                    if let Some(ref gae_endpoint) = app_state.gae_endpoint {
+                                        let url = format!(
+                                            "{}/v1/loaddata",
+                                            gae_endpoint.trim_end_matches('/')
+                                        );
+
+                                        let response = app_state
+                                            .http_client
+                                            .post(&url)
+                                            .json(&config)
+                                            .send()
+                                            .await;
+
+                                        match response {
+                                            Ok(resp) if resp.status().is_success() => {
+                                                // Successfully created the job, switch to jobs view
+                                                browser.view = GaeView::Jobs;
+                                                let _ = browser.load_jobs(app_state).await;
+                                                browser.load_graph_state = None;
+                                            }
+                                            // Errors keep the load view open
+                                            Ok(resp) => {
+                                                load_state.error_message = Some(format!(
+                                                    "Failed to load graph: {}",
+                                                    resp.status()
+                                                ));
+                                            }
+                                            Err(e) => {
+                                                load_state.error_message =
+                                                    Some(format!("Failed to load graph: {}", e));
                                             }
                                         }
-                                    } else {
-                                        // Pass Enter to the textarea for newline
-                                        load_state.textarea.input(key);
-
-                                        // Validate JSON after input
-                                        let text = load_state.textarea.lines().join("\n");
-                                        load_state.json_valid =
-                                            serde_json::from_str::<serde_json::Value>(&text)
-                                                .is_ok();
-                                    }
-                                }
-                                _ => {
-                                    // Pass other keys to the textarea only if we're in JsonInput field
-                                    if matches!(load_state.active_field, LoadGraphField::JsonInput)
-                                    {
-                                        load_state.textarea.input(key);
-
-                                        // Validate JSON after input
-                                        let text = load_state.textarea.lines().join("\n");
-                                        load_state.json_valid =
-                                            serde_json::from_str::<serde_json::Value>(&text)
-                                                .is_ok();
                                     }
                                 }
+                                _ => load_state.input(key),
                             }
                         }
                     }
@@ -3207,6 +9443,7 @@ async fn main() -> Result<()> {
         gae_version,
         selected_menu_item: 0,
         http_client: client,
+        restore_tabs: args.restore_tabs,
     };
 
     // Run the TUI
@@ -3214,3 +9451,231 @@ async fn main() -> Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    // Collection name that would break (or inject into) a query built with format!
+    const HOSTILE_COLLECTION: &str = "users` RETURN 1 //";
+
+    fn test_app_state(endpoint: String) -> AppState {
+        AppState {
+            arango_endpoint: endpoint,
+            gae_endpoint: None,
+            username: "root".to_string(),
+            password: String::new(),
+            arango_version: ArangoVersion {
+                server: "arango".to_string(),
+                license: "community".to_string(),
+                version: "3.12.0".to_string(),
+            },
+            gae_version: None,
+            selected_menu_item: 0,
+            http_client: create_http_client().unwrap(),
+            restore_tabs: false,
+        }
+    }
+
+    // Serve a single cursor request and hand back the JSON body the client posted
+    async fn mock_cursor_endpoint(
+        response: serde_json::Value,
+    ) -> (String, tokio::task::JoinHandle<serde_json::Value>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let endpoint = format!("http://{}", listener.local_addr().unwrap());
+
+        let handle = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0u8; 4096];
+            let body_start = loop {
+                let n = socket.read(&mut buf).await.unwrap();
+                request.extend_from_slice(&buf[..n]);
+                if let Some(pos) = request.windows(4).position(|w| w == b"\r\n\r\n") {
+                    break pos + 4;
+                }
+            };
+
+            let headers = String::from_utf8_lossy(&request[..body_start]).to_lowercase();
+            assert!(headers.starts_with("post /_db/_system/_api/cursor "));
+            let content_length: usize = headers
+                .lines()
+                .find_map(|l| l.strip_prefix("content-length:"))
+                .map(|v| v.trim().parse().unwrap())
+                .unwrap();
+            while request.len() < body_start + content_length {
+                let n = socket.read(&mut buf).await.unwrap();
+                request.extend_from_slice(&buf[..n]);
+            }
+
+            let body = response.to_string();
+            let reply = format!(
+                "HTTP/1.1 201 Created\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            socket.write_all(reply.as_bytes()).await.unwrap();
+
+            serde_json::from_slice(&request[body_start..body_start + content_length]).unwrap()
+        });
+
+        (endpoint, handle)
+    }
+
+    #[tokio::test]
+    async fn document_page_query_uses_bind_parameters() {
+        let (endpoint, request) = mock_cursor_endpoint(serde_json::json!({
+            "error": false,
+            "code": 201,
+            "result": [{ "_key": "a" }, { "_key": "b" }, { "_key": "c" }],
+            "hasMore": false,
+            "cached": false,
+        }))
+        .await;
+        let app_state = test_app_state(endpoint);
+
+        let mut browser = DatabaseBrowser::new();
+        browser.document_paging.page_size = 2;
+        browser.document_paging.filter = Some(DocumentFilter {
+            text: "name == \"x\"".to_string(),
+            aql: filter::parse_filter("name == \"x\"", "d").unwrap(),
+        });
+        browser
+            .load_document_page(&app_state, "_system", HOSTILE_COLLECTION)
+            .await
+            .unwrap();
+
+        let body = request.await.unwrap();
+        let query = body["query"].as_str().unwrap();
+        assert!(!query.contains(HOSTILE_COLLECTION));
+        assert!(!query.contains("\"x\""));
+        assert!(query.starts_with("FOR d IN @@collection "));
+        assert!(query.contains("LIMIT @limit"));
+        assert_eq!(body["bindVars"]["@collection"], HOSTILE_COLLECTION);
+        assert_eq!(body["bindVars"]["limit"], 3);
+        assert_eq!(body["bindVars"]["filter1"], "x");
+
+        // The extra document only signals that there is another page
+        assert_eq!(browser.documents.len(), 2);
+        assert!(browser.document_paging.has_more);
+    }
+
+    #[test]
+    fn generated_queries_never_inline_values() {
+        let mut paging = DocumentPaging {
+            page_size: 10,
+            page_after: vec![None, Some("k\" || true".to_string())],
+            ..DocumentPaging::default()
+        };
+
+        let (query, bind_vars) = paging.page_query(HOSTILE_COLLECTION);
+        assert_eq!(
+            query,
+            "FOR d IN @@collection FILTER d._key > @after SORT d._key LIMIT @limit RETURN d"
+        );
+        assert_eq!(bind_vars["after"], "k\" || true");
+
+        paging.sort = Some(DocumentSort::parse("address.city desc").unwrap());
+        paging.filter = Some(DocumentFilter {
+            text: String::new(),
+            aql: filter::parse_filter("age >= 30 || tags IN [\"a\"]", "d").unwrap(),
+        });
+        let (query, bind_vars) = paging.page_query(HOSTILE_COLLECTION);
+        assert_eq!(
+            query,
+            "FOR d IN @@collection FILTER d.@filter0 >= @filter1 || d.@filter2 IN @filter3 \
+             SORT d.@sortAttribute DESC, d._key LIMIT @offset, @limit RETURN d"
+        );
+        assert_eq!(
+            bind_vars["sortAttribute"],
+            serde_json::json!(["address", "city"])
+        );
+        assert_eq!(bind_vars["offset"], 10);
+
+        let (query, bind_vars) = paging.count_query(HOSTILE_COLLECTION).unwrap();
+        assert!(!query.contains(HOSTILE_COLLECTION));
+        assert_eq!(bind_vars["@collection"], HOSTILE_COLLECTION);
+        assert_eq!(bind_vars["filter3"], serde_json::json!(["a"]));
+    }
+
+    #[test]
+    fn api_urls_encode_every_segment() {
+        let url = database_api_url(
+            "http://localhost:8529/",
+            "my db",
+            &["document", "users", "a/b?c#d%e"],
+        )
+        .unwrap();
+        assert_eq!(
+            url.as_str(),
+            "http://localhost:8529/_db/my%20db/_api/document/users/a%2Fb%3Fc%23d%25e"
+        );
+
+        let url = database_api_url("http://host/prefix", "_system", &["index", "c", "1"]).unwrap();
+        assert_eq!(
+            url.as_str(),
+            "http://host/prefix/_db/_system/_api/index/c/1"
+        );
+    }
+
+    #[test]
+    fn document_handles() {
+        let databases = ["_system", "shop"];
+        let parse =
+            |input, collection| parse_document_handle(input, "_system", collection, &databases);
+        let handle = |db: &str, coll: &str, key: &str| {
+            Some((db.to_string(), coll.to_string(), key.to_string()))
+        };
+        assert_eq!(parse("shop:orders/1", None), handle("shop", "orders", "1"));
+        assert_eq!(
+            parse(" orders/a:b ", None),
+            handle("_system", "orders", "a:b")
+        );
+        assert_eq!(
+            parse("a:b", Some("users")),
+            handle("_system", "users", "a:b")
+        );
+        assert_eq!(
+            parse("shop:1", Some("users")),
+            handle("_system", "users", "shop:1")
+        );
+        assert_eq!(
+            parse("other:orders/1", None),
+            handle("_system", "other:orders", "1")
+        );
+        assert_eq!(parse("key", None), None);
+        assert_eq!(parse("orders/", None), None);
+        assert_eq!(parse("a/b/c", None), None);
+    }
+
+    #[test]
+    fn line_diffs() {
+        use DiffLine::*;
+        let line = |s: &str| s.to_string();
+        assert_eq!(
+            diff_lines("a\nb\nc\nd", "a\nx\nc\nd\ne"),
+            [
+                Same(line("a")),
+                Removed(line("b")),
+                Added(line("x")),
+                Same(line("c")),
+                Same(line("d")),
+                Added(line("e")),
+            ]
+        );
+
+        // Too large for the table: the differing middle is replaced as a block
+        let old: Vec<String> = (0..2000).map(|n| n.to_string()).collect();
+        let mut new = old.clone();
+        new[1] = line("x");
+        new[1998] = line("y");
+        let diff = diff_lines(&old.join("\n"), &new.join("\n"));
+        assert_eq!(diff.len(), 1 + 1998 + 1998 + 1);
+        assert_eq!(diff[0], Same(line("0")));
+        assert_eq!(diff[1], Removed(line("1")));
+        assert_eq!(diff[1999], Added(line("x")));
+        assert_eq!(diff[3997], Same(line("1999")));
+    }
+}
@@ -0,0 +1,249 @@
+// Bind parameters of AQL queries and the form used to enter them.
+//
+// A parameter counts as unbound only while it is missing from the bind
+// variables; `null` is a value like any other. In the form an empty input
+// leaves a parameter unbound; to bind an empty string, use the JSON type and
+// enter `""`.
+
+use serde_json::{Map, Value};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ParamType {
+    String,
+    Number,
+    Bool,
+    Json, // Any JSON value, e.g. arrays and objects
+}
+
+impl ParamType {
+    pub fn label(self) -> &'static str {
+        match self {
+            ParamType::String => "string",
+            ParamType::Number => "number",
+            ParamType::Bool => "bool",
+            ParamType::Json => "JSON",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            ParamType::String => ParamType::Number,
+            ParamType::Number => ParamType::Bool,
+            ParamType::Bool => ParamType::Json,
+            ParamType::Json => ParamType::String,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ParamField {
+    pub name: String, // Key in the bind variables, `@coll` for collections
+    pub kind: ParamType,
+    pub input: String,
+}
+
+impl ParamField {
+    fn from_value(name: &str, value: &Value) -> Self {
+        let (kind, input) = match value {
+            Value::String(s) => (ParamType::String, s.clone()),
+            Value::Number(n) => (ParamType::Number, n.to_string()),
+            Value::Bool(b) => (ParamType::Bool, b.to_string()),
+            other => (ParamType::Json, other.to_string()),
+        };
+        Self {
+            name: name.to_string(),
+            kind,
+            input,
+        }
+    }
+
+    // A field without input, for a parameter that is not bound yet
+    fn unbound(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            kind: ParamType::String,
+            input: String::new(),
+        }
+    }
+
+    // The bound value, None for an empty input
+    pub fn value(&self) -> Result<Option<Value>, String> {
+        if self.input.is_empty() {
+            return Ok(None);
+        }
+        let value = match self.kind {
+            ParamType::String => Value::String(self.input.clone()),
+            ParamType::Number => match serde_json::from_str(self.input.trim()) {
+                Ok(Value::Number(n)) => Value::Number(n),
+                _ => return Err("not a number".to_string()),
+            },
+            ParamType::Bool => match self.input.trim() {
+                "true" => Value::Bool(true),
+                "false" => Value::Bool(false),
+                _ => return Err("expected true or false".to_string()),
+            },
+            ParamType::Json => serde_json::from_str(&self.input).map_err(|e| e.to_string())?,
+        };
+        Ok(Some(value))
+    }
+
+    // Switch to the next type, keeping the input where it still makes sense
+    pub fn cycle_type(&mut self) {
+        self.kind = self.kind.next();
+        if self.kind == ParamType::Bool && !matches!(self.input.as_str(), "true" | "false") {
+            self.input = "true".to_string();
+        }
+    }
+}
+
+pub struct ParamForm {
+    pub fields: Vec<ParamField>,
+    pub selected: usize,
+}
+
+impl ParamForm {
+    // One field per referenced parameter, followed by the unused bound ones
+    pub fn new(bind_vars: &Map<String, Value>, names: &[String]) -> Self {
+        let mut fields: Vec<ParamField> = names
+            .iter()
+            .map(|name| match bind_vars.get(name) {
+                Some(value) => ParamField::from_value(name, value),
+                None => ParamField::unbound(name),
+            })
+            .collect();
+        for (name, value) in bind_vars {
+            if !names.contains(name) {
+                fields.push(ParamField::from_value(name, value));
+            }
+        }
+        Self {
+            fields,
+            selected: 0,
+        }
+    }
+
+    pub fn add_missing(&mut self, names: &[String]) {
+        for name in names {
+            if !self.fields.iter().any(|f| &f.name == name) {
+                self.fields.push(ParamField::unbound(name));
+            }
+        }
+    }
+
+    pub fn selected_field(&mut self) -> Option<&mut ParamField> {
+        self.fields.get_mut(self.selected)
+    }
+
+    // The bind variables without the empty fields, or the first field with an
+    // invalid input
+    pub fn bind_vars(&self) -> Result<Map<String, Value>, String> {
+        let mut bind_vars = Map::new();
+        for field in &self.fields {
+            let value = field
+                .value()
+                .map_err(|e| format!("@{}: {}", field.name, e))?;
+            if let Some(value) = value {
+                bind_vars.insert(field.name.clone(), value);
+            }
+        }
+        Ok(bind_vars)
+    }
+}
+
+// Referenced parameters missing from the bind variables
+pub fn unbound(bind_vars: &Map<String, Value>, names: &[String]) -> Vec<String> {
+    names
+        .iter()
+        .filter(|name| !bind_vars.contains_key(*name))
+        .cloned()
+        .collect()
+}
+
+// Bound parameters the query does not reference
+pub fn unused(bind_vars: &Map<String, Value>, names: &[String]) -> Vec<String> {
+    bind_vars
+        .keys()
+        .filter(|key| !names.contains(key))
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn names(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    fn object(value: Value) -> Map<String, Value> {
+        value.as_object().unwrap().clone()
+    }
+
+    #[test]
+    fn form_fields() {
+        let bound =
+            object(json!({ "a": "x", "n": 1.5, "@coll": "users", "old": [1], "none": null }));
+        let form = ParamForm::new(&bound, &names(&["a", "@coll", "missing", "n", "none"]));
+        let fields: Vec<(&str, ParamType, &str)> = form
+            .fields
+            .iter()
+            .map(|f| (f.name.as_str(), f.kind, f.input.as_str()))
+            .collect();
+        assert_eq!(
+            fields,
+            [
+                ("a", ParamType::String, "x"),
+                ("@coll", ParamType::String, "users"),
+                ("missing", ParamType::String, ""),
+                ("n", ParamType::Number, "1.5"),
+                ("none", ParamType::Json, "null"),
+                ("old", ParamType::Json, "[1]"),
+            ]
+        );
+    }
+
+    #[test]
+    fn bind_vars_from_the_form() {
+        let mut form = ParamForm::new(&Map::new(), &names(&["s", "n", "b", "j", "empty"]));
+        form.fields[0].input = "text".to_string();
+        form.fields[1].kind = ParamType::Number;
+        form.fields[1].input = " 42 ".to_string();
+        form.fields[2].cycle_type();
+        form.fields[2].cycle_type();
+        assert_eq!(form.fields[2].kind, ParamType::Bool);
+        assert_eq!(form.fields[2].input, "true");
+        form.fields[3].kind = ParamType::Json;
+        form.fields[3].input = "null".to_string();
+
+        // Empty inputs stay unbound, an explicit null is bound
+        let bind_vars = form.bind_vars().unwrap();
+        assert_eq!(
+            Value::Object(bind_vars.clone()),
+            json!({ "s": "text", "n": 42, "b": true, "j": null })
+        );
+        let referenced = names(&["s", "n", "b", "j", "empty"]);
+        assert_eq!(unbound(&bind_vars, &referenced), ["empty"]);
+
+        form.add_missing(&names(&["s", "new"]));
+        assert_eq!(form.fields.len(), 6);
+
+        form.fields[1].input = "4x".to_string();
+        assert_eq!(form.bind_vars(), Err("@n: not a number".to_string()));
+        form.fields[1].input = "4".to_string();
+        form.fields[2].input = "yes".to_string();
+        assert_eq!(
+            form.bind_vars(),
+            Err("@b: expected true or false".to_string())
+        );
+    }
+
+    #[test]
+    fn unbound_and_unused() {
+        let bind_vars = object(json!({ "a": null, "b": "", "c": 1 }));
+        let referenced = names(&["a", "b", "d"]);
+        assert_eq!(unbound(&bind_vars, &referenced), ["d"]);
+        assert_eq!(unused(&bind_vars, &referenced), ["c"]);
+    }
+}
@@ -0,0 +1,445 @@
+// Flattening of AQL execution plans (from the explain and profile APIs) into
+// an indented node tree.
+//
+// Plan nodes point to the nodes they depend on, so the tree is rendered from
+// the final node (usually a ReturnNode) down to the SingletonNode. Subquery
+// plans of SubqueryNodes are nested below their node.
+
+use serde_json::Value;
+
+#[derive(Debug, Clone)]
+pub struct PlanRow {
+    pub id: u64,
+    pub depth: usize,
+    pub node_type: String,
+    pub details: String,
+    pub estimated_cost: Option<f64>,
+    pub estimated_items: Option<u64>,
+}
+
+// Indexes used by the plan, as `collection: type [fields] (name)`
+pub fn used_indexes(plan: &Value) -> Vec<String> {
+    let mut indexes = Vec::new();
+    collect_indexes(plan, &mut indexes);
+    indexes
+}
+
+fn collect_indexes(plan: &Value, indexes: &mut Vec<String>) {
+    for node in plan["nodes"].as_array().into_iter().flatten() {
+        let collection = node["collection"].as_str().unwrap_or("");
+        for index in node["indexes"].as_array().into_iter().flatten() {
+            let description = format!(
+                "{}: {} [{}] ({})",
+                collection,
+                index["type"].as_str().unwrap_or("?"),
+                index_fields(index),
+                index["name"].as_str().unwrap_or("?")
+            );
+            if !indexes.contains(&description) {
+                indexes.push(description);
+            }
+        }
+        if node["subquery"].is_object() {
+            collect_indexes(&node["subquery"], indexes);
+        }
+    }
+}
+
+fn index_fields(index: &Value) -> String {
+    index["fields"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|f| match f {
+            Value::String(s) => s.clone(),
+            // Inverted indexes describe fields as objects
+            other => other["name"].as_str().unwrap_or("?").to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+// Flatten a plan into rows, root first, each dependency one level deeper
+pub fn plan_rows(plan: &Value) -> Vec<PlanRow> {
+    let mut rows = Vec::new();
+    add_plan(plan, 0, &mut rows);
+    rows
+}
+
+fn add_plan(plan: &Value, depth: usize, rows: &mut Vec<PlanRow>) {
+    let nodes: Vec<&Value> = plan["nodes"].as_array().into_iter().flatten().collect();
+    let node_id = |node: &Value| node["id"].as_u64().unwrap_or(0);
+
+    // The root is the node no other node depends on
+    let is_dependency = |id: u64| {
+        nodes.iter().any(|n| {
+            n["dependencies"]
+                .as_array()
+                .is_some_and(|deps| deps.iter().any(|d| d.as_u64() == Some(id)))
+        })
+    };
+    let Some(root) = nodes.iter().rev().find(|n| !is_dependency(node_id(n))) else {
+        return;
+    };
+
+    let mut stack = vec![(*root, depth)];
+    let mut visited = Vec::new();
+    while let Some((node, depth)) = stack.pop() {
+        let id = node_id(node);
+        if visited.contains(&id) {
+            continue;
+        }
+        visited.push(id);
+
+        rows.push(PlanRow {
+            id,
+            depth,
+            node_type: node["type"].as_str().unwrap_or("?").to_string(),
+            details: node_details(node),
+            estimated_cost: node["estimatedCost"].as_f64(),
+            estimated_items: node["estimatedNrItems"].as_u64(),
+        });
+        if node["subquery"].is_object() {
+            add_plan(&node["subquery"], depth + 1, rows);
+        }
+
+        let dependencies: Vec<&Value> = node["dependencies"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|d| {
+                nodes
+                    .iter()
+                    .find(|n| Some(node_id(n)) == d.as_u64())
+                    .copied()
+            })
+            .collect();
+        for dependency in dependencies.into_iter().rev() {
+            stack.push((dependency, depth + 1));
+        }
+    }
+}
+
+fn variable(node: &Value, attribute: &str) -> String {
+    node[attribute]["name"].as_str().unwrap_or("?").to_string()
+}
+
+// Short, node type specific description of what a node does
+fn node_details(node: &Value) -> String {
+    let collection = node["collection"].as_str().unwrap_or("?");
+    match node["type"].as_str().unwrap_or("") {
+        "EnumerateCollectionNode" => format!(
+            "FOR {} IN {}{}",
+            variable(node, "outVariable"),
+            collection,
+            if node["random"].as_bool() == Some(true) {
+                " (random order)"
+            } else {
+                ""
+            }
+        ),
+        "IndexNode" => {
+            let indexes: Vec<String> = node["indexes"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .map(|i| {
+                    format!(
+                        "{} [{}]",
+                        i["type"].as_str().unwrap_or("?"),
+                        index_fields(i)
+                    )
+                })
+                .collect();
+            format!(
+                "FOR {} IN {} using {}",
+                variable(node, "outVariable"),
+                collection,
+                indexes.join(", ")
+            )
+        }
+        "EnumerateListNode" => format!(
+            "FOR {} IN {}",
+            variable(node, "outVariable"),
+            variable(node, "inVariable")
+        ),
+        "EnumerateViewNode" => format!(
+            "FOR {} IN {}",
+            variable(node, "outVariable"),
+            node["view"].as_str().unwrap_or("?")
+        ),
+        "CalculationNode" => format!(
+            "LET {} = {}",
+            variable(node, "outVariable"),
+            expression_text(&node["expression"])
+        ),
+        "FilterNode" => format!("FILTER {}", variable(node, "inVariable")),
+        "ReturnNode" => format!("RETURN {}", variable(node, "inVariable")),
+        "LimitNode" => format!(
+            "LIMIT {}, {}",
+            node["offset"].as_u64().unwrap_or(0),
+            node["limit"].as_u64().unwrap_or(0)
+        ),
+        "SortNode" => {
+            let elements: Vec<String> = node["elements"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .map(|e| {
+                    format!(
+                        "{} {}",
+                        variable(e, "inVariable"),
+                        if e["ascending"].as_bool() == Some(false) {
+                            "DESC"
+                        } else {
+                            "ASC"
+                        }
+                    )
+                })
+                .collect();
+            format!("SORT {}", elements.join(", "))
+        }
+        "CollectNode" => {
+            let groups: Vec<String> = node["groups"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .map(|g| {
+                    format!(
+                        "{} = {}",
+                        variable(g, "outVariable"),
+                        variable(g, "inVariable")
+                    )
+                })
+                .collect();
+            format!(
+                "COLLECT {} ({})",
+                groups.join(", "),
+                node["collectOptions"]["method"].as_str().unwrap_or("?")
+            )
+        }
+        "InsertNode" => format!("INSERT INTO {}", collection),
+        "UpdateNode" => format!("UPDATE IN {}", collection),
+        "ReplaceNode" => format!("REPLACE IN {}", collection),
+        "RemoveNode" => format!("REMOVE IN {}", collection),
+        "UpsertNode" => format!("UPSERT IN {}", collection),
+        "TraversalNode" | "ShortestPathNode" | "EnumeratePathsNode" => {
+            let graph = match &node["graph"] {
+                Value::String(name) => name.clone(),
+                Value::Array(edges) => edges
+                    .iter()
+                    .filter_map(|e| e.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                _ => "?".to_string(),
+            };
+            format!(
+                "{}..{} {} {}",
+                node["minDepth"].as_u64().unwrap_or(1),
+                node["maxDepth"].as_u64().unwrap_or(1),
+                node["defaultDirection"]
+                    .as_u64()
+                    .map_or("ANY", |d| match d {
+                        1 => "INBOUND",
+                        2 => "OUTBOUND",
+                        _ => "ANY",
+                    }),
+                graph
+            )
+        }
+        "SubqueryNode" | "SubqueryStartNode" | "SubqueryEndNode" => node["outVariable"]["name"]
+            .as_str()
+            .map(|v| format!("LET {} = (subquery)", v))
+            .unwrap_or_default(),
+        "ScatterNode" | "GatherNode" | "DistributeNode" | "RemoteNode" => {
+            node["server"].as_str().unwrap_or("").to_string()
+        }
+        _ => String::new(),
+    }
+}
+
+// Reconstruct readable AQL from an expression AST of a CalculationNode
+fn expression_text(node: &Value) -> String {
+    let sub = |i: usize| expression_text(&node["subNodes"][i]);
+    let subs = |separator: &str| {
+        node["subNodes"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .map(expression_text)
+            .collect::<Vec<_>>()
+            .join(separator)
+    };
+    let name = node["name"].as_str().unwrap_or("?");
+
+    let node_type = node["type"].as_str().unwrap_or("");
+    if let Some(op) = node_type.strip_prefix("compare ") {
+        let op = match op {
+            "in" => "IN",
+            "not in" => "NOT IN",
+            other => other,
+        };
+        return format!("{} {} {}", sub(0), op, sub(1));
+    }
+
+    match node_type {
+        "reference" | "variable" | "collection" | "view" => name.to_string(),
+        "parameter" => format!("@{}", name),
+        "value" => node["value"].to_string(),
+        "attribute access" => format!("{}.{}", sub(0), name),
+        "indexed access" => format!("{}[{}]", sub(0), sub(1)),
+        "expansion" => format!("{}[*]", sub(0)),
+        "iterator" => format!("{} IN {}", name, sub(0)),
+        "array" => format!("[{}]", subs(", ")),
+        "object" => format!("{{{}}}", subs(", ")),
+        "object element" => format!("{}: {}", name, sub(0)),
+        "function call" => format!("{}({})", name, {
+            // Arguments are wrapped in an array node
+            let args = &node["subNodes"][0];
+            args["subNodes"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .map(expression_text)
+                .collect::<Vec<_>>()
+                .join(", ")
+        }),
+        "logical and" => format!("({} && {})", sub(0), sub(1)),
+        "logical or" => format!("({} || {})", sub(0), sub(1)),
+        "unary not" => format!("!{}", sub(0)),
+        "unary minus" => format!("-{}", sub(0)),
+        "plus" => format!("({} + {})", sub(0), sub(1)),
+        "minus" => format!("({} - {})", sub(0), sub(1)),
+        "times" => format!("({} * {})", sub(0), sub(1)),
+        "division" => format!("({} / {})", sub(0), sub(1)),
+        "modulus" => format!("({} % {})", sub(0), sub(1)),
+        "range" => format!("{}..{}", sub(0), sub(1)),
+        "ternary" => format!("({} ? {} : {})", sub(0), sub(1), sub(2)),
+        "" => String::new(),
+        other => format!("<{}>", other),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn var(name: &str) -> Value {
+        json!({ "name": name })
+    }
+
+    fn summary(rows: &[PlanRow]) -> Vec<(u64, usize, &str, &str)> {
+        rows.iter()
+            .map(|r| (r.id, r.depth, r.node_type.as_str(), r.details.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn linear_plan() {
+        let plan = json!({ "nodes": [
+            { "type": "SingletonNode", "id": 1, "dependencies": [] },
+            {
+                "type": "IndexNode", "id": 6, "dependencies": [1],
+                "collection": "users", "outVariable": var("u"),
+                "indexes": [{ "type": "persistent", "fields": ["age"], "name": "idx_age" }],
+                "estimatedCost": 12.5, "estimatedNrItems": 10
+            },
+            { "type": "LimitNode", "id": 4, "dependencies": [6], "offset": 0, "limit": 5 },
+            { "type": "ReturnNode", "id": 5, "dependencies": [4], "inVariable": var("u") }
+        ] });
+        let rows = plan_rows(&plan);
+        assert_eq!(
+            summary(&rows),
+            [
+                (5, 0, "ReturnNode", "RETURN u"),
+                (4, 1, "LimitNode", "LIMIT 0, 5"),
+                (6, 2, "IndexNode", "FOR u IN users using persistent [age]"),
+                (1, 3, "SingletonNode", ""),
+            ]
+        );
+        assert_eq!(rows[2].estimated_cost, Some(12.5));
+        assert_eq!(rows[2].estimated_items, Some(10));
+        assert_eq!(used_indexes(&plan), ["users: persistent [age] (idx_age)"]);
+    }
+
+    #[test]
+    fn subquery() {
+        let subquery = json!({ "nodes": [
+            { "type": "SingletonNode", "id": 3, "dependencies": [] },
+            {
+                "type": "EnumerateCollectionNode", "id": 4, "dependencies": [3],
+                "collection": "orders", "outVariable": var("o")
+            },
+            { "type": "ReturnNode", "id": 5, "dependencies": [4], "inVariable": var("o") }
+        ] });
+        let plan = json!({ "nodes": [
+            { "type": "SingletonNode", "id": 1, "dependencies": [] },
+            {
+                "type": "SubqueryNode", "id": 2, "dependencies": [1],
+                "subquery": subquery, "outVariable": var("all")
+            },
+            { "type": "ReturnNode", "id": 6, "dependencies": [2], "inVariable": var("all") }
+        ] });
+        assert_eq!(
+            summary(&plan_rows(&plan)),
+            [
+                (6, 0, "ReturnNode", "RETURN all"),
+                (2, 1, "SubqueryNode", "LET all = (subquery)"),
+                (5, 2, "ReturnNode", "RETURN o"),
+                (4, 3, "EnumerateCollectionNode", "FOR o IN orders"),
+                (3, 4, "SingletonNode", ""),
+                (1, 2, "SingletonNode", ""),
+            ]
+        );
+        assert!(plan_rows(&json!({})).is_empty());
+    }
+
+    #[test]
+    fn expressions() {
+        let reference = |name: &str| json!({ "type": "reference", "name": name });
+        let value = |v: Value| json!({ "type": "value", "value": v });
+        let age = json!({
+            "type": "attribute access", "name": "age", "subNodes": [reference("u")]
+        });
+        let expression = json!({
+            "type": "logical and",
+            "subNodes": [
+                { "type": "compare >", "subNodes": [age, value(json!(30))] },
+                {
+                    "type": "compare not in",
+                    "subNodes": [
+                        {
+                            "type": "function call", "name": "LOWER",
+                            "subNodes": [{ "type": "array", "subNodes": [reference("name")] }]
+                        },
+                        { "type": "parameter", "name": "blocked" }
+                    ]
+                }
+            ]
+        });
+        assert_eq!(
+            expression_text(&expression),
+            "(u.age > 30 && LOWER(name) NOT IN @blocked)"
+        );
+
+        let calculation = json!({
+            "type": "CalculationNode",
+            "outVariable": var("x"),
+            "expression": {
+                "type": "object",
+                "subNodes": [
+                    { "type": "object element", "name": "a", "subNodes": [value(json!("s"))] },
+                    { "type": "object element", "name": "b", "subNodes": [{
+                        "type": "ternary",
+                        "subNodes": [reference("c"), value(json!(1)), { "type": "unknown thing" }]
+                    }] }
+                ]
+            }
+        });
+        assert_eq!(
+            node_details(&calculation),
+            "LET x = {a: \"s\", b: (c ? 1 : <unknown thing>)}"
+        );
+    }
+}
@@ -0,0 +1,370 @@
+// Tabular view of AQL query results.
+//
+// Columns are the top-level attributes of the result documents, in order of
+// first appearance. Results that are not objects (e.g. `RETURN 42`) are shown
+// in a `(value)` column of their own. Sorting only reorders row indices, the
+// results themselves stay in server order. Only the results in memory are
+// sorted.
+
+use serde_json::Value;
+use std::cmp::Ordering;
+
+// Column for results that are not objects
+pub const VALUE_COLUMN: &str = "(value)";
+
+const MIN_COLUMN_WIDTH: u16 = 4;
+const MAX_COLUMN_WIDTH: u16 = 60;
+const DEFAULT_MAX_WIDTH: u16 = 30;
+
+// Rows looked at to pick initial column widths
+const WIDTH_SAMPLE_SIZE: usize = 200;
+
+#[derive(Debug, Clone)]
+pub struct Column {
+    pub name: String,
+    pub width: u16,
+    pub hidden: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SortDirection {
+    Ascending,
+    Descending,
+}
+
+pub struct ResultTable {
+    pub columns: Vec<Column>,
+    pub order: Vec<usize>, // Result indices in display order
+    pub sort: Option<(usize, SortDirection)>,
+    pub selected_row: usize,    // Position in `order`
+    pub selected_column: usize, // Index into `columns`, always a visible column
+    pub show_detail: bool,
+}
+
+// Text of a cell: strings without quotes, nested values as compact JSON,
+// missing attributes empty
+pub fn cell_text(result: &Value, column: &str) -> String {
+    match cell_value(result, column) {
+        None => String::new(),
+        Some(Value::String(s)) => s.replace('\n', " "),
+        Some(other) => other.to_string(),
+    }
+}
+
+// The value shown in a cell, None if the attribute is missing
+pub fn cell_value<'a>(result: &'a Value, column: &str) -> Option<&'a Value> {
+    match result {
+        Value::Object(object) => object.get(column),
+        other if column == VALUE_COLUMN => Some(other),
+        _ => None,
+    }
+}
+
+// Rank of a type in AQL's sort order: null < bool < number < string < array < object
+fn type_rank(value: &Value) -> u8 {
+    match value {
+        Value::Null => 0,
+        Value::Bool(_) => 1,
+        Value::Number(_) => 2,
+        Value::String(_) => 3,
+        Value::Array(_) => 4,
+        Value::Object(_) => 5,
+    }
+}
+
+// Compare values like AQL's SORT does; missing attributes sort like null
+pub fn compare_values(a: Option<&Value>, b: Option<&Value>) -> Ordering {
+    let (a, b) = (a.unwrap_or(&Value::Null), b.unwrap_or(&Value::Null));
+    match (a, b) {
+        (Value::Bool(x), Value::Bool(y)) => x.cmp(y),
+        (Value::Number(x), Value::Number(y)) => {
+            let (x, y) = (x.as_f64().unwrap_or(0.0), y.as_f64().unwrap_or(0.0));
+            x.partial_cmp(&y).unwrap_or(Ordering::Equal)
+        }
+        (Value::String(x), Value::String(y)) => x.cmp(y),
+        (Value::Array(x), Value::Array(y)) => {
+            for (x, y) in x.iter().zip(y) {
+                let ordering = compare_values(Some(x), Some(y));
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+            }
+            x.len().cmp(&y.len())
+        }
+        (Value::Object(_), Value::Object(_)) => a.to_string().cmp(&b.to_string()),
+        _ => type_rank(a).cmp(&type_rank(b)),
+    }
+}
+
+impl ResultTable {
+    pub fn new(results: &[Value]) -> Self {
+        let mut names: Vec<String> = Vec::new();
+        if results.iter().any(|r| !r.is_object()) {
+            names.push(VALUE_COLUMN.to_string());
+        }
+        for result in results {
+            if let Value::Object(object) = result {
+                for name in object.keys() {
+                    if !names.contains(name) {
+                        names.push(name.clone());
+                    }
+                }
+            }
+        }
+
+        let columns = names
+            .into_iter()
+            .map(|name| {
+                let widest = results
+                    .iter()
+                    .take(WIDTH_SAMPLE_SIZE)
+                    .map(|r| cell_text(r, &name).chars().count())
+                    .max()
+                    .unwrap_or(0)
+                    .max(name.chars().count());
+                Column {
+                    width: (widest as u16).clamp(MIN_COLUMN_WIDTH, DEFAULT_MAX_WIDTH),
+                    name,
+                    hidden: false,
+                }
+            })
+            .collect();
+
+        Self {
+            columns,
+            order: (0..results.len()).collect(),
+            sort: None,
+            selected_row: 0,
+            selected_column: 0,
+            show_detail: false,
+        }
+    }
+
+    pub fn visible_columns(&self) -> Vec<usize> {
+        (0..self.columns.len())
+            .filter(|&i| !self.columns[i].hidden)
+            .collect()
+    }
+
+    // Result index of the selected row
+    pub fn selected_result(&self) -> Option<usize> {
+        self.order.get(self.selected_row).copied()
+    }
+
+    pub fn move_rows(&mut self, delta: isize) {
+        let last = self.order.len().saturating_sub(1);
+        self.selected_row = self.selected_row.saturating_add_signed(delta).min(last);
+    }
+
+    // Move the column selection by `delta` visible columns
+    pub fn move_columns(&mut self, delta: isize) {
+        let visible = self.visible_columns();
+        let Some(current) = visible.iter().position(|&i| i == self.selected_column) else {
+            return;
+        };
+        let target = current
+            .saturating_add_signed(delta)
+            .min(visible.len().saturating_sub(1));
+        self.selected_column = visible[target];
+    }
+
+    pub fn resize_column(&mut self, delta: i16) {
+        if let Some(column) = self.columns.get_mut(self.selected_column) {
+            column.width = column
+                .width
+                .saturating_add_signed(delta)
+                .clamp(MIN_COLUMN_WIDTH, MAX_COLUMN_WIDTH);
+        }
+    }
+
+    // Hide the selected column; the last visible column stays
+    pub fn hide_column(&mut self) {
+        let visible = self.visible_columns();
+        if visible.len() <= 1 {
+            return;
+        }
+        let Some(position) = visible.iter().position(|&i| i == self.selected_column) else {
+            return;
+        };
+        self.columns[self.selected_column].hidden = true;
+        // Select the neighbour, preferring the one to the right
+        self.selected_column = visible
+            .get(position + 1)
+            .copied()
+            .unwrap_or_else(|| visible[position - 1]);
+    }
+
+    pub fn show_all_columns(&mut self) {
+        for column in &mut self.columns {
+            column.hidden = false;
+        }
+    }
+
+    // Cycle the sort of the selected column: ascending, descending, unsorted
+    pub fn toggle_sort(&mut self, results: &[Value]) {
+        let column = self.selected_column;
+        self.sort = match self.sort {
+            Some((c, SortDirection::Ascending)) if c == column => {
+                Some((column, SortDirection::Descending))
+            }
+            Some((c, SortDirection::Descending)) if c == column => None,
+            _ => Some((column, SortDirection::Ascending)),
+        };
+        self.apply_sort(results);
+    }
+
+    // Follow the results after a batch was appended and `dropped` results were
+    // removed from the front: new attributes become columns, and without a
+    // sort order the selection stays on the same result
+    pub fn update(&mut self, results: &[Value], dropped: usize) {
+        for column in ResultTable::new(results).columns {
+            if !self.columns.iter().any(|c| c.name == column.name) {
+                self.columns.push(column);
+            }
+        }
+        self.apply_sort(results);
+        if self.sort.is_none() {
+            self.selected_row = self.selected_row.saturating_sub(dropped);
+        }
+        self.selected_row = self.selected_row.min(self.order.len().saturating_sub(1));
+    }
+
+    fn apply_sort(&mut self, results: &[Value]) {
+        self.order = (0..results.len()).collect();
+        if let Some((column, direction)) = self.sort {
+            let name = &self.columns[column].name;
+            // Stable, so equal values keep the server order
+            self.order.sort_by(|&a, &b| {
+                let ordering =
+                    compare_values(cell_value(&results[a], name), cell_value(&results[b], name));
+                match direction {
+                    SortDirection::Ascending => ordering,
+                    SortDirection::Descending => ordering.reverse(),
+                }
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn column_names(table: &ResultTable) -> Vec<&str> {
+        table.columns.iter().map(|c| c.name.as_str()).collect()
+    }
+
+    #[test]
+    fn columns() {
+        let results = [
+            json!({ "b": 1, "a": "x\ny" }),
+            json!(42),
+            json!({ "c": [1] }),
+        ];
+        let table = ResultTable::new(&results);
+        assert_eq!(column_names(&table), [VALUE_COLUMN, "b", "a", "c"]);
+        assert_eq!(cell_text(&results[0], "a"), "x y");
+        assert_eq!(cell_text(&results[1], VALUE_COLUMN), "42");
+        assert_eq!(cell_text(&results[2], "c"), "[1]");
+        assert_eq!(cell_text(&results[2], "a"), "");
+    }
+
+    #[test]
+    fn sort_cycles_through_directions() {
+        let results = [
+            json!({ "n": 2 }),
+            json!({ "n": "a" }),
+            json!({}),
+            json!({ "n": 1 }),
+            json!({ "n": null }),
+        ];
+        let mut table = ResultTable::new(&results);
+        table.toggle_sort(&results);
+        assert_eq!(table.sort, Some((0, SortDirection::Ascending)));
+        // Missing sorts like null, and equal values keep the server order
+        assert_eq!(table.order, [2, 4, 3, 0, 1]);
+        table.toggle_sort(&results);
+        assert_eq!(table.sort, Some((0, SortDirection::Descending)));
+        assert_eq!(table.order, [1, 0, 3, 2, 4]);
+        table.toggle_sort(&results);
+        assert_eq!(table.sort, None);
+        assert_eq!(table.order, [0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn update_after_a_batch() {
+        let mut results = vec![json!({ "a": 3 }), json!({ "a": 1 }), json!({ "a": 2 })];
+        let mut table = ResultTable::new(&results);
+        table.selected_row = 2;
+
+        // Two results dropped from the front, two appended with a new attribute
+        results.drain(..2);
+        results.extend([json!({ "a": 0, "b": true }), json!({ "a": 5 })]);
+        table.update(&results, 2);
+        assert_eq!(column_names(&table), ["a", "b"]);
+        assert_eq!(table.order, [0, 1, 2]);
+        assert_eq!(table.selected_row, 0);
+        assert_eq!(table.selected_result(), Some(0));
+
+        // With a sort order the rows are sorted again, the selection stays in range
+        table.toggle_sort(&results);
+        table.selected_row = 2;
+        results.drain(..2);
+        table.update(&results, 2);
+        assert_eq!(table.order, [0]);
+        assert_eq!(table.selected_row, 0);
+    }
+
+    #[test]
+    fn hiding_and_moving_columns() {
+        let results = [json!({ "a": 1, "b": 2, "c": 3 })];
+        let mut table = ResultTable::new(&results);
+        table.move_columns(1);
+        table.hide_column();
+        assert_eq!(table.visible_columns(), [0, 2]);
+        assert_eq!(table.selected_column, 2);
+        table.move_columns(-1);
+        assert_eq!(table.selected_column, 0);
+        table.hide_column();
+        assert_eq!(table.visible_columns(), [2]);
+        // The last visible column stays
+        table.hide_column();
+        assert_eq!(table.visible_columns(), [2]);
+        table.show_all_columns();
+        assert_eq!(table.visible_columns(), [0, 1, 2]);
+
+        table.resize_column(100);
+        assert_eq!(table.columns[2].width, MAX_COLUMN_WIDTH);
+        table.resize_column(-100);
+        assert_eq!(table.columns[2].width, MIN_COLUMN_WIDTH);
+    }
+
+    #[test]
+    fn aql_sort_order() {
+        let ordered = [
+            json!(null),
+            json!(false),
+            json!(true),
+            json!(-1),
+            json!(2.5),
+            json!(""),
+            json!("b"),
+            json!([]),
+            json!([1]),
+            json!([1, 0]),
+            json!([2]),
+            json!({}),
+        ];
+        for pair in ordered.windows(2) {
+            assert_eq!(
+                compare_values(Some(&pair[0]), Some(&pair[1])),
+                Ordering::Less,
+                "{} < {}",
+                pair[0],
+                pair[1]
+            );
+        }
+        assert_eq!(compare_values(None, Some(&json!(null))), Ordering::Equal);
+    }
+}
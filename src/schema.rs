@@ -0,0 +1,562 @@
+// Local validation of documents against a collection schema.
+//
+// ArangoDB stores a collection schema as
+//   { "rule": <JSON Schema>, "level": "strict", "message": "..." }
+// and validates the rule with a JSON Schema (draft-04 style) validator. This
+// module implements the subset of keywords that is commonly used in rules, so
+// a schema can be tried against existing documents before it is applied.
+//
+// Like the server, the system attributes `_key`, `_id`, `_rev`, `_from` and
+// `_to` are ignored at the top level of a document.
+
+use anyhow::{Result, bail};
+use regex::Regex;
+use serde_json::Value;
+
+const SYSTEM_ATTRIBUTES: [&str; 5] = ["_key", "_id", "_rev", "_from", "_to"];
+
+const KNOWN_LEVELS: [&str; 4] = ["none", "new", "moderate", "strict"];
+
+// A validation failure, located by the path of the offending value
+#[derive(Debug, Clone, PartialEq)]
+pub struct SchemaViolation {
+    pub path: String,
+    pub message: String,
+}
+
+// Check the shape of a collection schema and return its rule
+pub fn schema_rule(schema: &Value) -> Result<&Value> {
+    let Some(object) = schema.as_object() else {
+        bail!("The schema must be an object with \"rule\", \"level\" and \"message\"");
+    };
+    let Some(rule) = object.get("rule") else {
+        bail!("The schema has no \"rule\"");
+    };
+    if !rule.is_object() {
+        bail!("\"rule\" must be a JSON Schema object");
+    }
+    if let Some(level) = object.get("level")
+        && !level.as_str().is_some_and(|l| KNOWN_LEVELS.contains(&l))
+    {
+        bail!("\"level\" must be one of {}", KNOWN_LEVELS.join(", "));
+    }
+    if let Some(message) = object.get("message")
+        && !message.is_string()
+    {
+        bail!("\"message\" must be a string");
+    }
+    Ok(rule)
+}
+
+// Validate a document against a schema rule, returning all violations
+pub fn validate_document(rule: &Value, document: &Value) -> Vec<SchemaViolation> {
+    let document = match document {
+        Value::Object(object) => Value::Object(
+            object
+                .iter()
+                .filter(|(name, _)| !SYSTEM_ATTRIBUTES.contains(&name.as_str()))
+                .map(|(name, value)| (name.clone(), value.clone()))
+                .collect(),
+        ),
+        other => other.clone(),
+    };
+
+    let mut violations = Vec::new();
+    validate(rule, &document, "", &mut violations);
+    violations
+}
+
+fn violation(violations: &mut Vec<SchemaViolation>, path: &str, message: String) {
+    violations.push(SchemaViolation {
+        path: if path.is_empty() {
+            "(document)".to_string()
+        } else {
+            path.to_string()
+        },
+        message,
+    });
+}
+
+fn child_path(path: &str, name: &str) -> String {
+    if path.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}.{}", path, name)
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(n) if n.is_i64() || n.is_u64() => "integer",
+        Value::Number(n) if n.as_f64().is_some_and(|f| f.fract() == 0.0) => "integer",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn matches_type(value: &Value, expected: &str) -> bool {
+    let actual = type_name(value);
+    actual == expected || (expected == "number" && actual == "integer")
+}
+
+fn is_valid(rule: &Value, value: &Value) -> bool {
+    let mut violations = Vec::new();
+    validate(rule, value, "", &mut violations);
+    violations.is_empty()
+}
+
+fn validate(rule: &Value, value: &Value, path: &str, violations: &mut Vec<SchemaViolation>) {
+    let rule = match rule {
+        Value::Bool(true) => return,
+        Value::Bool(false) => {
+            violation(violations, path, "is not allowed".to_string());
+            return;
+        }
+        Value::Object(rule) => rule,
+        _ => return,
+    };
+
+    if let Some(expected) = rule.get("type") {
+        let allowed: Vec<&str> = match expected {
+            Value::String(t) => vec![t.as_str()],
+            Value::Array(types) => types.iter().filter_map(|t| t.as_str()).collect(),
+            _ => Vec::new(),
+        };
+        if !allowed.is_empty() && !allowed.iter().any(|t| matches_type(value, t)) {
+            violation(
+                violations,
+                path,
+                format!(
+                    "expected {}, found {}",
+                    allowed.join(" or "),
+                    type_name(value)
+                ),
+            );
+            // The remaining keywords would only repeat the type mismatch
+            return;
+        }
+    }
+
+    if let Some(Value::Array(options)) = rule.get("enum")
+        && !options.contains(value)
+    {
+        violation(
+            violations,
+            path,
+            format!("{} is not one of {}", value, Value::from(options.clone())),
+        );
+    }
+    if let Some(constant) = rule.get("const")
+        && constant != value
+    {
+        violation(violations, path, format!("must be {}", constant));
+    }
+
+    for (keyword, combine) in [("allOf", "all"), ("anyOf", "any"), ("oneOf", "exactly one")] {
+        let Some(Value::Array(subschemas)) = rule.get(keyword) else {
+            continue;
+        };
+        let matching = subschemas.iter().filter(|s| is_valid(s, value)).count();
+        let ok = match keyword {
+            "allOf" => matching == subschemas.len(),
+            "anyOf" => matching > 0,
+            _ => matching == 1,
+        };
+        if !ok {
+            violation(
+                violations,
+                path,
+                format!(
+                    "must match {} of the {} schemas ({} matched)",
+                    combine, keyword, matching
+                ),
+            );
+        }
+    }
+    if let Some(not) = rule.get("not")
+        && is_valid(not, value)
+    {
+        violation(
+            violations,
+            path,
+            "must not match the \"not\" schema".to_string(),
+        );
+    }
+
+    match value {
+        Value::Object(object) => validate_object(rule, object, path, violations),
+        Value::Array(items) => validate_array(rule, items, path, violations),
+        Value::String(s) => validate_string(rule, s, path, violations),
+        Value::Number(n) => validate_number(rule, n.as_f64().unwrap_or_default(), path, violations),
+        _ => {}
+    }
+}
+
+fn validate_object(
+    rule: &serde_json::Map<String, Value>,
+    object: &serde_json::Map<String, Value>,
+    path: &str,
+    violations: &mut Vec<SchemaViolation>,
+) {
+    if let Some(Value::Array(required)) = rule.get("required") {
+        for name in required.iter().filter_map(|r| r.as_str()) {
+            if !object.contains_key(name) {
+                violation(
+                    violations,
+                    &child_path(path, name),
+                    "is required".to_string(),
+                );
+            }
+        }
+    }
+
+    let properties = rule.get("properties").and_then(|p| p.as_object());
+    let patterns: Vec<(Regex, &Value)> = rule
+        .get("patternProperties")
+        .and_then(|p| p.as_object())
+        .map(|p| {
+            p.iter()
+                .filter_map(|(pattern, schema)| Some((Regex::new(pattern).ok()?, schema)))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    for (name, value) in object {
+        let mut matched = false;
+        if let Some(schema) = properties.and_then(|p| p.get(name)) {
+            validate(schema, value, &child_path(path, name), violations);
+            matched = true;
+        }
+        for (regex, schema) in &patterns {
+            if regex.is_match(name) {
+                validate(schema, value, &child_path(path, name), violations);
+                matched = true;
+            }
+        }
+        if !matched {
+            match rule.get("additionalProperties") {
+                Some(Value::Bool(false)) => violation(
+                    violations,
+                    &child_path(path, name),
+                    "is not an allowed attribute".to_string(),
+                ),
+                Some(schema @ Value::Object(_)) => {
+                    validate(schema, value, &child_path(path, name), violations)
+                }
+                _ => {}
+            }
+        }
+    }
+
+    if let Some(min) = rule.get("minProperties").and_then(|m| m.as_u64())
+        && (object.len() as u64) < min
+    {
+        violation(
+            violations,
+            path,
+            format!("must have at least {} attributes", min),
+        );
+    }
+    if let Some(max) = rule.get("maxProperties").and_then(|m| m.as_u64())
+        && (object.len() as u64) > max
+    {
+        violation(
+            violations,
+            path,
+            format!("must have at most {} attributes", max),
+        );
+    }
+}
+
+fn validate_array(
+    rule: &serde_json::Map<String, Value>,
+    items: &[Value],
+    path: &str,
+    violations: &mut Vec<SchemaViolation>,
+) {
+    match rule.get("items") {
+        Some(Value::Array(schemas)) => {
+            // Tuple validation
+            for (i, (schema, item)) in schemas.iter().zip(items).enumerate() {
+                validate(schema, item, &format!("{}[{}]", path, i), violations);
+            }
+            if let Some(Value::Bool(false)) = rule.get("additionalItems")
+                && items.len() > schemas.len()
+            {
+                violation(
+                    violations,
+                    path,
+                    format!("must have at most {} items", schemas.len()),
+                );
+            }
+        }
+        Some(schema) => {
+            for (i, item) in items.iter().enumerate() {
+                validate(schema, item, &format!("{}[{}]", path, i), violations);
+            }
+        }
+        None => {}
+    }
+
+    if let Some(min) = rule.get("minItems").and_then(|m| m.as_u64())
+        && (items.len() as u64) < min
+    {
+        violation(
+            violations,
+            path,
+            format!("must have at least {} items", min),
+        );
+    }
+    if let Some(max) = rule.get("maxItems").and_then(|m| m.as_u64())
+        && (items.len() as u64) > max
+    {
+        violation(violations, path, format!("must have at most {} items", max));
+    }
+    if rule.get("uniqueItems") == Some(&Value::Bool(true))
+        && items
+            .iter()
+            .enumerate()
+            .any(|(i, item)| items[..i].contains(item))
+    {
+        violation(violations, path, "items must be unique".to_string());
+    }
+    if let Some(contains) = rule.get("contains")
+        && !items.iter().any(|item| is_valid(contains, item))
+    {
+        violation(
+            violations,
+            path,
+            "must contain an item matching \"contains\"".to_string(),
+        );
+    }
+}
+
+fn validate_string(
+    rule: &serde_json::Map<String, Value>,
+    s: &str,
+    path: &str,
+    violations: &mut Vec<SchemaViolation>,
+) {
+    let length = s.chars().count() as u64;
+    if let Some(min) = rule.get("minLength").and_then(|m| m.as_u64())
+        && length < min
+    {
+        violation(
+            violations,
+            path,
+            format!("must be at least {} characters long", min),
+        );
+    }
+    if let Some(max) = rule.get("maxLength").and_then(|m| m.as_u64())
+        && length > max
+    {
+        violation(
+            violations,
+            path,
+            format!("must be at most {} characters long", max),
+        );
+    }
+    if let Some(pattern) = rule.get("pattern").and_then(|p| p.as_str()) {
+        match Regex::new(pattern) {
+            Ok(regex) if !regex.is_match(s) => violation(
+                violations,
+                path,
+                format!("must match the pattern {}", pattern),
+            ),
+            Ok(_) => {}
+            Err(_) => violation(violations, path, format!("invalid pattern {}", pattern)),
+        }
+    }
+}
+
+fn validate_number(
+    rule: &serde_json::Map<String, Value>,
+    n: f64,
+    path: &str,
+    violations: &mut Vec<SchemaViolation>,
+) {
+    // Draft-04 uses boolean exclusiveMinimum/exclusiveMaximum, later drafts numbers
+    let exclusive_min = rule.get("exclusiveMinimum");
+    let exclusive_max = rule.get("exclusiveMaximum");
+
+    if let Some(min) = rule.get("minimum").and_then(|m| m.as_f64()) {
+        if exclusive_min == Some(&Value::Bool(true)) {
+            if n <= min {
+                violation(violations, path, format!("must be greater than {}", min));
+            }
+        } else if n < min {
+            violation(violations, path, format!("must be at least {}", min));
+        }
+    }
+    if let Some(max) = rule.get("maximum").and_then(|m| m.as_f64()) {
+        if exclusive_max == Some(&Value::Bool(true)) {
+            if n >= max {
+                violation(violations, path, format!("must be less than {}", max));
+            }
+        } else if n > max {
+            violation(violations, path, format!("must be at most {}", max));
+        }
+    }
+    if let Some(min) = exclusive_min.and_then(|m| m.as_f64())
+        && n <= min
+    {
+        violation(violations, path, format!("must be greater than {}", min));
+    }
+    if let Some(max) = exclusive_max.and_then(|m| m.as_f64())
+        && n >= max
+    {
+        violation(violations, path, format!("must be less than {}", max));
+    }
+    if let Some(divisor) = rule.get("multipleOf").and_then(|m| m.as_f64())
+        && divisor > 0.0
+        && ((n / divisor) - (n / divisor).round()).abs() > 1e-9
+    {
+        violation(
+            violations,
+            path,
+            format!("must be a multiple of {}", divisor),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn messages(rule: Value, document: Value) -> Vec<String> {
+        validate_document(&rule, &document)
+            .into_iter()
+            .map(|v| format!("{}: {}", v.path, v.message))
+            .collect()
+    }
+
+    #[test]
+    fn schema_shape() {
+        let schema = json!({ "rule": { "type": "object" }, "level": "moderate", "message": "x" });
+        assert_eq!(schema_rule(&schema).unwrap(), &json!({ "type": "object" }));
+        assert!(schema_rule(&json!([])).is_err());
+        assert!(schema_rule(&json!({ "level": "strict" })).is_err());
+        assert!(schema_rule(&json!({ "rule": true })).is_err());
+        assert!(schema_rule(&json!({ "rule": {}, "level": "loose" })).is_err());
+        assert!(schema_rule(&json!({ "rule": {}, "message": 1 })).is_err());
+    }
+
+    #[test]
+    fn types() {
+        let rule = json!({
+            "properties": {
+                "age": { "type": "integer" },
+                "score": { "type": "number" },
+                "nickname": { "type": ["string", "null"] }
+            }
+        });
+        assert!(
+            messages(
+                rule.clone(),
+                json!({ "age": 3.0, "score": 1, "nickname": null })
+            )
+            .is_empty()
+        );
+        assert_eq!(
+            messages(rule, json!({ "age": 3.5, "score": "1", "nickname": 2 })),
+            [
+                "age: expected integer, found number",
+                "score: expected number, found string",
+                "nickname: expected string or null, found integer"
+            ]
+        );
+        assert_eq!(
+            messages(json!({ "type": "object" }), json!([])),
+            ["(document): expected object, found array"]
+        );
+    }
+
+    #[test]
+    fn system_attributes_are_ignored() {
+        let rule = json!({ "properties": { "name": {} }, "additionalProperties": false });
+        let document =
+            json!({ "_key": "1", "_id": "c/1", "_rev": "x", "name": "a", "nested": { "_key": 1 } });
+        assert_eq!(
+            messages(rule, document),
+            ["nested: is not an allowed attribute"]
+        );
+    }
+
+    #[test]
+    fn combinators() {
+        let rule = json!({
+            "properties": {
+                "id": { "anyOf": [{ "type": "string" }, { "type": "integer" }] },
+                "value": { "oneOf": [{ "type": "number" }, { "minimum": 0 }] },
+                "tag": { "not": { "enum": ["deleted"] } }
+            }
+        });
+        assert!(messages(rule.clone(), json!({ "id": 1, "value": -1, "tag": "new" })).is_empty());
+        assert_eq!(
+            messages(rule, json!({ "id": true, "value": 1, "tag": "deleted" })),
+            [
+                "id: must match any of the anyOf schemas (0 matched)",
+                "value: must match exactly one of the oneOf schemas (2 matched)",
+                "tag: must not match the \"not\" schema"
+            ]
+        );
+    }
+
+    #[test]
+    fn strings_numbers_and_arrays() {
+        let rule = json!({
+            "required": ["code"],
+            "properties": {
+                "name": { "pattern": "^[A-Z]", "minLength": 2 },
+                "count": { "minimum": 0, "exclusiveMaximum": 10, "multipleOf": 2 },
+                "tags": { "items": { "type": "string" }, "uniqueItems": true, "maxItems": 3 }
+            }
+        });
+        assert!(
+            messages(
+                rule.clone(),
+                json!({ "code": 1, "name": "Ab", "count": 4, "tags": ["a"] })
+            )
+            .is_empty()
+        );
+        assert_eq!(
+            messages(
+                rule,
+                json!({ "name": "a", "count": 10, "tags": ["a", 1, "a", "b"] })
+            ),
+            [
+                "code: is required",
+                "name: must be at least 2 characters long",
+                "name: must match the pattern ^[A-Z]",
+                "count: must be less than 10",
+                "tags[1]: expected string, found integer",
+                "tags: must have at most 3 items",
+                "tags: items must be unique"
+            ]
+        );
+        assert_eq!(
+            messages(json!({ "pattern": "(" }), json!("x")),
+            ["(document): invalid pattern ("]
+        );
+    }
+
+    #[test]
+    fn pattern_and_additional_properties() {
+        let rule = json!({
+            "patternProperties": { "^x-": { "type": "string" } },
+            "additionalProperties": { "type": "integer" }
+        });
+        assert_eq!(
+            messages(rule, json!({ "x-a": "s", "x-b": 1, "n": 2, "m": "3" })),
+            [
+                "x-b: expected string, found integer",
+                "m: expected integer, found string"
+            ]
+        );
+    }
+}